@@ -0,0 +1,64 @@
+//! Timings of drawing point-heavy workouts, run with `cargo bench --bench visualizer`.
+//! A ride turned into a workout has one effort per second, far more than there are pixels,
+//! so drawing it should cost about as much as drawing a workout with one effort per pixel.
+use mrc_workout_creator::chart::decimation::decimate;
+use mrc_workout_creator::chart::export::{workout_to_svg, ChartOptions};
+use mrc_workout_creator::workout_data::effort::Effort;
+use mrc_workout_creator::workout_data::workout::Workout;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 50;
+
+/// A workout derived from a ride of `seconds` seconds.
+fn ride(seconds: usize) -> Workout {
+    Workout::new(
+        "Ride",
+        "",
+        (0..seconds)
+            .map(|second| Effort::new(1.0 / 60.0, 150.0 + (second % 90) as f64, None))
+            .collect(),
+    )
+}
+
+/// Intervals of a minute each, as many as fit the chart.
+fn intervals(minutes: usize) -> Workout {
+    Workout::new(
+        "Intervals",
+        "",
+        (0..minutes)
+            .map(|minute| Effort::new(1.0, 100.0 + (minute % 5) as f64 * 50.0, None))
+            .collect(),
+    )
+}
+
+fn bench(name: &str, mut run: impl FnMut()) -> Duration {
+    run();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        run();
+    }
+    let duration = start.elapsed() / ITERATIONS;
+    println!("{:<45} {:>12.3?}", name, duration);
+    duration
+}
+
+fn main() {
+    let options = ChartOptions::default();
+    let four_hour_ride = ride(4 * 3600);
+    let intervals = intervals(380);
+
+    bench("decimate 14400 efforts to 380", || {
+        black_box(decimate(black_box(four_hour_ride.efforts()), 380));
+    });
+    let ride_svg = bench("svg of a 4 hour ride, 14400 efforts", || {
+        black_box(workout_to_svg(black_box(&four_hour_ride), &options));
+    });
+    let intervals_svg = bench("svg of 380 intervals", || {
+        black_box(workout_to_svg(black_box(&intervals), &options));
+    });
+    println!(
+        "drawing the ride costs {:.1} times as much as drawing the intervals",
+        ride_svg.as_secs_f64() / intervals_svg.as_secs_f64()
+    );
+}
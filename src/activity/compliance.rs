@@ -0,0 +1,272 @@
+use super::Recording;
+use crate::workout_data::workout::Workout;
+use std::ops::Range;
+
+/// Share of the target the power may deviate and still count as on target.
+pub const DEFAULT_TOLERANCE: f64 = 0.05;
+/// Window of the rolling average used for normalized power.
+const NORMALIZED_POWER_WINDOW_IN_SECONDS: usize = 30;
+
+/// How well one effort of a workout was ridden.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntervalCompliance {
+    pub effort_index: usize,
+    pub average_target: f64,
+    /// `None` if no power was recorded during the effort.
+    pub average_power: Option<f64>,
+    /// Share of the effort's seconds with power within the tolerance of the target.
+    pub time_in_range: f64,
+    pub normalized_power: Option<f64>,
+}
+
+/// The seconds of every effort, rounded from where it starts and ends in the workout,
+/// so efforts of fractional seconds neither overlap nor leave gaps.
+/// Efforts with a negative duration, as typed into the designer, last no seconds.
+fn seconds_of_efforts(workout: &Workout) -> Vec<Range<usize>> {
+    let to_second = |minute: f64| (minute * 60.0).round() as usize;
+    let mut ending_minute = 0.0;
+    workout
+        .efforts()
+        .iter()
+        .map(|effort| {
+            let starting_minute = ending_minute;
+            ending_minute += effort.duration_in_minutes().max(0.0);
+            to_second(starting_minute)..to_second(ending_minute)
+        })
+        .collect()
+}
+
+/// The target wattage of every second of the workout.
+pub fn targets_per_second(workout: &Workout) -> Vec<f64> {
+    seconds_of_efforts(workout)
+        .into_iter()
+        .zip(workout.efforts())
+        .flat_map(|(seconds, effort)| {
+            let seconds = seconds.len();
+            (0..seconds).map(move |second| {
+                effort.starting_value()
+                    + (effort.ending_value() - effort.starting_value()) * second as f64
+                        / seconds as f64
+            })
+        })
+        .collect()
+}
+
+/// Seconds the ride started before the workout, found by
+/// the smallest squared difference between power and target.
+/// Seconds without power are not compared.
+pub fn best_offset(workout: &Workout, recording: &Recording, maximal_offset: usize) -> usize {
+    let targets = targets_per_second(workout);
+    let squared_error = |offset: usize| -> f64 {
+        let (error, compared) = recording
+            .samples()
+            .iter()
+            .skip(offset)
+            .zip(&targets)
+            .filter_map(|(sample, target)| sample.power_in_watts.map(|power| (power, target)))
+            .fold((0.0, 0), |(error, compared), (power, target)| {
+                (error + (f64::from(power) - target).powi(2), compared + 1)
+            });
+        if compared == 0 {
+            f64::INFINITY
+        } else {
+            error / f64::from(compared)
+        }
+    };
+    (0..=maximal_offset.min(recording.samples().len()))
+        .map(|offset| (offset, squared_error(offset)))
+        .min_by(|(_, first), (_, second)| first.total_cmp(second))
+        .map_or(0, |(offset, _)| offset)
+}
+
+/// Compare every effort of the workout with the ride, where the
+/// workout started `offset` seconds into the ride.
+pub fn analyze(
+    workout: &Workout,
+    recording: &Recording,
+    offset: usize,
+    tolerance: f64,
+) -> Vec<IntervalCompliance> {
+    let targets = targets_per_second(workout);
+    let powers: Vec<Option<f64>> = recording
+        .samples()
+        .iter()
+        .skip(offset)
+        .map(|sample| sample.power_in_watts.map(f64::from))
+        .collect();
+
+    seconds_of_efforts(workout)
+        .into_iter()
+        .enumerate()
+        .map(|(effort_index, seconds)| {
+            let effort_targets = &targets[seconds.clone()];
+            let effort_powers = powers
+                .get(seconds.start..seconds.end.min(powers.len()))
+                .unwrap_or_default();
+
+            let seconds_in_range = effort_powers
+                .iter()
+                .zip(effort_targets)
+                .filter(|(power, target)| {
+                    power.is_some_and(|power| (power - *target).abs() <= tolerance * *target)
+                })
+                .count();
+            IntervalCompliance {
+                effort_index,
+                average_target: mean(effort_targets.iter().copied()).unwrap_or_default(),
+                average_power: mean(effort_powers.iter().flatten().copied()),
+                time_in_range: if effort_targets.is_empty() {
+                    0.0
+                } else {
+                    seconds_in_range as f64 / effort_targets.len() as f64
+                },
+                normalized_power: normalized_power(effort_powers),
+            }
+        })
+        .collect()
+}
+
+/// The fourth root of the mean of the fourth power of the 30 second rolling average.
+/// Efforts shorter than the window use a single window over all of it.
+/// Seconds without power count as zero watts.
+pub fn normalized_power(powers: &[Option<f64>]) -> Option<f64> {
+    if powers.iter().all(Option::is_none) {
+        return None;
+    }
+    let powers: Vec<f64> = powers.iter().map(|power| power.unwrap_or(0.0)).collect();
+    let window = NORMALIZED_POWER_WINDOW_IN_SECONDS.min(powers.len());
+    let rolling_averages = powers
+        .windows(window)
+        .map(|window| window.iter().sum::<f64>() / window.len() as f64);
+    mean(rolling_averages.map(|average| average.powi(4))).map(|mean| mean.powf(0.25))
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+    if count == 0 {
+        None
+    } else {
+        Some(sum / f64::from(count))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::activity::Sample;
+    use crate::workout_data::effort::Effort;
+    use crate::workout_data::test_workout;
+
+    fn ride(powers: impl Iterator<Item = u16>) -> Recording {
+        let mut recording = Recording::new(0);
+        for power in powers {
+            recording.record(Sample {
+                power_in_watts: Some(power),
+                ..Sample::default()
+            });
+        }
+        recording
+    }
+
+    #[test]
+    fn ramp_targets() {
+        let ramp = Workout::new("", "", vec![Effort::new(0.1, 100.0, Some(160.0))]);
+        assert_eq!(
+            targets_per_second(&ramp),
+            vec![100.0, 110.0, 120.0, 130.0, 140.0, 150.0]
+        );
+    }
+    #[test]
+    fn fractional_seconds_match_analyzed_efforts() {
+        let workout = Workout::new(
+            "",
+            "",
+            vec![
+                Effort::new(0.5 / 60.0, 100.0, None),
+                Effort::new(1.0 / 60.0, 200.0, None),
+                Effort::new(0.5 / 60.0, 300.0, None),
+            ],
+        );
+        assert_eq!(targets_per_second(&workout), vec![100.0, 200.0]);
+        let compliance = analyze(&workout, &ride([100, 200].into_iter()), 0, 0.0);
+        assert_eq!(compliance[0].average_target, 100.0);
+        assert_eq!(compliance[1].average_target, 200.0);
+        assert_eq!(compliance[1].time_in_range, 1.0);
+        assert_eq!(compliance[2].average_target, 0.0);
+    }
+    #[test]
+    fn effort_with_negative_duration_lasts_no_seconds() {
+        let workout = Workout::new(
+            "",
+            "",
+            vec![
+                Effort::new(5.0 / 60.0, 100.0, None),
+                Effort::new(-2.0 / 60.0, 200.0, None),
+                Effort::new(3.0 / 60.0, 300.0, None),
+            ],
+        );
+        assert_eq!(
+            targets_per_second(&workout),
+            vec![100.0, 100.0, 100.0, 100.0, 100.0, 300.0, 300.0, 300.0]
+        );
+        let compliance = analyze(&workout, &ride([100; 8].into_iter()), 0, 0.0);
+        assert_eq!(compliance.len(), 3);
+        assert_eq!(compliance[1].average_target, 0.0);
+        assert_eq!(compliance[1].time_in_range, 0.0);
+        assert_eq!(compliance[2].average_target, 300.0);
+    }
+    #[test]
+    fn perfect_ride() {
+        let workout = test_workout();
+        let targets = targets_per_second(&workout);
+        let recording = ride(targets.iter().map(|target| target.round() as u16));
+        let compliance = analyze(&workout, &recording, 0, DEFAULT_TOLERANCE);
+
+        assert_eq!(compliance.len(), 5);
+        assert_eq!(compliance[1].average_target, 300.0);
+        assert_eq!(compliance[1].average_power, Some(300.0));
+        assert_eq!(compliance[1].time_in_range, 1.0);
+        assert!((compliance[1].normalized_power.unwrap() - 300.0).abs() < 1e-9);
+    }
+    #[test]
+    fn ride_ended_early() {
+        let workout = test_workout();
+        let targets = targets_per_second(&workout);
+        let recording = ride(
+            targets[..600]
+                .iter()
+                .map(|target| target.round() as u16)
+                .chain([280; 150]),
+        );
+        let compliance = analyze(&workout, &recording, 0, DEFAULT_TOLERANCE);
+
+        assert_eq!(compliance[1].average_power, Some(280.0));
+        assert_eq!(compliance[1].time_in_range, 0.0);
+        assert_eq!(analyze(&workout, &recording, 0, 0.1)[1].time_in_range, 0.5);
+    }
+    #[test]
+    fn offset_of_late_start() {
+        let workout = test_workout();
+        let targets = targets_per_second(&workout);
+        let recording = ride(
+            [300; 30]
+                .into_iter()
+                .chain(targets.iter().map(|target| target.round() as u16)),
+        );
+        assert_eq!(best_offset(&workout, &recording, 60), 30);
+        assert_eq!(
+            analyze(&workout, &recording, 30, DEFAULT_TOLERANCE)[0].time_in_range,
+            1.0
+        );
+    }
+    #[test]
+    fn normalized_power_weighs_surges() {
+        let steady = normalized_power(&vec![Some(200.0); 60]).unwrap();
+        let surges: Vec<Option<f64>> = (0..60)
+            .map(|second| Some(if second < 30 { 100.0 } else { 300.0 }))
+            .collect();
+        assert!((steady - 200.0).abs() < 1e-9);
+        assert!(normalized_power(&surges).unwrap() > 200.0);
+        assert_eq!(normalized_power(&[None, None]), None);
+    }
+}
@@ -0,0 +1,98 @@
+use super::{ImportError, Recording, Sample};
+
+/// Header names understood for each column, compared case-insensitively.
+const TIME_COLUMNS: [&str; 4] = ["time", "seconds", "secs", "elapsed"];
+const POWER_COLUMNS: [&str; 3] = ["power", "watts", "power_in_watts"];
+const CADENCE_COLUMNS: [&str; 3] = ["cadence", "cad", "rpm"];
+const HEART_RATE_COLUMNS: [&str; 4] = ["heart_rate", "heartrate", "hr", "bpm"];
+
+/// Read a power stream from a CSV file with a header row.
+/// A power column is required, without a time column one row per second is assumed.
+/// Columns may be separated by commas or semicolons.
+pub fn csv_to_recording(csv: &str) -> Result<Recording, ImportError> {
+    let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+    let header = lines.next().ok_or(ImportError::InvalidCsv)?;
+    let separator = if header.contains(';') { ';' } else { ',' };
+    let column_names: Vec<String> = header
+        .split(separator)
+        .map(|name| name.trim().trim_matches('"').to_lowercase())
+        .collect();
+    let find_column = |names: &[&str]| {
+        column_names
+            .iter()
+            .position(|column_name| names.contains(&column_name.as_str()))
+    };
+    let power_column = find_column(&POWER_COLUMNS).ok_or(ImportError::InvalidCsv)?;
+    let time_column = find_column(&TIME_COLUMNS);
+    let cadence_column = find_column(&CADENCE_COLUMNS);
+    let heart_rate_column = find_column(&HEART_RATE_COLUMNS);
+
+    let timed_samples = lines
+        .enumerate()
+        .map(|(row, line)| {
+            let values: Vec<&str> = line.split(separator).map(str::trim).collect();
+            let number = |column: Option<usize>| -> Option<f64> {
+                column
+                    .and_then(|column| values.get(column))
+                    .filter(|value| !value.is_empty())
+                    .and_then(|value| value.parse().ok())
+            };
+            let time = match time_column {
+                Some(_) => number(time_column).ok_or(ImportError::InvalidCsv)?,
+                None => row as f64,
+            };
+            Ok((
+                time.round().max(0.0) as u64,
+                Sample {
+                    power_in_watts: number(Some(power_column))
+                        .map(|power| power.round().clamp(0.0, f64::from(u16::MAX)) as u16),
+                    cadence_in_rpm: number(cadence_column)
+                        .map(|cadence| cadence.round().clamp(0.0, f64::from(u8::MAX)) as u8),
+                    heart_rate: number(heart_rate_column)
+                        .map(|heart_rate| heart_rate.round().clamp(0.0, f64::from(u8::MAX)) as u8),
+                    ..Sample::default()
+                },
+            ))
+        })
+        .collect::<Result<Vec<_>, ImportError>>()?;
+    Recording::from_timed_samples(timed_samples)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn powers(recording: &Recording) -> Vec<Option<u16>> {
+        recording
+            .samples()
+            .iter()
+            .map(|sample| sample.power_in_watts)
+            .collect()
+    }
+
+    #[test]
+    fn one_row_per_second_without_time() {
+        let recording = csv_to_recording("Watts\n100\n\n150\n").unwrap();
+        assert_eq!(powers(&recording), vec![Some(100), Some(150)]);
+    }
+    #[test]
+    fn rows_placed_by_time() {
+        let recording = csv_to_recording("time;power;hr\n10;100;120\n12;;121\n").unwrap();
+        assert_eq!(powers(&recording), vec![Some(100), None, None]);
+        assert_eq!(recording.samples()[2].heart_rate, Some(121));
+    }
+    #[test]
+    fn power_column_is_required() {
+        assert_eq!(
+            csv_to_recording("time,speed\n0,30\n"),
+            Err(ImportError::InvalidCsv)
+        );
+    }
+    #[test]
+    fn rows_need_a_time_if_there_is_a_time_column() {
+        assert_eq!(
+            csv_to_recording("time,power\n0,100\n,120\n"),
+            Err(ImportError::InvalidCsv)
+        );
+    }
+}
@@ -0,0 +1,988 @@
+use super::{summarize, ImportError, Recording, Sample, Summary};
+use crate::workout_data::effort::{effort_can_be_split, Effort, Section};
+use crate::workout_data::workout::Workout;
+
+/// FIT timestamps count seconds from 1989-12-31T00:00:00Z.
+const FIT_EPOCH_IN_UNIX_SECONDS: u64 = 631_065_600;
+const PROTOCOL_VERSION: u8 = 0x20;
+const PROFILE_VERSION: u16 = 2132;
+const HEADER_SIZE: u8 = 14;
+/// Manufacturer id reserved for development.
+const MANUFACTURER_DEVELOPMENT: u16 = 255;
+/// Identifies the developer fields written by this application.
+const APPLICATION_ID: [u8; 16] = *b"mrc-workout-crea";
+
+/// Global message numbers of the FIT profile.
+mod message {
+    pub const FILE_ID: u16 = 0;
+    pub const SESSION: u16 = 18;
+    pub const LAP: u16 = 19;
+    pub const RECORD: u16 = 20;
+    pub const EVENT: u16 = 21;
+    pub const WORKOUT: u16 = 26;
+    pub const WORKOUT_STEP: u16 = 27;
+    pub const ACTIVITY: u16 = 34;
+    pub const FIELD_DESCRIPTION: u16 = 206;
+    pub const DEVELOPER_DATA_ID: u16 = 207;
+}
+
+mod base_type {
+    pub const ENUM: u8 = 0x00;
+    pub const UINT8: u8 = 0x02;
+    pub const STRING: u8 = 0x07;
+    pub const BYTE: u8 = 0x0D;
+    pub const UINT16: u8 = 0x84;
+    pub const UINT32: u8 = 0x86;
+    pub const UINT32Z: u8 = 0x8C;
+}
+
+/// Values of FIT enums used in this file.
+mod value {
+    pub const FILE_ACTIVITY: u8 = 4;
+    pub const FILE_WORKOUT: u8 = 5;
+    pub const EVENT_TIMER: u8 = 0;
+    pub const EVENT_SESSION: u8 = 8;
+    pub const EVENT_LAP: u8 = 9;
+    pub const EVENT_ACTIVITY: u8 = 26;
+    pub const EVENT_TYPE_START: u8 = 0;
+    pub const EVENT_TYPE_STOP: u8 = 1;
+    pub const EVENT_TYPE_STOP_ALL: u8 = 4;
+    pub const SPORT_CYCLING: u8 = 2;
+    pub const SUB_SPORT_INDOOR_CYCLING: u8 = 6;
+    pub const LAP_TRIGGER_TIME: u8 = 1;
+    pub const ACTIVITY_MANUAL: u8 = 0;
+    pub const DURATION_TIME: u8 = 0;
+    /// Until the lap button is pressed.
+    pub const DURATION_OPEN: u8 = 5;
+    pub const DURATION_REPEAT_UNTIL_STEPS_COMPLETED: u8 = 6;
+    pub const TARGET_POWER: u8 = 4;
+    pub const INTENSITY_ACTIVE: u8 = 0;
+    pub const INTENSITY_WARMUP: u8 = 2;
+    pub const INTENSITY_COOLDOWN: u8 = 3;
+    /// Custom power targets above this are watts, below it percent of the FTP.
+    pub const POWER_IN_WATTS_OFFSET: u32 = 1000;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFitError {
+    /// More steps than FIT can count, ramps already split into steady steps.
+    TooManySteps(usize),
+}
+
+/// Render the recording as FIT activity file.
+/// The target wattage of every record is stored in a developer field.
+pub fn recording_to_fit(recording: &Recording) -> Vec<u8> {
+    let start = to_fit_time(recording.start_in_unix_seconds());
+    let end = start + recording.duration_in_seconds() as u32;
+    let timer_time = recording.duration_in_seconds() as u32 * 1000;
+    let mut writer = Writer::default();
+
+    writer.write(
+        0,
+        Message::new(message::FILE_ID)
+            .field(Field::enumeration(0, value::FILE_ACTIVITY))
+            .field(Field::uint16(1, Some(MANUFACTURER_DEVELOPMENT)))
+            .field(Field::uint16(2, Some(0)))
+            .field(Field::uint32z(3, 1))
+            .field(Field::uint32(4, Some(start))),
+    );
+    writer.write(
+        1,
+        Message::new(message::DEVELOPER_DATA_ID)
+            .field(Field::bytes(1, &APPLICATION_ID))
+            .field(Field::uint8(3, Some(0))),
+    );
+    writer.write(
+        2,
+        Message::new(message::FIELD_DESCRIPTION)
+            .field(Field::uint8(0, Some(0)))
+            .field(Field::uint8(1, Some(0)))
+            .field(Field::uint8(2, Some(base_type::UINT16)))
+            .field(Field::string(3, "target_power"))
+            .field(Field::string(8, "watts")),
+    );
+    writer.write(3, event(start, value::EVENT_TYPE_START));
+
+    for (second, sample) in recording.samples().iter().enumerate() {
+        writer.write(
+            4,
+            Message::new(message::RECORD)
+                .field(Field::uint32(253, Some(start + second as u32)))
+                .field(Field::uint8(3, sample.heart_rate))
+                .field(Field::uint8(4, sample.cadence_in_rpm))
+                .field(Field::uint16(7, sample.power_in_watts))
+                .developer_field(Field::uint16(0, sample.target_in_watts)),
+        );
+    }
+
+    writer.write(3, event(end, value::EVENT_TYPE_STOP_ALL));
+    let laps = recording.laps();
+    for (index, lap) in laps.iter().enumerate() {
+        let lap_start = start + lap.first_sample as u32;
+        let lap_time = lap.number_of_samples as u32 * 1000;
+        writer.write(
+            5,
+            with_summary(
+                Message::new(message::LAP)
+                    .field(Field::uint32(
+                        253,
+                        Some(lap_start + lap.number_of_samples as u32),
+                    ))
+                    .field(Field::uint16(254, Some(index as u16)))
+                    .field(Field::enumeration(0, value::EVENT_LAP))
+                    .field(Field::enumeration(1, value::EVENT_TYPE_STOP))
+                    .field(Field::uint32(2, Some(lap_start)))
+                    .field(Field::uint32(7, Some(lap_time)))
+                    .field(Field::uint32(8, Some(lap_time)))
+                    .field(Field::enumeration(24, value::LAP_TRIGGER_TIME)),
+                &summarize(recording.lap_samples(lap)),
+                LAP_SUMMARY_FIELDS,
+            ),
+        );
+    }
+    writer.write(
+        6,
+        with_summary(
+            Message::new(message::SESSION)
+                .field(Field::uint32(253, Some(end)))
+                .field(Field::uint16(254, Some(0)))
+                .field(Field::enumeration(0, value::EVENT_SESSION))
+                .field(Field::enumeration(1, value::EVENT_TYPE_STOP))
+                .field(Field::uint32(2, Some(start)))
+                .field(Field::enumeration(5, value::SPORT_CYCLING))
+                .field(Field::enumeration(6, value::SUB_SPORT_INDOOR_CYCLING))
+                .field(Field::uint32(7, Some(timer_time)))
+                .field(Field::uint32(8, Some(timer_time)))
+                .field(Field::uint16(25, Some(0)))
+                .field(Field::uint16(26, Some(laps.len() as u16))),
+            &summarize(recording.samples()),
+            SESSION_SUMMARY_FIELDS,
+        ),
+    );
+    writer.write(
+        7,
+        Message::new(message::ACTIVITY)
+            .field(Field::uint32(253, Some(end)))
+            .field(Field::uint32(0, Some(timer_time)))
+            .field(Field::uint16(1, Some(1)))
+            .field(Field::enumeration(2, value::ACTIVITY_MANUAL))
+            .field(Field::enumeration(3, value::EVENT_ACTIVITY))
+            .field(Field::enumeration(4, value::EVENT_TYPE_STOP))
+            .field(Field::uint32(5, Some(end))),
+    );
+
+    writer.finish()
+}
+
+/// Render the workout as FIT workout file, with one step per effort.
+/// The target range of an effort becomes the range of power of its step.
+/// FIT has no ramps, so they are split into steady steps.
+pub fn workout_to_fit(workout: &Workout) -> Result<Vec<u8>, ExportFitError> {
+    let steps: Vec<Effort> = workout
+        .efforts()
+        .iter()
+        .flat_map(
+            |effort| match effort.split_ramp_effort_into_constant_chunks() {
+                Ok(chunks) if effort_can_be_split(effort) => chunks,
+                _ => vec![effort.clone()],
+            },
+        )
+        .collect();
+    let too_many_steps = |_| ExportFitError::TooManySteps(steps.len());
+    let number_of_steps = u16::try_from(steps.len()).map_err(too_many_steps)?;
+    let mut writer = Writer::default();
+    writer.write(
+        0,
+        Message::new(message::FILE_ID)
+            .field(Field::enumeration(0, value::FILE_WORKOUT))
+            .field(Field::uint16(1, Some(MANUFACTURER_DEVELOPMENT)))
+            .field(Field::uint16(2, Some(0)))
+            .field(Field::uint32z(3, 1)),
+    );
+    writer.write(
+        1,
+        Message::new(message::WORKOUT)
+            .field(Field::enumeration(4, value::SPORT_CYCLING))
+            .field(Field::uint16(6, Some(number_of_steps)))
+            .field(Field::string(8, workout.name()))
+            .field(Field::enumeration(11, value::SUB_SPORT_INDOOR_CYCLING)),
+    );
+    for (index, effort) in steps.iter().enumerate() {
+        let in_watts =
+            |wattage: f64| wattage.round().max(0.0) as u32 + value::POWER_IN_WATTS_OFFSET;
+        let intensity = match effort.section() {
+            Section::Warmup => value::INTENSITY_WARMUP,
+            Section::Main => value::INTENSITY_ACTIVE,
+            Section::Cooldown => value::INTENSITY_COOLDOWN,
+        };
+        let (low, high) = match effort.target_range() {
+            Some(range) => (range.low, range.high),
+            None => {
+                let average = (effort.starting_value() + effort.ending_value()) / 2.0;
+                (average, average)
+            }
+        };
+        let index = u16::try_from(index).map_err(too_many_steps)?;
+        let step = Message::new(message::WORKOUT_STEP)
+            .field(Field::uint16(254, Some(index)))
+            .field(Field::enumeration(1, value::DURATION_TIME))
+            .field(Field::uint32(
+                2,
+                Some((effort.duration_in_minutes() * 60_000.0).round() as u32),
+            ))
+            .field(Field::enumeration(3, value::TARGET_POWER))
+            .field(Field::uint32(4, Some(0)))
+            .field(Field::uint32(5, Some(in_watts(low))))
+            .field(Field::uint32(6, Some(in_watts(high))))
+            .field(Field::enumeration(7, intensity));
+        writer.write(
+            2,
+            match effort.name() {
+                Some(name) => step.field(Field::string(0, name)),
+                None => step,
+            },
+        );
+    }
+    Ok(writer.finish())
+}
+
+fn to_fit_time(unix_seconds: u64) -> u32 {
+    unix_seconds.saturating_sub(FIT_EPOCH_IN_UNIX_SECONDS) as u32
+}
+
+fn event(timestamp: u32, event_type: u8) -> Message {
+    Message::new(message::EVENT)
+        .field(Field::uint32(253, Some(timestamp)))
+        .field(Field::enumeration(0, value::EVENT_TIMER))
+        .field(Field::enumeration(1, event_type))
+}
+
+/// Field numbers of average power, maximum power, average heart rate,
+/// maximum heart rate and average cadence.
+type SummaryFields = [u8; 5];
+const LAP_SUMMARY_FIELDS: SummaryFields = [19, 20, 15, 16, 17];
+const SESSION_SUMMARY_FIELDS: SummaryFields = [20, 21, 16, 17, 18];
+
+fn with_summary(message: Message, summary: &Summary, fields: SummaryFields) -> Message {
+    message
+        .field(Field::uint16(fields[0], summary.average_power))
+        .field(Field::uint16(fields[1], summary.maximum_power))
+        .field(Field::uint8(fields[2], summary.average_heart_rate))
+        .field(Field::uint8(fields[3], summary.maximum_heart_rate))
+        .field(Field::uint8(fields[4], summary.average_cadence))
+}
+
+struct Field {
+    number: u8,
+    base_type: u8,
+    bytes: Vec<u8>,
+}
+
+impl Field {
+    fn enumeration(number: u8, value: u8) -> Self {
+        Self::new(number, base_type::ENUM, vec![value])
+    }
+    /// Missing values are written as the invalid value of the base type.
+    fn uint8(number: u8, value: Option<u8>) -> Self {
+        Self::new(number, base_type::UINT8, vec![value.unwrap_or(u8::MAX)])
+    }
+    fn uint16(number: u8, value: Option<u16>) -> Self {
+        let value = value.unwrap_or(u16::MAX);
+        Self::new(number, base_type::UINT16, value.to_le_bytes().to_vec())
+    }
+    fn uint32(number: u8, value: Option<u32>) -> Self {
+        let value = value.unwrap_or(u32::MAX);
+        Self::new(number, base_type::UINT32, value.to_le_bytes().to_vec())
+    }
+    fn uint32z(number: u8, value: u32) -> Self {
+        Self::new(number, base_type::UINT32Z, value.to_le_bytes().to_vec())
+    }
+    /// Strings are cut to the 254 bytes a field can hold besides the terminating zero.
+    fn string(number: u8, value: &str) -> Self {
+        let mut length = value.len().min(usize::from(u8::MAX) - 1);
+        while !value.is_char_boundary(length) {
+            length -= 1;
+        }
+        let mut bytes = value.as_bytes()[..length].to_vec();
+        bytes.push(0);
+        Self::new(number, base_type::STRING, bytes)
+    }
+    fn bytes(number: u8, value: &[u8]) -> Self {
+        Self::new(number, base_type::BYTE, value.to_vec())
+    }
+    fn new(number: u8, base_type: u8, bytes: Vec<u8>) -> Self {
+        Self {
+            number,
+            base_type,
+            bytes,
+        }
+    }
+}
+
+struct Message {
+    global_number: u16,
+    fields: Vec<Field>,
+    developer_fields: Vec<Field>,
+}
+
+impl Message {
+    fn new(global_number: u16) -> Self {
+        Self {
+            global_number,
+            fields: Vec::new(),
+            developer_fields: Vec::new(),
+        }
+    }
+    fn field(mut self, field: Field) -> Self {
+        self.fields.push(field);
+        self
+    }
+    /// Developer fields all belong to developer data index 0.
+    fn developer_field(mut self, field: Field) -> Self {
+        self.developer_fields.push(field);
+        self
+    }
+
+    fn definition(&self, local_type: u8) -> Vec<u8> {
+        let header = if self.developer_fields.is_empty() {
+            0x40
+        } else {
+            0x60
+        };
+        let mut bytes = vec![header | local_type, 0, 0];
+        bytes.extend_from_slice(&self.global_number.to_le_bytes());
+        bytes.push(self.fields.len() as u8);
+        for field in &self.fields {
+            bytes.extend([field.number, field.bytes.len() as u8, field.base_type]);
+        }
+        if !self.developer_fields.is_empty() {
+            bytes.push(self.developer_fields.len() as u8);
+            for field in &self.developer_fields {
+                bytes.extend([field.number, field.bytes.len() as u8, 0]);
+            }
+        }
+        bytes
+    }
+    fn data(&self, local_type: u8) -> Vec<u8> {
+        let mut bytes = vec![local_type];
+        for field in self.fields.iter().chain(&self.developer_fields) {
+            bytes.extend_from_slice(&field.bytes);
+        }
+        bytes
+    }
+}
+
+/// Collects messages and writes a definition whenever a local message type changes its layout.
+#[derive(Default)]
+struct Writer {
+    records: Vec<u8>,
+    definitions: [Option<Vec<u8>>; 16],
+}
+
+impl Writer {
+    fn write(&mut self, local_type: u8, message: Message) {
+        let definition = message.definition(local_type);
+        let current = &mut self.definitions[usize::from(local_type)];
+        if current.as_ref() != Some(&definition) {
+            self.records.extend_from_slice(&definition);
+            *current = Some(definition);
+        }
+        self.records.extend(message.data(local_type));
+    }
+    fn finish(self) -> Vec<u8> {
+        let mut file = vec![HEADER_SIZE, PROTOCOL_VERSION];
+        file.extend_from_slice(&PROFILE_VERSION.to_le_bytes());
+        file.extend_from_slice(&(self.records.len() as u32).to_le_bytes());
+        file.extend_from_slice(b".FIT");
+        file.extend_from_slice(&crc(&file).to_le_bytes());
+        file.extend(self.records);
+        file.extend_from_slice(&crc(&file).to_le_bytes());
+        file
+    }
+}
+
+/// Layout of a local message type as announced by a definition message.
+struct Definition {
+    global_number: u16,
+    big_endian: bool,
+    /// Number, size and base type of every field.
+    fields: Vec<(u8, u8, u8)>,
+    developer_data_size: usize,
+}
+
+/// The values of a data message.
+#[derive(Default)]
+struct Values {
+    /// Fields of up to four bytes by field number.
+    numbers: Vec<(u8, u32)>,
+    strings: Vec<(u8, String)>,
+}
+
+impl Values {
+    fn number(&self, number: u8) -> Option<u32> {
+        self.numbers
+            .iter()
+            .find(|(field_number, _)| *field_number == number)
+            .map(|(_, value)| *value)
+    }
+    fn string(&self, number: u8) -> Option<&str> {
+        self.strings
+            .iter()
+            .find(|(field_number, _)| *field_number == number)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A data message as read from a file.
+struct DataMessage {
+    global_number: u16,
+    /// Seconds since the last full timestamp, for messages with a compressed header.
+    compressed_timestamp: Option<u32>,
+    values: Values,
+}
+
+/// Reads through the messages of a FIT file.
+struct Reader<'a> {
+    records: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], ImportError> {
+        let taken = self
+            .records
+            .get(self.position..self.position + count)
+            .ok_or(ImportError::InvalidFit)?;
+        self.position += count;
+        Ok(taken)
+    }
+    fn u8(&mut self) -> Result<u8, ImportError> {
+        Ok(self.take(1)?[0])
+    }
+    fn definition(&mut self, has_developer_data: bool) -> Result<Definition, ImportError> {
+        let header = self.take(5)?;
+        let big_endian = header[1] == 1;
+        let global_number = if big_endian {
+            u16::from_be_bytes([header[2], header[3]])
+        } else {
+            u16::from_le_bytes([header[2], header[3]])
+        };
+        let fields = self
+            .take(3 * usize::from(header[4]))?
+            .chunks(3)
+            .map(|field| (field[0], field[1], field[2]))
+            .collect();
+        let developer_data_size = if has_developer_data {
+            let number_of_fields = usize::from(self.u8()?);
+            self.take(3 * number_of_fields)?
+                .chunks(3)
+                .map(|field| usize::from(field[1]))
+                .sum()
+        } else {
+            0
+        };
+        Ok(Definition {
+            global_number,
+            big_endian,
+            fields,
+            developer_data_size,
+        })
+    }
+    /// Values of all strings and all other fields of up to four bytes.
+    fn data(&mut self, definition: &Definition) -> Result<Values, ImportError> {
+        let mut values = Values::default();
+        for &(number, size, base_type) in &definition.fields {
+            let bytes = self.take(usize::from(size))?;
+            if base_type == base_type::STRING {
+                let text = bytes.split(|&byte| byte == 0).next().unwrap_or_default();
+                values
+                    .strings
+                    .push((number, String::from_utf8_lossy(text).into_owned()));
+            } else if size <= 4 {
+                let value = if definition.big_endian {
+                    bytes
+                        .iter()
+                        .fold(0, |value, &byte| value << 8 | u32::from(byte))
+                } else {
+                    bytes
+                        .iter()
+                        .rev()
+                        .fold(0, |value, &byte| value << 8 | u32::from(byte))
+                };
+                values.numbers.push((number, value));
+            }
+        }
+        self.take(definition.developer_data_size)?;
+        Ok(values)
+    }
+}
+
+/// All data messages of a FIT file, in the order they were written.
+fn read_messages(file: &[u8]) -> Result<Vec<DataMessage>, ImportError> {
+    let header_size = usize::from(*file.first().ok_or(ImportError::InvalidFit)?);
+    if file.len() < header_size || header_size < 12 || &file[8..12] != b".FIT" {
+        return Err(ImportError::InvalidFit);
+    }
+    let data_size = u32::from_le_bytes([file[4], file[5], file[6], file[7]]) as usize;
+    let mut reader = Reader {
+        records: file
+            .get(header_size..header_size + data_size)
+            .ok_or(ImportError::InvalidFit)?,
+        position: 0,
+    };
+
+    let mut definitions: [Option<Definition>; 16] = Default::default();
+    let mut messages = Vec::new();
+    while reader.position < reader.records.len() {
+        let header = reader.u8()?;
+        if header & 0x80 == 0 && header & 0x40 != 0 {
+            definitions[usize::from(header & 0x0F)] = Some(reader.definition(header & 0x20 != 0)?);
+            continue;
+        }
+
+        let (local_type, compressed_timestamp) = if header & 0x80 != 0 {
+            (
+                usize::from(header >> 5 & 0x03),
+                Some(u32::from(header & 0x1F)),
+            )
+        } else {
+            (usize::from(header & 0x0F), None)
+        };
+        let definition = definitions[local_type]
+            .as_ref()
+            .ok_or(ImportError::InvalidFit)?;
+        messages.push(DataMessage {
+            global_number: definition.global_number,
+            compressed_timestamp,
+            values: reader.data(definition)?,
+        });
+    }
+    Ok(messages)
+}
+
+/// Read the records of a FIT activity file, all other messages are skipped.
+pub fn fit_to_recording(file: &[u8]) -> Result<Recording, ImportError> {
+    let mut last_timestamp = 0;
+    let mut timed_samples = Vec::new();
+    for message in read_messages(file)? {
+        let values = &message.values;
+        if let Some(offset) = message.compressed_timestamp {
+            let mut timestamp = (last_timestamp & !0x1F) + offset;
+            if offset < last_timestamp & 0x1F {
+                timestamp += 0x20;
+            }
+            last_timestamp = timestamp;
+        } else if let Some(timestamp) = values
+            .number(253)
+            .filter(|&timestamp| timestamp != u32::MAX)
+        {
+            last_timestamp = timestamp;
+        }
+        if message.global_number == message::RECORD {
+            let valid =
+                |number: u8, invalid: u32| values.number(number).filter(|&value| value != invalid);
+            timed_samples.push((
+                u64::from(last_timestamp) + FIT_EPOCH_IN_UNIX_SECONDS,
+                Sample {
+                    power_in_watts: valid(7, 0xFFFF).map(|power| power as u16),
+                    cadence_in_rpm: valid(4, 0xFF).map(|cadence| cadence as u8),
+                    heart_rate: valid(3, 0xFF).map(|heart_rate| heart_rate as u8),
+                    ..Sample::default()
+                },
+            ));
+        }
+    }
+    Recording::from_timed_samples(timed_samples)
+}
+
+/// Length of steps lasting until the lap button is pressed, which efforts can not.
+const OPEN_STEP_IN_MINUTES: f64 = 5.0;
+/// Most efforts a workout with repeated steps may expand to.
+const MAXIMAL_NUMBER_OF_EFFORTS: usize = 10_000;
+/// Bounds of the seven power zones in percent of the FTP, the open last one capped at 200%.
+const POWER_ZONES_IN_PERCENT: [(f64, f64); 7] = [
+    (0.0, 55.0),
+    (55.0, 75.0),
+    (75.0, 90.0),
+    (90.0, 105.0),
+    (105.0, 120.0),
+    (120.0, 150.0),
+    (150.0, 200.0),
+];
+
+/// Read a FIT workout file.
+/// Steps with power targets are supported, given in watts, in percent of the `ftp` or as power zone.
+/// They last a fixed time or until the lap button is pressed, and repeat steps are expanded.
+pub fn fit_to_workout(file: &[u8], ftp: Option<f64>) -> Result<Workout, ImportError> {
+    let messages = read_messages(file)?;
+    let is_workout = messages.iter().any(|message| {
+        message.global_number == message::FILE_ID
+            && message.values.number(0) == Some(u32::from(value::FILE_WORKOUT))
+    });
+    if !is_workout {
+        return Err(ImportError::InvalidFit);
+    }
+    let name = messages
+        .iter()
+        .find(|message| message.global_number == message::WORKOUT)
+        .and_then(|message| message.values.string(8))
+        .unwrap_or_default();
+    let mut efforts: Vec<Effort> = Vec::new();
+    // The index of the first effort of every step read so far.
+    let mut first_efforts = Vec::new();
+    for step in messages
+        .iter()
+        .filter(|message| message.global_number == message::WORKOUT_STEP)
+    {
+        first_efforts.push(efforts.len());
+        let values = &step.values;
+        if values.number(1) != Some(u32::from(value::DURATION_REPEAT_UNTIL_STEPS_COMPLETED)) {
+            efforts.push(step_to_effort(values, ftp)?);
+            continue;
+        }
+        let first_step = values.number(2).ok_or(ImportError::InvalidFit)?;
+        let repetitions = values.number(4).ok_or(ImportError::InvalidFit)? as usize;
+        let repeated = efforts[*first_efforts
+            .get(first_step as usize)
+            .ok_or(ImportError::InvalidFit)?..]
+            .to_vec();
+        if repeated.len().saturating_mul(repetitions) > MAXIMAL_NUMBER_OF_EFFORTS {
+            return Err(ImportError::InvalidFit);
+        }
+        for _ in 1..repetitions {
+            efforts.extend(repeated.iter().cloned());
+        }
+        if efforts.len() > MAXIMAL_NUMBER_OF_EFFORTS {
+            return Err(ImportError::InvalidFit);
+        }
+    }
+    Ok(Workout::new(name, "", efforts))
+}
+
+fn step_to_effort(values: &Values, ftp: Option<f64>) -> Result<Effort, ImportError> {
+    let duration_in_minutes = match values.number(1).map(|duration| duration as u8) {
+        Some(value::DURATION_TIME) => {
+            f64::from(values.number(2).ok_or(ImportError::InvalidFit)?) / 60_000.0
+        }
+        Some(value::DURATION_OPEN) => OPEN_STEP_IN_MINUTES,
+        _ => return Err(ImportError::InvalidFit),
+    };
+    if values.number(3) != Some(u32::from(value::TARGET_POWER)) {
+        return Err(ImportError::InvalidFit);
+    }
+    let custom_target = |number: u8| {
+        values
+            .number(number)
+            .filter(|&target| target != 0 && target != u32::MAX)
+    };
+    let (low, high) = match (custom_target(5), custom_target(6)) {
+        (Some(low), Some(high)) => (
+            custom_power_in_watts(low, ftp)?,
+            custom_power_in_watts(high, ftp)?,
+        ),
+        _ => zone_in_watts(values.number(4), ftp)?,
+    };
+    let section = match values.number(7).map(|intensity| intensity as u8) {
+        Some(value::INTENSITY_WARMUP) => Section::Warmup,
+        Some(value::INTENSITY_COOLDOWN) => Section::Cooldown,
+        _ => Section::Main,
+    };
+    let effort = if low == high {
+        Effort::new(duration_in_minutes, low, None)
+    } else {
+        Effort::new(duration_in_minutes, (low + high) / 2.0, None).with_target_range(low, high)
+    }
+    .with_section(section);
+    Ok(match values.string(0).filter(|name| !name.is_empty()) {
+        Some(name) => effort.with_name(name),
+        None => effort,
+    })
+}
+
+/// A custom power target, in watts above `POWER_IN_WATTS_OFFSET` and in percent of the FTP below.
+fn custom_power_in_watts(target: u32, ftp: Option<f64>) -> Result<f64, ImportError> {
+    if target > value::POWER_IN_WATTS_OFFSET {
+        Ok(f64::from(target - value::POWER_IN_WATTS_OFFSET))
+    } else {
+        let ftp = ftp.ok_or(ImportError::MissingFtp)?;
+        Ok((f64::from(target) / 100.0 * ftp).round())
+    }
+}
+
+/// The lowest and highest wattage of the power zone with the given number, counted from one.
+fn zone_in_watts(zone: Option<u32>, ftp: Option<f64>) -> Result<(f64, f64), ImportError> {
+    let (low, high) = zone
+        .and_then(|zone| POWER_ZONES_IN_PERCENT.get((zone as usize).checked_sub(1)?))
+        .ok_or(ImportError::InvalidFit)?;
+    let ftp = ftp.ok_or(ImportError::MissingFtp)?;
+    Ok(((low / 100.0 * ftp).round(), (high / 100.0 * ftp).round()))
+}
+
+/// The CRC-16 used by FIT files.
+pub(super) fn crc(bytes: &[u8]) -> u16 {
+    const TABLE: [u16; 16] = [
+        0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401, 0xA001, 0x6C00, 0x7800,
+        0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
+    ];
+    bytes.iter().fold(0, |crc, byte| {
+        let crc = (crc >> 4) ^ TABLE[usize::from(crc & 0xF)] ^ TABLE[usize::from(byte & 0xF)];
+        (crc >> 4) ^ TABLE[usize::from(crc & 0xF)] ^ TABLE[usize::from(byte >> 4)]
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::activity::Sample;
+
+    fn recording() -> Recording {
+        let mut recording = Recording::new(1_709_274_600);
+        for second in 0..90 {
+            recording.record(Sample {
+                power_in_watts: Some(200),
+                cadence_in_rpm: Some(90),
+                heart_rate: None,
+                target_in_watts: Some(200),
+                effort_index: Some(second / 60),
+            });
+        }
+        recording
+    }
+
+    #[test]
+    fn crc_of_check_string() {
+        assert_eq!(crc(b"123456789"), 0xBB3D);
+    }
+    #[test]
+    fn header_describes_file() {
+        let file = recording_to_fit(&recording());
+        assert_eq!(file[0], HEADER_SIZE);
+        assert_eq!(&file[8..12], b".FIT");
+        let data_size = u32::from_le_bytes([file[4], file[5], file[6], file[7]]) as usize;
+        assert_eq!(file.len(), usize::from(HEADER_SIZE) + data_size + 2);
+        assert_eq!(crc(&file[..14]), 0);
+    }
+    #[test]
+    fn file_checksum_is_valid() {
+        assert_eq!(crc(&recording_to_fit(&recording())), 0);
+    }
+    #[test]
+    fn definitions_are_written_once() {
+        let mut writer = Writer::default();
+        writer.write(0, event(0, value::EVENT_TYPE_START));
+        let length_with_definition = writer.records.len();
+        writer.write(0, event(1, value::EVENT_TYPE_STOP));
+        assert_eq!(writer.records.len() - length_with_definition, 7);
+    }
+    #[test]
+    fn read_written_file() {
+        let read = fit_to_recording(&recording_to_fit(&recording())).unwrap();
+        assert_eq!(read.start_in_unix_seconds(), 1_709_274_600);
+        assert_eq!(read.samples().len(), 90);
+        assert_eq!(read.samples()[42].power_in_watts, Some(200));
+        assert_eq!(read.samples()[42].cadence_in_rpm, Some(90));
+        assert_eq!(read.samples()[42].heart_rate, None);
+    }
+    #[test]
+    fn read_compressed_timestamps() {
+        let mut records = vec![0x40, 0, 0, 20, 0, 2, 253, 4, 0x86, 7, 2, 0x84];
+        records.extend([0x41, 0, 0, 20, 0, 1, 7, 2, 0x84]);
+        records.extend([0x00, 0x1E, 0, 0, 0, 100, 0]);
+        // Local type 1 with time offsets 31 and 1, i.e. one and three seconds later.
+        records.extend([0xBF, 0xFF, 0xFF, 0xA1, 150, 0]);
+        let mut file = vec![HEADER_SIZE, PROTOCOL_VERSION, 0, 0];
+        file.extend((records.len() as u32).to_le_bytes());
+        file.extend(b".FIT\0\0");
+        file.extend(records);
+
+        let read = fit_to_recording(&file).unwrap();
+        let powers: Vec<Option<u16>> = read
+            .samples()
+            .iter()
+            .map(|sample| sample.power_in_watts)
+            .collect();
+        assert_eq!(powers, vec![Some(100), None, None, Some(150)]);
+    }
+    #[test]
+    fn reject_records_before_first_timestamp() {
+        let mut records = vec![0x40, 0, 0, 20, 0, 2, 253, 4, 0x86, 7, 2, 0x84];
+        records.extend([0x41, 0, 0, 20, 0, 1, 7, 2, 0x84]);
+        // A time offset before any full timestamp, counted from the FIT epoch.
+        records.extend([0xA1, 150, 0]);
+        records.extend([0x00, 0, 0, 0, 0x40, 100, 0]);
+        let mut file = vec![HEADER_SIZE, PROTOCOL_VERSION, 0, 0];
+        file.extend((records.len() as u32).to_le_bytes());
+        file.extend(b".FIT\0\0");
+        file.extend(records);
+
+        assert_eq!(fit_to_recording(&file), Err(ImportError::GapTooLong));
+    }
+    #[test]
+    fn reject_other_files() {
+        assert_eq!(
+            fit_to_recording(b"<?xml version=\"1.0\"?>"),
+            Err(ImportError::InvalidFit)
+        );
+    }
+    #[test]
+    fn workout_round_trip() {
+        let workout = Workout::new(
+            "Sweet Spot",
+            "",
+            vec![
+                Effort::new(10.0, 100.0, None)
+                    .with_section(Section::Warmup)
+                    .with_name("Warmup"),
+                Effort::new(20.0, 250.0, None).with_name("VO2 #3"),
+                Effort::new(20.0, 182.0, None).with_target_range(176.0, 188.0),
+                Effort::new(5.0, 150.0, None).with_section(Section::Cooldown),
+            ],
+        );
+        let file = workout_to_fit(&workout).unwrap();
+        assert_eq!(crc(&file), 0);
+        assert_eq!(fit_to_workout(&file, None), Ok(workout));
+    }
+    #[test]
+    fn ramps_become_steady_steps() {
+        let workout = Workout::new("Ramp", "", vec![Effort::new(1.0, 100.0, Some(200.0))]);
+        let read = fit_to_workout(&workout_to_fit(&workout).unwrap(), None).unwrap();
+        assert_eq!(read.efforts().len(), 5);
+        assert!(read
+            .efforts()
+            .iter()
+            .all(|effort| effort.target_range().is_none()
+                && effort.starting_value() == effort.ending_value()));
+        assert_eq!(read.efforts()[0].starting_value(), 110.0);
+    }
+    #[test]
+    fn ramps_keep_their_duration() {
+        let workout = Workout::new("Ramp", "", vec![Effort::new(1.1, 100.0, Some(210.0))]);
+        let read = fit_to_workout(&workout_to_fit(&workout).unwrap(), None).unwrap();
+        assert_eq!(read.efforts().len(), 6);
+        assert!((read.total_time_of_workout() - 1.1).abs() < 1e-9);
+    }
+    #[test]
+    fn too_many_steps() {
+        let workout = Workout::new("", "", vec![Effort::new(1.0, 100.0, None); 65_536]);
+        assert_eq!(
+            workout_to_fit(&workout),
+            Err(ExportFitError::TooManySteps(65_536))
+        );
+    }
+    #[test]
+    fn activity_is_no_workout() {
+        assert_eq!(
+            fit_to_workout(&recording_to_fit(&recording()), None),
+            Err(ImportError::InvalidFit)
+        );
+    }
+    /// A workout file with steps of the given duration type and value,
+    /// target value and custom targets.
+    fn workout_file(steps: &[(u8, u32, u32, u32, u32)]) -> Vec<u8> {
+        let mut writer = Writer::default();
+        writer.write(
+            0,
+            Message::new(message::FILE_ID).field(Field::enumeration(0, value::FILE_WORKOUT)),
+        );
+        for (index, &(duration_type, duration, target, low, high)) in steps.iter().enumerate() {
+            writer.write(
+                1,
+                Message::new(message::WORKOUT_STEP)
+                    .field(Field::uint16(254, Some(index as u16)))
+                    .field(Field::enumeration(1, duration_type))
+                    .field(Field::uint32(2, Some(duration)))
+                    .field(Field::enumeration(3, value::TARGET_POWER))
+                    .field(Field::uint32(4, Some(target)))
+                    .field(Field::uint32(5, Some(low)))
+                    .field(Field::uint32(6, Some(high))),
+            );
+        }
+        writer.finish()
+    }
+
+    #[test]
+    fn targets_in_percent_of_ftp() {
+        let file = workout_file(&[(value::DURATION_TIME, 60_000, 0, 88, 94)]);
+        assert_eq!(
+            fit_to_workout(&file, Some(250.0)).unwrap().efforts(),
+            &[Effort::new(1.0, 227.5, None).with_target_range(220.0, 235.0)]
+        );
+        assert_eq!(fit_to_workout(&file, None), Err(ImportError::MissingFtp));
+    }
+    #[test]
+    fn zone_targets() {
+        let file = workout_file(&[(value::DURATION_TIME, 60_000, 2, 0, 0)]);
+        assert_eq!(
+            fit_to_workout(&file, Some(200.0)).unwrap().efforts(),
+            &[Effort::new(1.0, 130.0, None).with_target_range(110.0, 150.0)]
+        );
+        let unknown_zone = workout_file(&[(value::DURATION_TIME, 60_000, 8, 0, 0)]);
+        assert_eq!(
+            fit_to_workout(&unknown_zone, Some(200.0)),
+            Err(ImportError::InvalidFit)
+        );
+    }
+    #[test]
+    fn open_steps_get_a_fixed_length() {
+        let file = workout_file(&[(value::DURATION_OPEN, 0, 0, 1100, 1100)]);
+        assert_eq!(
+            fit_to_workout(&file, None).unwrap().efforts(),
+            &[Effort::new(OPEN_STEP_IN_MINUTES, 100.0, None)]
+        );
+    }
+    #[test]
+    fn repeat_steps_are_expanded() {
+        let file = workout_file(&[
+            (value::DURATION_TIME, 60_000, 0, 1100, 1100),
+            (value::DURATION_TIME, 120_000, 0, 1300, 1300),
+            (value::DURATION_TIME, 60_000, 0, 1150, 1150),
+            (value::DURATION_REPEAT_UNTIL_STEPS_COMPLETED, 1, 3, 0, 0),
+        ]);
+        let read = fit_to_workout(&file, None).unwrap();
+        let wattages: Vec<f64> = read
+            .efforts()
+            .iter()
+            .map(|effort| effort.starting_value())
+            .collect();
+        assert_eq!(
+            wattages,
+            vec![100.0, 300.0, 150.0, 300.0, 150.0, 300.0, 150.0]
+        );
+    }
+    #[test]
+    fn reject_repeats_of_later_steps_and_too_many_repeats() {
+        let later = workout_file(&[(value::DURATION_REPEAT_UNTIL_STEPS_COMPLETED, 1, 3, 0, 0)]);
+        assert_eq!(fit_to_workout(&later, None), Err(ImportError::InvalidFit));
+        let too_many = workout_file(&[
+            (value::DURATION_TIME, 1_000, 0, 1100, 1100),
+            (
+                value::DURATION_REPEAT_UNTIL_STEPS_COMPLETED,
+                0,
+                u32::MAX - 1,
+                0,
+                0,
+            ),
+        ]);
+        assert_eq!(
+            fit_to_workout(&too_many, None),
+            Err(ImportError::InvalidFit)
+        );
+    }
+    #[test]
+    fn long_strings_are_cut() {
+        let field = Field::string(8, &"ä".repeat(200));
+        assert_eq!(field.bytes.len(), 255);
+        assert_eq!(field.bytes.last(), Some(&0));
+    }
+    #[test]
+    fn developer_field_is_declared_in_definition() {
+        let definition = Message::new(message::RECORD)
+            .field(Field::uint16(7, Some(100)))
+            .developer_field(Field::uint16(0, Some(120)))
+            .definition(4);
+        assert_eq!(
+            definition,
+            vec![0x64, 0, 0, 20, 0, 1, 7, 2, 0x84, 1, 0, 2, 0]
+        );
+    }
+}
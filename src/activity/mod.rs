@@ -0,0 +1,422 @@
+/// Reading and writing recordings as FIT activity files.
+#[cfg(feature = "fit")]
+pub mod fit;
+
+/// Reading and writing recordings as TCX activity files.
+pub mod tcx;
+
+/// Reading power streams from CSV files.
+pub mod csv;
+
+/// Comparing a ride to the workout it was meant to follow.
+pub mod compliance;
+
+/// Turning a ride into a workout.
+pub mod segmentation;
+
+use crate::trainer::ftms::IndoorBikeData;
+use crate::workout_data::player::{Clock, ManualClock, WorkoutPlayer};
+use crate::workout_data::workout::Workout;
+use regex::Regex;
+use std::path::Path;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq)]
+pub enum ImportError {
+    CouldNotRead,
+    UnknownFormat,
+    InvalidFit,
+    InvalidTcx,
+    InvalidCsv,
+    NoSamples,
+    /// Targets in percent of the FTP were read without knowing it.
+    MissingFtp,
+    /// Samples more than `MAXIMAL_GAP_IN_SECONDS` apart, most likely from a broken clock.
+    GapTooLong,
+}
+
+/// Longest pause within a ride, longer ones are taken for broken timestamps.
+const MAXIMAL_GAP_IN_SECONDS: u64 = 24 * 60 * 60;
+
+/// One second of a ride.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Sample {
+    pub power_in_watts: Option<u16>,
+    pub cadence_in_rpm: Option<u8>,
+    pub heart_rate: Option<u8>,
+    pub target_in_watts: Option<u16>,
+    /// The effort of the workout ridden during this second.
+    pub effort_index: Option<usize>,
+}
+
+impl Sample {
+    /// What the trainer reported together with what the player asked for.
+    pub fn from_player<C: Clock>(player: &WorkoutPlayer<C>, data: &IndoorBikeData) -> Self {
+        Self {
+            power_in_watts: data.power_in_watts.map(|power| power.max(0) as u16),
+            cadence_in_rpm: data
+                .cadence_in_rpm
+                .map(|cadence| cadence.round().min(f64::from(u8::MAX)) as u8),
+            heart_rate: data.heart_rate,
+            target_in_watts: player
+                .current_target()
+                .map(|target| target.round().max(0.0) as u16),
+            effort_index: player.current_index(),
+        }
+    }
+}
+
+/// A continuous run of samples belonging to the same effort.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lap {
+    pub first_sample: usize,
+    pub number_of_samples: usize,
+}
+
+/// Averages and maxima over some samples, `None` if nothing was recorded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub average_power: Option<u16>,
+    pub maximum_power: Option<u16>,
+    pub average_cadence: Option<u8>,
+    pub average_heart_rate: Option<u8>,
+    pub maximum_heart_rate: Option<u8>,
+}
+
+/// A ride recorded at one sample per second.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recording {
+    /// Start of the ride in seconds since the unix epoch.
+    start_in_unix_seconds: u64,
+    samples: Vec<Sample>,
+}
+
+impl Recording {
+    pub fn new(start_in_unix_seconds: u64) -> Self {
+        Self {
+            start_in_unix_seconds,
+            samples: Vec::new(),
+        }
+    }
+    /// Record a ride where one notification was received per second while riding the workout.
+    pub fn from_ride(
+        workout: &Workout,
+        ride_data: &[IndoorBikeData],
+        start_in_unix_seconds: u64,
+    ) -> Self {
+        let clock = ManualClock::default();
+        let mut player = WorkoutPlayer::new(workout, &clock);
+        let mut recording = Recording::new(start_in_unix_seconds);
+        player.play();
+        for data in ride_data {
+            recording.record(Sample::from_player(&player, data));
+            clock.advance(Duration::from_secs(1));
+        }
+        recording
+    }
+
+    /// Place samples taken at the given unix time on a one second grid.
+    /// Seconds without a sample stay empty, later samples of the same second win.
+    pub fn from_timed_samples(timed_samples: Vec<(u64, Sample)>) -> Result<Self, ImportError> {
+        let mut times: Vec<u64> = timed_samples.iter().map(|(time, _)| *time).collect();
+        times.sort_unstable();
+        let start = *times.first().ok_or(ImportError::NoSamples)?;
+        if times
+            .windows(2)
+            .any(|pair| pair[1] - pair[0] > MAXIMAL_GAP_IN_SECONDS)
+        {
+            return Err(ImportError::GapTooLong);
+        }
+        let mut recording = Recording::new(start);
+        for (time, sample) in timed_samples {
+            let second = (time - start) as usize;
+            if second >= recording.samples.len() {
+                recording.samples.resize(second + 1, Sample::default());
+            }
+            recording.samples[second] = sample;
+        }
+        Ok(recording)
+    }
+    /// Read a FIT, TCX or CSV file, depending on its extension.
+    pub fn load(path: &Path) -> Result<Self, ImportError> {
+        let contents = std::fs::read(path).map_err(|_| ImportError::CouldNotRead)?;
+        Self::parse(
+            path.extension().and_then(|extension| extension.to_str()),
+            contents,
+        )
+    }
+    /// Read the contents of a FIT, TCX or CSV file, depending on `extension`.
+    pub fn parse(extension: Option<&str>, contents: Vec<u8>) -> Result<Self, ImportError> {
+        let as_text = |contents| String::from_utf8(contents).map_err(|_| ImportError::CouldNotRead);
+        match extension {
+            #[cfg(feature = "fit")]
+            Some("fit") => fit::fit_to_recording(&contents),
+            Some("tcx") => tcx::tcx_to_recording(&as_text(contents)?),
+            Some("csv") => csv::csv_to_recording(&as_text(contents)?),
+            Some(_) | None => Err(ImportError::UnknownFormat),
+        }
+    }
+
+    pub fn record(&mut self, sample: Sample) {
+        self.samples.push(sample);
+    }
+    pub fn samples(&self) -> &[Sample] {
+        &self.samples
+    }
+    pub fn start_in_unix_seconds(&self) -> u64 {
+        self.start_in_unix_seconds
+    }
+    pub fn duration_in_seconds(&self) -> u64 {
+        self.samples.len() as u64
+    }
+
+    /// Laps start whenever the ridden effort changes.
+    pub fn laps(&self) -> Vec<Lap> {
+        let mut laps: Vec<Lap> = Vec::new();
+        for (index, sample) in self.samples.iter().enumerate() {
+            match laps.last_mut() {
+                Some(lap) if self.samples[lap.first_sample].effort_index == sample.effort_index => {
+                    lap.number_of_samples += 1
+                }
+                _ => laps.push(Lap {
+                    first_sample: index,
+                    number_of_samples: 1,
+                }),
+            }
+        }
+        laps
+    }
+    pub fn lap_samples(&self, lap: &Lap) -> &[Sample] {
+        &self.samples[lap.first_sample..lap.first_sample + lap.number_of_samples]
+    }
+
+    /// Write the recording as FIT file, or as TCX file if the path ends in `.tcx`.
+    /// Without the `fit` feature, only TCX files can be written.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("tcx") => std::fs::write(path, tcx::recording_to_tcx(self)),
+            #[cfg(feature = "fit")]
+            Some(_) | None => std::fs::write(path, fit::recording_to_fit(self)),
+            #[cfg(not(feature = "fit"))]
+            Some(_) | None => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Writing FIT files needs the `fit` feature.",
+            )),
+        }
+    }
+}
+
+pub fn summarize(samples: &[Sample]) -> Summary {
+    Summary {
+        average_power: average(samples.iter().filter_map(|sample| sample.power_in_watts)),
+        maximum_power: samples
+            .iter()
+            .filter_map(|sample| sample.power_in_watts)
+            .max(),
+        average_cadence: average(samples.iter().filter_map(|sample| sample.cadence_in_rpm)),
+        average_heart_rate: average(samples.iter().filter_map(|sample| sample.heart_rate)),
+        maximum_heart_rate: samples.iter().filter_map(|sample| sample.heart_rate).max(),
+    }
+}
+
+fn average<T>(values: impl Iterator<Item = T>) -> Option<T>
+where
+    T: Into<f64> + TryFrom<u32>,
+{
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| {
+        (sum + value.into(), count + 1)
+    });
+    if count == 0 {
+        None
+    } else {
+        T::try_from((sum / f64::from(count)).round() as u32).ok()
+    }
+}
+
+/// Render seconds since the unix epoch as UTC timestamp, e.g. `2024-03-01T06:30:00Z`.
+pub fn format_utc(unix_seconds: u64) -> String {
+    let days = (unix_seconds / 86_400) as i64;
+    let seconds_of_day = unix_seconds % 86_400;
+
+    // Convert days to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let shifted_days = days + 719_468;
+    let era = shifted_days.div_euclid(146_097);
+    let day_of_era = shifted_days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+/// Read an ISO 8601 timestamp such as `2024-03-01T07:30:00+01:00` as seconds since the unix epoch.
+pub fn parse_utc(timestamp: &str) -> Option<u64> {
+    let capture_timestamp = Regex::new(
+        r"^(\d{4})-(\d{2})-(\d{2})T(\d{2}):(\d{2}):(\d{2})(?:\.\d+)?(?:Z|([+-])(\d{2}):?(\d{2}))?$",
+    )
+    .expect("This regex is valid.");
+    let captures = capture_timestamp.captures(timestamp.trim())?;
+    let number = |index: usize| -> i64 {
+        captures
+            .get(index)
+            .and_then(|capture| capture.as_str().parse().ok())
+            .unwrap_or(0)
+    };
+
+    // Convert the civil date to days, see http://howardhinnant.github.io/date_algorithms.html
+    let (month, day) = (number(2), number(3));
+    let year = number(1) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    let offset_in_seconds = match captures.get(7).map(|sign| sign.as_str()) {
+        Some("-") => -(number(8) * 3600 + number(9) * 60),
+        Some(_) => number(8) * 3600 + number(9) * 60,
+        None => 0,
+    };
+    let seconds = days * 86_400 + number(4) * 3600 + number(5) * 60 + number(6) - offset_in_seconds;
+    u64::try_from(seconds).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workout_data::effort::Effort;
+
+    fn sample(power: u16, effort_index: usize) -> Sample {
+        Sample {
+            power_in_watts: Some(power),
+            effort_index: Some(effort_index),
+            ..Sample::default()
+        }
+    }
+
+    #[test]
+    fn laps_follow_efforts() {
+        let mut recording = Recording::new(0);
+        for sample in [
+            sample(100, 0),
+            sample(100, 0),
+            sample(300, 1),
+            sample(150, 2),
+        ] {
+            recording.record(sample);
+        }
+        assert_eq!(
+            recording.laps(),
+            vec![
+                Lap {
+                    first_sample: 0,
+                    number_of_samples: 2
+                },
+                Lap {
+                    first_sample: 2,
+                    number_of_samples: 1
+                },
+                Lap {
+                    first_sample: 3,
+                    number_of_samples: 1
+                },
+            ]
+        );
+    }
+    #[test]
+    fn recording_from_ride() {
+        let workout = Workout::new(
+            "",
+            "",
+            vec![Effort::new(1.0, 100.0, None), Effort::new(1.0, 200.0, None)],
+        );
+        let ride_data = vec![
+            IndoorBikeData {
+                power_in_watts: Some(120),
+                ..IndoorBikeData::default()
+            };
+            120
+        ];
+        let recording = Recording::from_ride(&workout, &ride_data, 0);
+
+        assert_eq!(recording.laps().len(), 2);
+        assert_eq!(recording.samples()[59].target_in_watts, Some(100));
+        assert_eq!(recording.samples()[60].target_in_watts, Some(200));
+    }
+    #[test]
+    fn summary_skips_missing_values() {
+        let samples = [
+            sample(100, 0),
+            sample(201, 0),
+            Sample {
+                heart_rate: Some(140),
+                ..Sample::default()
+            },
+        ];
+        assert_eq!(
+            summarize(&samples),
+            Summary {
+                average_power: Some(151),
+                maximum_power: Some(201),
+                average_cadence: None,
+                average_heart_rate: Some(140),
+                maximum_heart_rate: Some(140),
+            }
+        );
+    }
+    #[test]
+    fn timed_samples_on_grid() {
+        let recording =
+            Recording::from_timed_samples(vec![(12, sample(100, 0)), (10, sample(50, 0))]).unwrap();
+        assert_eq!(recording.start_in_unix_seconds(), 10);
+        assert_eq!(
+            recording.samples(),
+            &[sample(50, 0), Sample::default(), sample(100, 0)]
+        );
+    }
+    #[test]
+    fn no_timed_samples() {
+        assert_eq!(
+            Recording::from_timed_samples(vec![]),
+            Err(ImportError::NoSamples)
+        );
+    }
+    #[test]
+    fn timed_samples_days_apart() {
+        assert_eq!(
+            Recording::from_timed_samples(vec![
+                (10, sample(50, 0)),
+                (10 + 2 * MAXIMAL_GAP_IN_SECONDS, sample(100, 0)),
+            ]),
+            Err(ImportError::GapTooLong)
+        );
+    }
+    #[test]
+    fn utc_parse() {
+        assert_eq!(parse_utc("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_utc("2024-03-01T06:30:00.000Z"), Some(1_709_274_600));
+        assert_eq!(parse_utc("2024-03-01T07:30:00+01:00"), Some(1_709_274_600));
+        assert_eq!(parse_utc("yesterday"), None);
+    }
+    #[test]
+    fn utc_format() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(1_709_274_600), "2024-03-01T06:30:00Z");
+    }
+}
@@ -0,0 +1,340 @@
+use super::Recording;
+use crate::workout_data::effort::Effort;
+use crate::workout_data::workout::Workout;
+
+/// How a ride is cut into efforts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentationSettings {
+    /// Seconds of the centered moving average applied before cutting.
+    pub smoothing_window_in_seconds: usize,
+    /// No effort will be shorter than this, unless the ride is.
+    pub minimal_segment_in_seconds: usize,
+    /// How far the smoothed power may stray from an effort before it is cut.
+    pub tolerance_in_watts: f64,
+}
+
+impl Default for SegmentationSettings {
+    fn default() -> Self {
+        Self {
+            smoothing_window_in_seconds: 30,
+            minimal_segment_in_seconds: 60,
+            tolerance_in_watts: 25.0,
+        }
+    }
+}
+
+/// Turn a ride into a workout of steady and ramp efforts.
+/// Seconds without power count as zero watts.
+pub fn workout_from_recording(
+    recording: &Recording,
+    name: &str,
+    settings: &SegmentationSettings,
+) -> Workout {
+    let powers: Vec<f64> = recording
+        .samples()
+        .iter()
+        .map(|sample| sample.power_in_watts.map_or(0.0, f64::from))
+        .collect();
+    let smoothed = smooth(&powers, settings.smoothing_window_in_seconds);
+
+    let mut boundaries = vec![0, smoothed.len()];
+    split(
+        &smoothed,
+        &PrefixSums::new(&smoothed),
+        0,
+        smoothed.len(),
+        settings,
+        &mut boundaries,
+    );
+    boundaries.sort_unstable();
+
+    let efforts = merge_steady_efforts(
+        boundaries
+            .windows(2)
+            .filter(|segment| segment[1] > segment[0])
+            .map(|segment| segment_to_effort(&smoothed[segment[0]..segment[1]], settings))
+            .collect(),
+        settings.tolerance_in_watts,
+    );
+    Workout::new(name, "Generated from a ride", efforts)
+}
+
+/// Centered moving average, shrinking the window at both ends.
+pub fn smooth(powers: &[f64], window: usize) -> Vec<f64> {
+    let half_window = window / 2;
+    (0..powers.len())
+        .map(|second| {
+            let first = second.saturating_sub(half_window);
+            let last = (second + half_window + 1).min(powers.len());
+            powers[first..last].iter().sum::<f64>() / (last - first) as f64
+        })
+        .collect()
+}
+
+/// Recursively cut `powers[first..last]` while a straight line does not fit it within
+/// the tolerance. The cut is placed where two straight lines fit best,
+/// as long as both parts stay long enough.
+fn split(
+    powers: &[f64],
+    sums: &PrefixSums,
+    first: usize,
+    last: usize,
+    settings: &SegmentationSettings,
+    boundaries: &mut Vec<usize>,
+) {
+    let minimal_length = settings.minimal_segment_in_seconds.max(1);
+    if last - first < 2 * minimal_length
+        || LinearFit::new(&powers[first..last]).maximal_deviation(&powers[first..last])
+            <= settings.tolerance_in_watts
+    {
+        return;
+    }
+    let best_cut = (first + minimal_length..=last - minimal_length)
+        .map(|cut| {
+            let error = sums.squared_error(first, cut) + sums.squared_error(cut, last);
+            (cut, error)
+        })
+        .min_by(|(_, first), (_, second)| first.total_cmp(second))
+        .map(|(cut, _)| cut)
+        .expect("The segment is at least twice the minimal length.");
+
+    boundaries.push(best_cut);
+    split(powers, sums, first, best_cut, settings, boundaries);
+    split(powers, sums, best_cut, last, settings, boundaries);
+}
+
+/// Running sums to get the error of a least squares line over any range in constant time.
+struct PrefixSums {
+    seconds: Vec<f64>,
+    squared_seconds: Vec<f64>,
+    powers: Vec<f64>,
+    seconds_times_powers: Vec<f64>,
+    squared_powers: Vec<f64>,
+}
+
+impl PrefixSums {
+    fn new(powers: &[f64]) -> Self {
+        let seconds = || (0..powers.len()).map(|second| second as f64);
+        Self {
+            seconds: running_sum(seconds()),
+            squared_seconds: running_sum(seconds().map(|second| second * second)),
+            powers: running_sum(powers.iter().copied()),
+            seconds_times_powers: running_sum(
+                seconds().zip(powers).map(|(second, power)| second * power),
+            ),
+            squared_powers: running_sum(powers.iter().map(|power| power * power)),
+        }
+    }
+    fn squared_error(&self, first: usize, last: usize) -> f64 {
+        let sum = |values: &[f64]| values[last] - values[first];
+        let count = (last - first) as f64;
+        let variance = sum(&self.squared_seconds) - sum(&self.seconds).powi(2) / count;
+        let covariance =
+            sum(&self.seconds_times_powers) - sum(&self.seconds) * sum(&self.powers) / count;
+        let power_variance = sum(&self.squared_powers) - sum(&self.powers).powi(2) / count;
+        if variance > 0.0 {
+            (power_variance - covariance * covariance / variance).max(0.0)
+        } else {
+            power_variance.max(0.0)
+        }
+    }
+}
+
+fn running_sum(values: impl Iterator<Item = f64>) -> Vec<f64> {
+    std::iter::once(0.0)
+        .chain(values.scan(0.0, |sum, value| {
+            *sum += value;
+            Some(*sum)
+        }))
+        .collect()
+}
+
+/// Least squares line through powers, one per second.
+struct LinearFit {
+    starting_value: f64,
+    slope: f64,
+}
+
+impl LinearFit {
+    fn new(powers: &[f64]) -> Self {
+        let count = powers.len() as f64;
+        let mean_second = (count - 1.0) / 2.0;
+        let mean_power = powers.iter().sum::<f64>() / count;
+        let (covariance, variance) = powers.iter().enumerate().fold(
+            (0.0, 0.0),
+            |(covariance, variance), (second, power)| {
+                let distance = second as f64 - mean_second;
+                (
+                    covariance + distance * (power - mean_power),
+                    variance + distance * distance,
+                )
+            },
+        );
+        let slope = if variance > 0.0 {
+            covariance / variance
+        } else {
+            0.0
+        };
+        Self {
+            starting_value: mean_power - slope * mean_second,
+            slope,
+        }
+    }
+    fn value_at(&self, second: usize) -> f64 {
+        self.starting_value + self.slope * second as f64
+    }
+    fn maximal_deviation(&self, powers: &[f64]) -> f64 {
+        powers
+            .iter()
+            .enumerate()
+            .map(|(second, power)| (power - self.value_at(second)).abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+/// A steady effort at the mean, or a ramp along the least squares line
+/// if that line rises or falls by more than the tolerance.
+fn segment_to_effort(powers: &[f64], settings: &SegmentationSettings) -> Effort {
+    let duration_in_minutes = powers.len() as f64 / 60.0;
+    let fit = LinearFit::new(powers);
+    let starting_value = fit.value_at(0).max(0.0).round();
+    let ending_value = fit.value_at(powers.len() - 1).max(0.0).round();
+
+    if (ending_value - starting_value).abs() > settings.tolerance_in_watts {
+        Effort::new(duration_in_minutes, starting_value, Some(ending_value))
+    } else {
+        let mean_power = powers.iter().sum::<f64>() / powers.len() as f64;
+        Effort::new(duration_in_minutes, mean_power.round(), None)
+    }
+}
+
+/// Join neighbouring steady efforts whose wattages differ by less than the tolerance.
+fn merge_steady_efforts(efforts: Vec<Effort>, tolerance_in_watts: f64) -> Vec<Effort> {
+    let is_steady = |effort: &Effort| effort.starting_value() == effort.ending_value();
+    efforts
+        .into_iter()
+        .fold(Vec::new(), |mut merged: Vec<Effort>, effort| {
+            match merged.last_mut() {
+                Some(previous)
+                    if is_steady(previous)
+                        && is_steady(&effort)
+                        && (previous.starting_value() - effort.starting_value()).abs()
+                            < tolerance_in_watts =>
+                {
+                    let duration = previous.duration_in_minutes() + effort.duration_in_minutes();
+                    let wattage = (previous.starting_value() * previous.duration_in_minutes()
+                        + effort.starting_value() * effort.duration_in_minutes())
+                        / duration;
+                    *previous = Effort::new(duration, wattage.round(), None);
+                }
+                _ => merged.push(effort),
+            }
+            merged
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::activity::Sample;
+
+    fn ride(powers: impl Iterator<Item = u16>) -> Recording {
+        let mut recording = Recording::new(0);
+        for power in powers {
+            recording.record(Sample {
+                power_in_watts: Some(power),
+                ..Sample::default()
+            });
+        }
+        recording
+    }
+    fn settings() -> SegmentationSettings {
+        SegmentationSettings {
+            smoothing_window_in_seconds: 1,
+            ..SegmentationSettings::default()
+        }
+    }
+    fn values(workout: &Workout) -> Vec<(f64, f64, f64)> {
+        workout
+            .efforts()
+            .iter()
+            .map(|effort| {
+                (
+                    effort.duration_in_minutes(),
+                    effort.starting_value(),
+                    effort.ending_value(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn smoothing_averages_neighbours() {
+        assert_eq!(
+            smooth(&[0.0, 30.0, 60.0, 90.0], 3),
+            vec![15.0, 30.0, 60.0, 75.0]
+        );
+    }
+    #[test]
+    fn prefix_sums_match_fit() {
+        let powers = [100.0, 120.0, 90.0, 300.0, 310.0];
+        let sums = PrefixSums::new(&powers);
+        let fit = LinearFit::new(&powers[1..4]);
+        let squared_error: f64 = powers[1..4]
+            .iter()
+            .enumerate()
+            .map(|(second, power)| (power - fit.value_at(second)).powi(2))
+            .sum();
+        assert!((sums.squared_error(1, 4) - squared_error).abs() < 1e-6);
+        assert_eq!(sums.squared_error(3, 4), 0.0);
+    }
+    #[test]
+    fn steady_blocks() {
+        let recording = ride((0..300).map(|second| match second {
+            0..=119 => 150,
+            120..=239 => 300,
+            _ => 150,
+        }));
+        assert_eq!(
+            values(&workout_from_recording(&recording, "Race", &settings())),
+            vec![
+                (2.0, 150.0, 150.0),
+                (2.0, 300.0, 300.0),
+                (1.0, 150.0, 150.0)
+            ]
+        );
+    }
+    #[test]
+    fn ramp_is_kept_as_ramp() {
+        let recording = ride(0..240);
+        let workout = workout_from_recording(&recording, "Ramp", &settings());
+        assert_eq!(values(&workout), vec![(4.0, 0.0, 239.0)]);
+    }
+    #[test]
+    fn noise_within_tolerance_stays_one_effort() {
+        let recording = ride((0..600).map(|second| if second % 2 == 0 { 190 } else { 210 }));
+        assert_eq!(
+            values(&workout_from_recording(
+                &recording,
+                "Tempo",
+                &SegmentationSettings::default()
+            )),
+            vec![(10.0, 200.0, 200.0)]
+        );
+    }
+    #[test]
+    fn short_surges_respect_minimal_length() {
+        let recording = ride((0..300).map(|second| {
+            if (100..110).contains(&second) {
+                600
+            } else {
+                200
+            }
+        }));
+        let workout = workout_from_recording(&recording, "Surge", &settings());
+        assert!(workout
+            .efforts()
+            .iter()
+            .all(|effort| effort.duration_in_minutes() >= 1.0));
+    }
+}
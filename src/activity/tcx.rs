@@ -0,0 +1,194 @@
+use super::{format_utc, parse_utc, summarize, ImportError, Recording, Sample};
+use regex::Regex;
+use std::fmt::Write;
+
+/// Render the recording as Training Center XML.
+/// TCX has no place for target wattages, so they are left out.
+pub fn recording_to_tcx(recording: &Recording) -> String {
+    let start = recording.start_in_unix_seconds();
+    let laps: String = recording
+        .laps()
+        .iter()
+        .map(|lap| {
+            let lap_start = start + lap.first_sample as u64;
+            lap_to_tcx(lap_start, recording.lap_samples(lap))
+        })
+        .collect();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>
+<TrainingCenterDatabase xmlns=\"http://www.garmin.com/xmlschemas/TrainingCenterDatabase/v2\" xmlns:ns3=\"http://www.garmin.com/xmlschemas/ActivityExtension/v2\">
+<Activities>
+<Activity Sport=\"Biking\">
+<Id>{}</Id>
+{}</Activity>
+</Activities>
+</TrainingCenterDatabase>
+",
+        format_utc(start),
+        laps
+    )
+}
+
+fn lap_to_tcx(lap_start: u64, samples: &[Sample]) -> String {
+    let summary = summarize(samples);
+    let mut lap = format!(
+        "<Lap StartTime=\"{}\">\n<TotalTimeSeconds>{}</TotalTimeSeconds>\n<DistanceMeters>0</DistanceMeters>\n<Calories>0</Calories>\n",
+        format_utc(lap_start),
+        samples.len()
+    );
+    if let Some(average_heart_rate) = summary.average_heart_rate {
+        let _ = writeln!(
+            lap,
+            "<AverageHeartRateBpm><Value>{}</Value></AverageHeartRateBpm>",
+            average_heart_rate
+        );
+    }
+    if let Some(maximum_heart_rate) = summary.maximum_heart_rate {
+        let _ = writeln!(
+            lap,
+            "<MaximumHeartRateBpm><Value>{}</Value></MaximumHeartRateBpm>",
+            maximum_heart_rate
+        );
+    }
+    lap.push_str("<Intensity>Active</Intensity>\n");
+    if let Some(average_cadence) = summary.average_cadence {
+        let _ = writeln!(lap, "<Cadence>{}</Cadence>", average_cadence);
+    }
+    lap.push_str("<TriggerMethod>Time</TriggerMethod>\n<Track>\n");
+    for (second, sample) in samples.iter().enumerate() {
+        lap.push_str(&trackpoint_to_tcx(lap_start + second as u64, sample));
+    }
+    lap.push_str("</Track>\n");
+    if let Some(average_power) = summary.average_power {
+        let _ = writeln!(
+            lap,
+            "<Extensions><ns3:LX><ns3:AvgWatts>{}</ns3:AvgWatts></ns3:LX></Extensions>",
+            average_power
+        );
+    }
+    lap.push_str("</Lap>\n");
+    lap
+}
+
+fn trackpoint_to_tcx(time: u64, sample: &Sample) -> String {
+    let mut trackpoint = format!("<Trackpoint><Time>{}</Time>", format_utc(time));
+    if let Some(heart_rate) = sample.heart_rate {
+        let _ = write!(
+            trackpoint,
+            "<HeartRateBpm><Value>{}</Value></HeartRateBpm>",
+            heart_rate
+        );
+    }
+    if let Some(cadence) = sample.cadence_in_rpm {
+        let _ = write!(trackpoint, "<Cadence>{}</Cadence>", cadence);
+    }
+    if let Some(power) = sample.power_in_watts {
+        let _ = write!(
+            trackpoint,
+            "<Extensions><ns3:TPX><ns3:Watts>{}</ns3:Watts></ns3:TPX></Extensions>",
+            power
+        );
+    }
+    trackpoint.push_str("</Trackpoint>\n");
+    trackpoint
+}
+
+/// Read the trackpoints of a TCX file. Trackpoints without a time are skipped.
+pub fn tcx_to_recording(tcx: &str) -> Result<Recording, ImportError> {
+    if !tcx.contains("<TrainingCenterDatabase") {
+        return Err(ImportError::InvalidTcx);
+    }
+    let capture_trackpoint =
+        Regex::new(r"(?s)<Trackpoint>(.*?)</Trackpoint>").expect("This regex is valid.");
+    let capture_time = Regex::new(r"<Time>([^<]+)</Time>").expect("This regex is valid.");
+    let capture_heart_rate =
+        Regex::new(r"(?s)<HeartRateBpm>\s*<Value>(\d+)</Value>").expect("This regex is valid.");
+    let capture_cadence = Regex::new(r"<Cadence>(\d+)</Cadence>").expect("This regex is valid.");
+    let capture_watts = Regex::new(r"<(?:\w+:)?Watts>(\d+)</").expect("This regex is valid.");
+
+    let timed_samples = capture_trackpoint
+        .captures_iter(tcx)
+        .filter_map(|trackpoint| {
+            let trackpoint = &trackpoint[1];
+            let time = parse_utc(&capture_time.captures(trackpoint)?[1])?;
+            Some((
+                time,
+                Sample {
+                    power_in_watts: number_in(&capture_watts, trackpoint),
+                    cadence_in_rpm: number_in(&capture_cadence, trackpoint),
+                    heart_rate: number_in(&capture_heart_rate, trackpoint),
+                    ..Sample::default()
+                },
+            ))
+        })
+        .collect();
+    Recording::from_timed_samples(timed_samples)
+}
+
+fn number_in<T: std::str::FromStr>(regex: &Regex, trackpoint: &str) -> Option<T> {
+    regex
+        .captures(trackpoint)
+        .and_then(|captures| captures[1].parse().ok())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn recording() -> Recording {
+        let mut recording = Recording::new(1_709_274_600);
+        for second in 0..3 {
+            recording.record(Sample {
+                power_in_watts: Some(200 + second as u16),
+                cadence_in_rpm: Some(90),
+                heart_rate: Some(140),
+                target_in_watts: Some(200),
+                effort_index: Some(second / 2),
+            });
+        }
+        recording
+    }
+
+    #[test]
+    fn one_lap_per_effort() {
+        let tcx = recording_to_tcx(&recording());
+        assert_eq!(tcx.matches("<Lap ").count(), 2);
+        assert!(tcx.contains("<Lap StartTime=\"2024-03-01T06:30:02Z\">"));
+        assert_eq!(tcx.matches("<Trackpoint>").count(), 3);
+    }
+    #[test]
+    fn trackpoint_with_all_values() {
+        assert_eq!(
+            trackpoint_to_tcx(0, &recording().samples()[0]),
+            "<Trackpoint><Time>1970-01-01T00:00:00Z</Time><HeartRateBpm><Value>140</Value></HeartRateBpm><Cadence>90</Cadence><Extensions><ns3:TPX><ns3:Watts>200</ns3:Watts></ns3:TPX></Extensions></Trackpoint>\n"
+        );
+    }
+    #[test]
+    fn read_written_file() {
+        let read = tcx_to_recording(&recording_to_tcx(&recording())).unwrap();
+        assert_eq!(read.start_in_unix_seconds(), 1_709_274_600);
+        assert_eq!(
+            read.samples()[1],
+            Sample {
+                power_in_watts: Some(201),
+                cadence_in_rpm: Some(90),
+                heart_rate: Some(140),
+                ..Sample::default()
+            }
+        );
+    }
+    #[test]
+    fn reject_other_files() {
+        assert_eq!(
+            tcx_to_recording("time,power\n0,100"),
+            Err(ImportError::InvalidTcx)
+        );
+    }
+    #[test]
+    fn trackpoint_without_values() {
+        assert_eq!(
+            trackpoint_to_tcx(0, &Sample::default()),
+            "<Trackpoint><Time>1970-01-01T00:00:00Z</Time></Trackpoint>\n"
+        );
+    }
+}
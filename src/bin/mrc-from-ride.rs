@@ -0,0 +1,69 @@
+//! Turn a recorded ride into a workout in the MRC or plan format.
+use mrc_workout_creator::activity::segmentation::{workout_from_recording, SegmentationSettings};
+use mrc_workout_creator::activity::Recording;
+use std::path::Path;
+use std::process::ExitCode;
+
+const USAGE: &str = "Usage: mrc-from-ride [--smoothing <seconds>] [--minimal-segment <seconds>] \
+[--tolerance <watts>] <ride file (.fit, .tcx or .csv)> <workout file (.mrc, .plan, .json or .fit)>";
+
+fn main() -> ExitCode {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    match parse_arguments(&arguments).and_then(|(settings, ride_file, workout_file)| {
+        create_workout(Path::new(ride_file), Path::new(workout_file), &settings)
+    }) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_arguments(arguments: &[String]) -> Result<(SegmentationSettings, &str, &str), String> {
+    let mut settings = SegmentationSettings::default();
+    let mut files = Vec::new();
+    let mut arguments = arguments.iter();
+    while let Some(argument) = arguments.next() {
+        let mut value = || {
+            arguments
+                .next()
+                .and_then(|value| value.parse::<f64>().ok())
+                .filter(|value| *value > 0.0)
+                .ok_or(format!("{} needs a positive number.\n{}", argument, USAGE))
+        };
+        match argument.as_str() {
+            "--smoothing" => settings.smoothing_window_in_seconds = value()? as usize,
+            "--minimal-segment" => settings.minimal_segment_in_seconds = value()? as usize,
+            "--tolerance" => settings.tolerance_in_watts = value()?,
+            file => files.push(file),
+        }
+    }
+    match files.as_slice() {
+        [ride_file, workout_file] => Ok((settings, ride_file, workout_file)),
+        _ => Err(String::from(USAGE)),
+    }
+}
+
+fn create_workout(
+    ride_file: &Path,
+    workout_file: &Path,
+    settings: &SegmentationSettings,
+) -> Result<(), String> {
+    let recording = Recording::load(ride_file)
+        .map_err(|error| format!("Could not read {:?} because of: {:?}", ride_file, error))?;
+    let name = workout_file
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("ride");
+    let workout = workout_from_recording(&recording, name, settings);
+
+    let extension = workout_file
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+    let contents = workout
+        .to_file(extension, None)
+        .map_err(|error| format!("{:?}", error))?;
+    std::fs::write(workout_file, contents).map_err(|error| error.to_string())
+}
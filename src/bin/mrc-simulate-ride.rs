@@ -0,0 +1,38 @@
+//! Ride a workout on the simulated trainer and record it as activity file.
+use mrc_workout_creator::activity::Recording;
+use mrc_workout_creator::trainer::session::ride_workout;
+use mrc_workout_creator::trainer::simulated::SimulatedTrainer;
+use mrc_workout_creator::workout_data::workout::Workout;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn main() -> ExitCode {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+    let [workout_file, activity_file] = arguments.as_slice() else {
+        eprintln!("Usage: mrc-simulate-ride <workout file> <activity file (.fit or .tcx)>");
+        return ExitCode::FAILURE;
+    };
+
+    match simulate_ride(Path::new(workout_file), Path::new(activity_file)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Could not ride {} because of: {}", workout_file, error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn simulate_ride(workout_file: &Path, activity_file: &Path) -> Result<(), String> {
+    let workout = Workout::from_path(workout_file, None).map_err(|error| format!("{:?}", error))?;
+
+    let start = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|error| error.to_string())?
+        .as_secs();
+    let ride_data = ride_workout(&workout, SimulatedTrainer::default())
+        .map_err(|error| format!("{:?}", error))?;
+    Recording::from_ride(&workout, &ride_data, start)
+        .save(activity_file)
+        .map_err(|error| error.to_string())
+}
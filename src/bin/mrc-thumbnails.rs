@@ -0,0 +1,46 @@
+//! Render a PNG thumbnail next to every given workout file.
+use mrc_workout_creator::chart::export::{ChartOptions, PngRenderer};
+use mrc_workout_creator::workout_data::workout::Workout;
+use std::path::Path;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let files: Vec<String> = std::env::args().skip(1).collect();
+    if files.is_empty() {
+        eprintln!("Usage: mrc-thumbnails <workout files>...");
+        return ExitCode::FAILURE;
+    }
+
+    let renderer = PngRenderer::default();
+    let options = ChartOptions {
+        width: 320.0,
+        height: 180.0,
+        ..ChartOptions::default()
+    };
+    let mut all_rendered = true;
+    for file in files {
+        if let Err(error) = render_thumbnail(Path::new(&file), &renderer, &options) {
+            eprintln!("Could not render {} because of: {}", file, error);
+            all_rendered = false;
+        }
+    }
+
+    if all_rendered {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn render_thumbnail(
+    path: &Path,
+    renderer: &PngRenderer,
+    options: &ChartOptions,
+) -> Result<(), String> {
+    let workout = Workout::from_path(path, None).map_err(|error| format!("{:?}", error))?;
+
+    let png = renderer
+        .render(&workout, options)
+        .map_err(|error| format!("{:?}", error))?;
+    std::fs::write(path.with_extension("png"), png).map_err(|error| error.to_string())
+}
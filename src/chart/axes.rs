@@ -0,0 +1,130 @@
+/// Spacings between ticks on a time axis in minutes.
+static MINUTE_SPACINGS: [f32; 8] = [1.0, 2.0, 5.0, 10.0, 15.0, 30.0, 60.0, 120.0];
+
+/// Evenly spaced round values from 0 up to `max`,
+/// with at most `maximal_number_of_ticks` spaces in between.
+pub fn compute_ticks(max: f32, maximal_number_of_ticks: usize) -> Vec<f32> {
+    if max.is_nan() || max <= 0.0 || maximal_number_of_ticks == 0 {
+        return vec![];
+    }
+    ticks_with_spacing(max, round_spacing(max / maximal_number_of_ticks as f32))
+}
+
+/// Like `compute_ticks`, but prefers spacings that are
+/// common for minutes, e.g. quarters of an hour.
+pub fn compute_minute_ticks(max_minutes: f32, maximal_number_of_ticks: usize) -> Vec<f32> {
+    if max_minutes.is_nan() || max_minutes <= 0.0 || maximal_number_of_ticks == 0 {
+        return vec![];
+    }
+    let minimal_spacing = max_minutes / maximal_number_of_ticks as f32;
+    match MINUTE_SPACINGS
+        .iter()
+        .find(|&&spacing| spacing >= minimal_spacing)
+    {
+        Some(&spacing) => ticks_with_spacing(max_minutes, spacing),
+        None => compute_ticks(max_minutes, maximal_number_of_ticks),
+    }
+}
+
+/// Axis maximum used when there is nothing to show.
+const EMPTY_AXIS_MAXIMUM: f32 = 100.0;
+/// Space left above the highest value.
+const HEADROOM: f32 = 1.1;
+
+/// Upper end of an axis showing values up to `max`. Leaves some
+/// headroom and ends on a tick so the top of the axis is labelled.
+pub fn axis_maximum(max: f32, maximal_number_of_ticks: usize) -> f32 {
+    if max.is_nan() || max <= 0.0 || maximal_number_of_ticks == 0 {
+        return EMPTY_AXIS_MAXIMUM;
+    }
+    let max_with_headroom = max * HEADROOM;
+    let spacing = round_spacing(max_with_headroom / maximal_number_of_ticks as f32);
+    (max_with_headroom / spacing).ceil() * spacing
+}
+
+fn ticks_with_spacing(max: f32, spacing: f32) -> Vec<f32> {
+    (0..)
+        .map(|index| index as f32 * spacing)
+        .take_while(|&tick| tick <= max)
+        .collect()
+}
+
+/// The smallest value of the form 1, 2 or 5 times a power
+/// of ten that is at least `minimal_spacing`.
+fn round_spacing(minimal_spacing: f32) -> f32 {
+    let magnitude = 10.0_f32.powf(minimal_spacing.log10().floor());
+    let normalized = minimal_spacing / magnitude;
+    let round_normalized = if normalized <= 1.0 {
+        1.0
+    } else if normalized <= 2.0 {
+        2.0
+    } else if normalized <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    round_normalized * magnitude
+}
+
+/// Render minutes as `h:mm` once they pass the hour.
+pub fn format_minutes(minutes: f32) -> String {
+    let rounded_minutes = minutes.round() as i64;
+    if rounded_minutes < 60 {
+        format!("{}", rounded_minutes)
+    } else {
+        format!("{}:{:02}", rounded_minutes / 60, rounded_minutes % 60)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn power_ticks() {
+        assert_eq!(
+            compute_ticks(430.0, 5),
+            vec![0.0, 100.0, 200.0, 300.0, 400.0]
+        )
+    }
+    #[test]
+    fn small_power_ticks() {
+        assert_eq!(compute_ticks(9.0, 4), vec![0.0, 5.0])
+    }
+    #[test]
+    fn no_ticks_without_range() {
+        assert!(compute_ticks(f32::NAN, 5).is_empty());
+        assert!(compute_ticks(0.0, 5).is_empty());
+    }
+    #[test]
+    fn minute_ticks_use_quarter_hours() {
+        assert_eq!(
+            compute_minute_ticks(60.0, 5),
+            vec![0.0, 15.0, 30.0, 45.0, 60.0]
+        )
+    }
+    #[test]
+    fn minute_ticks_of_long_workout() {
+        assert_eq!(compute_minute_ticks(1000.0, 4), vec![0.0, 500.0, 1000.0])
+    }
+    #[test]
+    fn axis_maximum_ends_on_tick_above_max() {
+        assert_eq!(axis_maximum(300.0, 5), 400.0);
+        assert_eq!(axis_maximum(430.0, 10), 500.0);
+    }
+    #[test]
+    fn axis_maximum_of_nothing() {
+        assert_eq!(axis_maximum(f32::NAN, 5), EMPTY_AXIS_MAXIMUM);
+    }
+    #[test]
+    fn round_spacings() {
+        assert_eq!(round_spacing(0.3), 0.5);
+        assert_eq!(round_spacing(13.0), 20.0);
+        assert_eq!(round_spacing(70.0), 100.0);
+    }
+    #[test]
+    fn format_minutes_below_and_above_an_hour() {
+        assert_eq!(format_minutes(45.0), "45");
+        assert_eq!(format_minutes(90.0), "1:30");
+    }
+}
@@ -0,0 +1,203 @@
+use super::axes::format_minutes;
+use super::export::{escape_xml, workout_to_svg, ChartOptions};
+use super::layout::ChartSettings;
+use crate::workout_data::effort::Effort;
+use crate::workout_data::workout::{extract_initial_starting_minutes, Workout};
+
+static PRINT_STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #111; }
+h1 { margin-bottom: 0.2em; }
+.description { color: #444; margin-top: 0; }
+.chart svg { width: 100%; height: auto; }
+.summary { display: flex; gap: 2em; margin: 1em 0; }
+.summary div { font-size: 1.1em; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #999; padding: 0.3em 0.6em; text-align: left; }
+th { background: #eee; }
+tr { page-break-inside: avoid; }
+@media print {
+  body { margin: 0; }
+  @page { size: A4 portrait; margin: 1.5cm; }
+}
+";
+
+/// Render a printable card of the workout as HTML document.
+/// With an `ftp`, targets are given in percent of it instead of watts.
+pub fn workout_to_html(workout: &Workout, ftp: Option<f64>) -> String {
+    let chart = workout_to_svg(
+        workout,
+        &ChartOptions {
+            width: 1000.0,
+            height: 300.0,
+            settings: ChartSettings {
+                ftp,
+                show_zone_bands: ftp.is_some(),
+                power_in_percent_of_ftp: ftp.is_some(),
+                ..ChartSettings::default()
+            },
+            ..ChartOptions::default()
+        },
+    );
+    format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{name}</title>
+<style>{style}</style>
+</head>
+<body>
+<h1>{name}</h1>
+<p class=\"description\">{description}</p>
+<div class=\"chart\">
+{chart}</div>
+<div class=\"summary\">
+{summary}
+</div>
+<table>
+<tr><th>Start</th><th>Duration</th><th>Target</th><th>Cadence</th><th>Cue</th></tr>
+{rows}
+</table>
+</body>
+</html>
+",
+        name = escape_xml(workout.name()),
+        style = PRINT_STYLE,
+        description = escape_xml(workout.description()),
+        chart = chart,
+        summary = summary_metrics(workout, ftp)
+            .iter()
+            .map(|(label, value)| format!("<div><b>{}</b>: {}</div>", label, value))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        rows = interval_rows(workout, ftp).join("\n"),
+    )
+}
+
+/// Key figures of the workout, shown on its card and when comparing workouts.
+pub(crate) fn summary_metrics(workout: &Workout, ftp: Option<f64>) -> Vec<(&'static str, String)> {
+    let mut metrics = vec![
+        (
+            "Duration",
+            format_minutes(workout.total_time_of_workout() as f32),
+        ),
+        (
+            "Average Wattage",
+            format!("{:.0} W", workout.average_intensity()),
+        ),
+        (
+            "Work",
+            format!(
+                "{:.0} kJ",
+                workout.average_intensity() * workout.total_time_of_workout() * 60.0 / 1000.0
+            ),
+        ),
+    ];
+    if let Some(ftp) = ftp {
+        metrics.push((
+            "Average Intensity",
+            format!("{:.0}% FTP", workout.average_intensity() / ftp * 100.0),
+        ));
+    }
+    metrics
+}
+
+fn interval_rows(workout: &Workout, ftp: Option<f64>) -> Vec<String> {
+    workout
+        .efforts()
+        .iter()
+        .zip(extract_initial_starting_minutes(&workout.efforts, 0.0))
+        .map(|(effort, starting_minute)| {
+            format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                format_clock(starting_minute),
+                format_clock(effort.duration_in_minutes()),
+                format_target(effort, ftp),
+                effort
+                    .cadence()
+                    .map(|cadence| format!("{} rpm", cadence))
+                    .unwrap_or_default(),
+                escape_xml(effort.cue().unwrap_or_default()),
+            )
+        })
+        .collect()
+}
+
+fn format_target(effort: &Effort, ftp: Option<f64>) -> String {
+    let format_value = |value: f64| match ftp {
+        Some(ftp) => format!("{:.0}%", value / ftp * 100.0),
+        None => format!("{:.0} W", value),
+    };
+    if let Some(range) = effort.target_range() {
+        format!(
+            "{} &ndash; {}",
+            format_value(range.low),
+            format_value(range.high)
+        )
+    } else if effort.starting_value() == effort.ending_value() {
+        format_value(effort.starting_value())
+    } else {
+        format!(
+            "{} &rarr; {}",
+            format_value(effort.starting_value()),
+            format_value(effort.ending_value())
+        )
+    }
+}
+
+/// Render minutes as `mm:ss`.
+fn format_clock(minutes: f64) -> String {
+    let seconds = (minutes * 60.0).round() as i64;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workout_data::test_workout;
+
+    #[test]
+    fn card_contains_chart_and_rows() {
+        let html = workout_to_html(&test_workout(), None);
+        assert!(html.contains("<svg"));
+        assert!(html.contains("@media print"));
+        assert_eq!(html.matches("<tr><td>").count(), 5);
+        assert!(html.contains(
+            "<tr><td>10:00</td><td>5:00</td><td>300 W</td><td>95 rpm</td><td>Go hard</td></tr>"
+        ));
+    }
+    #[test]
+    fn card_escapes_name() {
+        assert!(
+            workout_to_html(&test_workout(), None).contains("<h1>Over &amp; &lt;Under&gt;</h1>")
+        );
+    }
+    #[test]
+    fn ramp_target_in_percent_of_ftp() {
+        assert_eq!(
+            format_target(&Effort::new(10.0, 100.0, Some(200.0)), Some(250.0)),
+            "40% &rarr; 80%"
+        );
+    }
+    #[test]
+    fn target_range_in_watts() {
+        assert_eq!(
+            format_target(
+                &Effort::new(10.0, 182.0, None).with_target_range(176.0, 188.0),
+                None
+            ),
+            "176 W &ndash; 188 W"
+        );
+    }
+    #[test]
+    fn summary_with_ftp() {
+        let metrics = summary_metrics(&test_workout(), Some(250.0));
+        assert_eq!(metrics.len(), 4);
+        assert_eq!(metrics[0], ("Duration", String::from("27")));
+    }
+    #[test]
+    fn clock_format() {
+        assert_eq!(format_clock(0.5), "0:30");
+        assert_eq!(format_clock(75.25), "75:15");
+    }
+}
@@ -0,0 +1,290 @@
+use super::geometry::Color;
+use crate::workout_data::effort::Effort;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+static COLOR_GRADIENT: [&str; 40] = [
+    "#0c0af0", "#0d12e7", "#0e1adf", "#0f23d7", "#102bce", "#1134c6", "#123cbe", "#1344b6",
+    "#154dad", "#1655a5", "#175e9d", "#186694", "#196e8c", "#1a7784", "#1b7f7c", "#1d8873",
+    "#1e906b", "#1f9963", "#20a15b", "#21a952", "#22b24a", "#23ba42", "#25c339", "#26cb31",
+    "#27d329", "#28dc21", "#29e418", "#2aed10", "#2bf508", "#2dfe00", "#41e400", "#56cb00",
+    "#6bb100", "#809800", "#957f00", "#aa6500", "#bf4c00", "#d43200", "#e91900", "#fe0000",
+];
+/// The viridis color map, which stays readable with any kind of colour blindness.
+static COLOR_BLIND_SAFE_GRADIENT: [&str; 9] = [
+    "#440154", "#472d7b", "#3b528b", "#2c728e", "#21918c", "#28ae80", "#5ec962", "#addc30",
+    "#fde725",
+];
+static MAX_WATTAGE: f64 = 500.0;
+
+/// Upper bounds of the classic power zones as fraction of the FTP.
+static POWER_ZONES: [f64; 6] = [0.55, 0.75, 0.90, 1.05, 1.20, f64::INFINITY];
+static CLASSIC_ZONE_COLORS: [&str; 6] = [
+    "#7f7f7f", "#338cff", "#59bf59", "#ffcc33", "#ff6633", "#e91900",
+];
+/// Colors of the Okabe-Ito palette, told apart with any kind of colour blindness.
+static COLOR_BLIND_SAFE_ZONE_COLORS: [&str; 6] = [
+    "#999999", "#56b4e9", "#009e73", "#f0e442", "#e69f00", "#d55e00",
+];
+
+/// The colors power zones are shaded in and efforts are filled with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneColors {
+    /// Blue over green to red.
+    #[default]
+    Classic,
+    ColorBlindSafe,
+}
+
+impl ZoneColors {
+    pub const ALL: [ZoneColors; 2] = [ZoneColors::Classic, ZoneColors::ColorBlindSafe];
+
+    fn gradient(self) -> &'static [&'static str] {
+        match self {
+            ZoneColors::Classic => &COLOR_GRADIENT,
+            ZoneColors::ColorBlindSafe => &COLOR_BLIND_SAFE_GRADIENT,
+        }
+    }
+    fn zones(self) -> &'static [&'static str; 6] {
+        match self {
+            ZoneColors::Classic => &CLASSIC_ZONE_COLORS,
+            ZoneColors::ColorBlindSafe => &COLOR_BLIND_SAFE_ZONE_COLORS,
+        }
+    }
+}
+
+impl fmt::Display for ZoneColors {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            ZoneColors::Classic => "Classic Colors",
+            ZoneColors::ColorBlindSafe => "Color-Blind Safe",
+        })
+    }
+}
+
+/// A range of wattage belonging to one power zone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZoneBand {
+    pub lower_wattage: f64,
+    pub upper_wattage: f64,
+    pub color: Color,
+}
+
+/// The power zones of a rider with the given FTP.
+pub fn zone_bands(ftp: f64, colors: ZoneColors) -> Vec<ZoneBand> {
+    std::iter::once(0.0)
+        .chain(POWER_ZONES.iter().copied())
+        .zip(POWER_ZONES.iter().zip(colors.zones()))
+        .map(|(lower_bound, (upper_bound, color))| ZoneBand {
+            lower_wattage: lower_bound * ftp,
+            upper_wattage: upper_bound * ftp,
+            color: color_from_hex(color),
+        })
+        .collect()
+}
+
+pub(super) fn color_from_hex(hex: &str) -> Color {
+    let hex_values = &hex[1..].chars().collect::<Vec<char>>();
+    let red = u8::from_str_radix(&hex_values[0..2].iter().collect::<String>(), 16)
+        .ok()
+        .unwrap() as f32;
+    let green = u8::from_str_radix(&hex_values[2..4].iter().collect::<String>(), 16)
+        .ok()
+        .unwrap() as f32;
+    let blue = u8::from_str_radix(&hex_values[4..6].iter().collect::<String>(), 16)
+        .ok()
+        .unwrap() as f32;
+
+    Color {
+        r: red / 255.0,
+        g: green / 255.0,
+        b: blue / 255.0,
+        a: 1.0,
+    }
+}
+
+impl Effort {
+    fn average_wattage(&self) -> f64 {
+        (self.starting_value + self.ending_value) / 2.0
+    }
+
+    /// The custom color of the effort, or else the one of its wattage.
+    pub fn to_color(&self, colors: ZoneColors) -> Color {
+        if let Some(color) = self.color() {
+            return Color::from_rgb8(color.red, color.green, color.blue);
+        }
+        let (color_before, color_after) =
+            select_colors_from_gradients(colors.gradient(), self.average_wattage());
+        interpolate_colors(color_before, color_after)
+    }
+}
+
+fn interpolate_colors(first: Color, second: Color) -> Color {
+    Color {
+        r: (first.r + second.r) / 2.0,
+        g: (first.g + second.g) / 2.0,
+        b: (first.b + second.b) / 2.0,
+        a: (first.a + second.a) / 2.0,
+    }
+}
+
+fn select_color_string_from_gradients<'a>(
+    gradient: &[&'a str],
+    wattage: f64,
+) -> (&'a str, &'a str) {
+    let percent_of_max_wattage = min(wattage / MAX_WATTAGE, 1.0);
+    let max_index_color_gradient = (gradient.len() - 1) as f64;
+
+    let color_before =
+        gradient[(percent_of_max_wattage * max_index_color_gradient).floor() as usize];
+
+    let color_after = gradient[(percent_of_max_wattage * max_index_color_gradient).ceil() as usize];
+
+    (color_before, color_after)
+}
+
+fn select_colors_from_gradients(gradient: &[&str], wattage: f64) -> (Color, Color) {
+    let (color_before, color_after) = select_color_string_from_gradients(gradient, wattage);
+    (color_from_hex(color_before), color_from_hex(color_after))
+}
+
+fn min(a: f64, b: f64) -> f64 {
+    if a > b {
+        b
+    } else {
+        a
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod color_from_hex {
+        use super::*;
+
+        #[test]
+        fn red() {
+            assert_eq!(color_from_hex("#ff0000"), Color::from_rgb8(255, 0, 0));
+        }
+        #[test]
+        fn blue() {
+            assert_eq!(color_from_hex("#2e00ff"), Color::from_rgb8(46, 0, 255));
+        }
+        #[test]
+        fn greenish() {
+            assert_eq!(color_from_hex("#0df114"), Color::from_rgb8(13, 241, 20));
+        }
+    }
+
+    mod effort {
+        use super::*;
+        #[test]
+        fn average_wattage() {
+            assert_eq!(Effort::new(0.0, 100.0, Some(60.0)).average_wattage(), 80.0)
+        }
+        #[test]
+        fn custom_color() {
+            let effort = Effort::new(1.0, 100.0, None).with_color("#ff8800".parse().unwrap());
+            assert_eq!(
+                effort.to_color(ZoneColors::Classic),
+                Color::from_rgb8(255, 136, 0)
+            );
+        }
+    }
+
+    #[test]
+    fn zone_bands_are_adjacent() {
+        let bands = zone_bands(200.0, ZoneColors::Classic);
+        assert_eq!(bands.len(), 6);
+        assert_eq!(bands[0].lower_wattage, 0.0);
+        assert!((bands[1].lower_wattage - 110.0).abs() < 1e-9);
+        assert!(bands
+            .windows(2)
+            .all(|pair| pair[0].upper_wattage == pair[1].lower_wattage));
+        assert_eq!(bands[5].upper_wattage, f64::INFINITY);
+    }
+
+    #[test]
+    fn color_blind_safe_zones_differ_from_classic_ones() {
+        let classic = zone_bands(200.0, ZoneColors::Classic);
+        let color_blind_safe = zone_bands(200.0, ZoneColors::ColorBlindSafe);
+        assert!(classic
+            .iter()
+            .zip(&color_blind_safe)
+            .all(|(classic, color_blind_safe)| classic.upper_wattage
+                == color_blind_safe.upper_wattage
+                && classic.color != color_blind_safe.color));
+    }
+    #[test]
+    fn color_blind_safe_gradient_ends_in_yellow() {
+        assert_eq!(
+            select_color_string_from_gradients(&COLOR_BLIND_SAFE_GRADIENT, 600.0),
+            ("#fde725", "#fde725")
+        );
+    }
+
+    #[test]
+    fn minimum() {
+        assert_eq!(min(1.0, 2.0), 1.0)
+    }
+
+    #[test]
+    fn interpolate_between_red_and_green() {
+        let red = Color {
+            r: 255.0,
+            g: 0.0,
+            b: 0.0,
+            a: 0.0,
+        };
+        let green = Color {
+            r: 0.0,
+            g: 255.0,
+            b: 0.0,
+            a: 0.0,
+        };
+
+        assert_eq!(
+            interpolate_colors(red, green),
+            Color {
+                r: 127.5,
+                g: 127.5,
+                b: 0.0,
+                a: 0.0,
+            }
+        )
+    }
+
+    mod select_color {
+        use super::*;
+        #[test]
+        fn select_first_color() {
+            assert_eq!(
+                select_color_string_from_gradients(&COLOR_GRADIENT, 0.0),
+                ("#0c0af0", "#0c0af0")
+            );
+        }
+        #[test]
+        fn select_first_and_second() {
+            assert_eq!(
+                select_color_string_from_gradients(&COLOR_GRADIENT, 1.0),
+                ("#0c0af0", "#0d12e7")
+            );
+        }
+        #[test]
+        fn select_last() {
+            assert_eq!(
+                select_color_string_from_gradients(&COLOR_GRADIENT, 500.0),
+                ("#fe0000", "#fe0000")
+            );
+        }
+        #[test]
+        fn select_middle() {
+            assert_eq!(
+                select_color_string_from_gradients(&COLOR_GRADIENT, 201.0),
+                ("#1d8873", "#1e906b")
+            );
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::workout_data::effort::Effort;
+use std::borrow::Cow;
+
+/// Efforts to draw in place of `efforts` when there is only room for about `room` of them.
+/// Runs of efforts too short to be seen are merged into one steady effort at their
+/// average power, while efforts long enough are kept as they are.
+/// At most `2 * room + 1` efforts are returned.
+pub fn decimate(efforts: &[Effort], room: usize) -> Cow<'_, [Effort]> {
+    if efforts.len() <= room {
+        return Cow::Borrowed(efforts);
+    }
+    let total_minutes: f64 = efforts
+        .iter()
+        .map(|effort| effort.duration_in_minutes)
+        .sum();
+    let shortest_minutes = total_minutes / room.max(1) as f64;
+    let mut decimated = Vec::with_capacity(2 * room + 1);
+    let mut run = Run::default();
+    for effort in efforts {
+        if effort.duration_in_minutes >= shortest_minutes {
+            run.flush_into(&mut decimated);
+            decimated.push(effort.clone());
+            continue;
+        }
+        run.push(effort);
+        if run.duration_in_minutes >= shortest_minutes {
+            run.flush_into(&mut decimated);
+        }
+    }
+    run.flush_into(&mut decimated);
+    Cow::Owned(decimated)
+}
+
+/// Consecutive short efforts waiting to be merged.
+#[derive(Default)]
+struct Run<'a> {
+    first: Option<&'a Effort>,
+    length: usize,
+    duration_in_minutes: f64,
+    /// Sum of the average wattages weighted by the durations.
+    work: f64,
+}
+
+impl<'a> Run<'a> {
+    fn push(&mut self, effort: &'a Effort) {
+        self.first.get_or_insert(effort);
+        self.length += 1;
+        self.duration_in_minutes += effort.duration_in_minutes;
+        self.work +=
+            effort.duration_in_minutes * (effort.starting_value + effort.ending_value) / 2.0;
+    }
+    fn flush_into(&mut self, decimated: &mut Vec<Effort>) {
+        match (self.first, self.length) {
+            (Some(first), 1) => decimated.push(first.clone()),
+            (Some(_), _) if self.duration_in_minutes > 0.0 => decimated.push(Effort::new(
+                self.duration_in_minutes,
+                self.work / self.duration_in_minutes,
+                None,
+            )),
+            _ => {}
+        }
+        *self = Run::default();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn ride(seconds: usize) -> Vec<Effort> {
+        (0..seconds)
+            .map(|second| Effort::new(1.0 / 60.0, 100.0 + (second % 7) as f64 * 10.0, None))
+            .collect()
+    }
+
+    #[test]
+    fn few_efforts_are_kept() {
+        let efforts = ride(10);
+        assert!(matches!(decimate(&efforts, 10), Cow::Borrowed(_)));
+    }
+    #[test]
+    fn short_efforts_are_merged() {
+        let efforts = ride(4 * 3600);
+        let decimated = decimate(&efforts, 500);
+        assert!(decimated.len() <= 2 * 500 + 1);
+        let minutes = |efforts: &[Effort]| -> f64 {
+            efforts
+                .iter()
+                .map(|effort| effort.duration_in_minutes)
+                .sum()
+        };
+        assert!((minutes(&decimated) - minutes(&efforts)).abs() < 1e-6);
+        let work = |efforts: &[Effort]| -> f64 {
+            efforts
+                .iter()
+                .map(|effort| effort.duration_in_minutes * effort.starting_value)
+                .sum()
+        };
+        assert!((work(&decimated) - work(&efforts)).abs() < 1e-6);
+    }
+    #[test]
+    fn long_efforts_are_kept() {
+        let mut efforts = ride(600);
+        efforts.insert(300, Effort::new(20.0, 300.0, Some(350.0)));
+        let decimated = decimate(&efforts, 10);
+        assert!(decimated.contains(&Effort::new(20.0, 300.0, Some(350.0))));
+        assert!(decimated.len() <= 21);
+    }
+}
@@ -0,0 +1,406 @@
+use super::decimation::decimate;
+use super::geometry::{Color, Point, Rectangle, Size};
+use super::layout::{
+    compute_boxes_for_efforts, room_for_efforts, summary_lines, target_band_color, ChartLayout,
+    ChartSettings, LABEL_SIZE, MARGIN_LEFT, SUMMARY_SIZE,
+};
+use super::palette::Palette;
+use crate::workout_data::workout::Workout;
+#[cfg(feature = "render")]
+use resvg::{tiny_skia, usvg};
+
+/// How to render a workout chart outside of the GUI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartOptions {
+    pub width: f32,
+    pub height: f32,
+    pub settings: ChartSettings,
+    pub palette: Palette,
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        Self {
+            width: 1200.0,
+            height: 400.0,
+            settings: ChartSettings::default(),
+            palette: Palette::default(),
+        }
+    }
+}
+
+#[cfg(feature = "render")]
+#[derive(Debug, PartialEq)]
+pub enum RenderImageError {
+    InvalidSvg(String),
+    InvalidSize,
+    Encoding(String),
+}
+
+/// Render the chart of a workout as SVG document.
+pub fn workout_to_svg(workout: &Workout, options: &ChartOptions) -> String {
+    let efforts = decimate(
+        &workout.efforts,
+        room_for_efforts(options.width - MARGIN_LEFT),
+    );
+    let layout = ChartLayout::new(
+        Size::new(options.width, options.height),
+        &efforts,
+        options.settings.ftp,
+    );
+    let palette = &options.palette;
+
+    let mut elements = vec![svg_rectangle(
+        Point::ORIGIN,
+        Size::new(options.width, options.height),
+        palette.chart_background,
+    )];
+    elements.extend(layout.zone_bands(&options.settings).into_iter().map(
+        |(top, bottom, color)| {
+            svg_rectangle(
+                Point::new(MARGIN_LEFT, top),
+                Size::new(layout.plot.width, bottom - top),
+                color,
+            )
+        },
+    ));
+    elements.extend(power_axis(&layout, &options.settings, palette));
+    elements.extend(
+        compute_boxes_for_efforts(
+            &Rectangle::with_size(layout.plot),
+            &efforts,
+            layout.axis_maximum,
+            options.settings.zone_colors,
+        )
+        .into_iter()
+        .map(|(shape, color)| svg_polygon(&shape.corners(), color)),
+    );
+    elements.extend(layout.target_bands(&efforts).into_iter().map(|band| {
+        format!(
+            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" fill-opacity="{}" stroke="{}"/>"#,
+            MARGIN_LEFT + band.x,
+            band.y,
+            band.width,
+            band.height,
+            svg_color(palette.text),
+            target_band_color(palette).a,
+            svg_color(palette.text),
+        )
+    }));
+    elements.extend(
+        layout
+            .effort_labels(&efforts)
+            .into_iter()
+            .map(|(position, name)| {
+                svg_text(
+                    &name,
+                    Point::new(MARGIN_LEFT + position.x, position.y + LABEL_SIZE / 2.0),
+                    "middle",
+                    palette.text,
+                    LABEL_SIZE,
+                )
+            }),
+    );
+    elements.extend(time_axis(&layout, palette));
+    if let Some(y) = layout.ftp_line(&options.settings) {
+        elements.push(format!(
+            r#"<line x1="{:.2}" y1="{y:.2}" x2="{:.2}" y2="{y:.2}" stroke="{}" stroke-width="2"/>"#,
+            MARGIN_LEFT,
+            MARGIN_LEFT + layout.plot.width,
+            svg_color(palette.accent),
+        ));
+    }
+    elements.push(format!(
+        r#"<rect x="1.5" y="1.5" width="{}" height="{}" fill="none" stroke="{}" stroke-width="3"/>"#,
+        options.width - 3.0,
+        options.height - 3.0,
+        svg_color(palette.highlight)
+    ));
+    for (line, summary) in summary_lines(workout).iter().enumerate() {
+        elements.push(svg_text(
+            summary,
+            Point::new(
+                options.width * 0.82,
+                (line + 1) as f32 * SUMMARY_SIZE * 1.25,
+            ),
+            "middle",
+            palette.accent,
+            SUMMARY_SIZE,
+        ));
+    }
+
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="monospace">
+{}
+</svg>
+"#,
+        elements.join("\n"),
+        width = options.width,
+        height = options.height,
+    )
+}
+
+/// Render the chart of a workout as PNG image.
+/// Keep the renderer around when rendering many workouts,
+/// as loading the system fonts is expensive.
+#[cfg(feature = "render")]
+pub struct PngRenderer {
+    svg_options: usvg::Options<'static>,
+}
+
+#[cfg(feature = "render")]
+impl Default for PngRenderer {
+    fn default() -> Self {
+        let mut svg_options = usvg::Options::default();
+        svg_options.fontdb_mut().load_system_fonts();
+        Self { svg_options }
+    }
+}
+
+#[cfg(feature = "render")]
+impl PngRenderer {
+    pub fn render(
+        &self,
+        workout: &Workout,
+        options: &ChartOptions,
+    ) -> Result<Vec<u8>, RenderImageError> {
+        let tree = usvg::Tree::from_str(&workout_to_svg(workout, options), &self.svg_options)
+            .map_err(|error| RenderImageError::InvalidSvg(error.to_string()))?;
+        let mut pixmap = tiny_skia::Pixmap::new(options.width as u32, options.height as u32)
+            .ok_or(RenderImageError::InvalidSize)?;
+        resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+        pixmap
+            .encode_png()
+            .map_err(|error| RenderImageError::Encoding(error.to_string()))
+    }
+}
+
+fn power_axis(layout: &ChartLayout, settings: &ChartSettings, palette: &Palette) -> Vec<String> {
+    layout
+        .power_ticks(settings)
+        .into_iter()
+        .flat_map(|(wattage, label)| {
+            let y = layout.value_to_y(wattage);
+            [
+                svg_line(
+                    Point::new(MARGIN_LEFT, y),
+                    Point::new(MARGIN_LEFT + layout.plot.width, y),
+                    palette.faint,
+                ),
+                svg_text(
+                    &label,
+                    Point::new(MARGIN_LEFT - 6.0, y + LABEL_SIZE / 3.0),
+                    "end",
+                    palette.text,
+                    LABEL_SIZE,
+                ),
+            ]
+        })
+        .collect()
+}
+
+fn time_axis(layout: &ChartLayout, palette: &Palette) -> Vec<String> {
+    layout
+        .time_ticks()
+        .into_iter()
+        .flat_map(|(x, label)| {
+            let x = MARGIN_LEFT + x;
+            [
+                svg_line(
+                    Point::new(x, layout.plot.height),
+                    Point::new(x, layout.plot.height + 6.0),
+                    palette.text,
+                ),
+                svg_text(
+                    &label,
+                    Point::new(x, layout.plot.height + 6.0 + LABEL_SIZE),
+                    "middle",
+                    palette.text,
+                    LABEL_SIZE,
+                ),
+            ]
+        })
+        .collect()
+}
+
+fn svg_polygon(corners: &[Point], color: Color) -> String {
+    format!(
+        r#"<polygon points="{}" fill="{}" fill-opacity="{}"/>"#,
+        corners
+            .iter()
+            .map(|corner| format!("{:.2},{:.2}", corner.x + MARGIN_LEFT, corner.y))
+            .collect::<Vec<_>>()
+            .join(" "),
+        svg_color(color),
+        color.a
+    )
+}
+
+fn svg_rectangle(top_left: Point, size: Size, color: Color) -> String {
+    format!(
+        r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}" fill-opacity="{}"/>"#,
+        top_left.x,
+        top_left.y,
+        size.width,
+        size.height,
+        svg_color(color),
+        color.a
+    )
+}
+
+fn svg_line(from: Point, to: Point, color: Color) -> String {
+    format!(
+        r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}" stroke-opacity="{}"/>"#,
+        from.x,
+        from.y,
+        to.x,
+        to.y,
+        svg_color(color),
+        color.a
+    )
+}
+
+fn svg_text(content: &str, position: Point, anchor: &str, color: Color, size: f32) -> String {
+    format!(
+        r#"<text x="{:.2}" y="{:.2}" text-anchor="{}" fill="{}" fill-opacity="{}" font-size="{}">{}</text>"#,
+        position.x,
+        position.y,
+        anchor,
+        svg_color(color),
+        color.a,
+        size,
+        escape_xml(content)
+    )
+}
+
+fn svg_color(color: Color) -> String {
+    let [red, green, blue, _] = color.into_rgba8();
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workout_data::effort::Effort;
+    use crate::workout_data::test_workout;
+
+    #[test]
+    fn svg_contains_two_shapes_per_effort() {
+        let svg = workout_to_svg(&test_workout(), &ChartOptions::default());
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<polygon").count(), 10);
+    }
+    #[test]
+    fn svg_shows_rounded_duration() {
+        let workout = Workout::new(
+            "",
+            "",
+            vec![
+                Effort::new(61.1, 100.0, None),
+                Effort::new(0.2, 100.0, None),
+            ],
+        );
+        let svg = workout_to_svg(&workout, &ChartOptions::default());
+        assert!(svg.contains(">Duration: 1:01</text>"));
+    }
+    #[test]
+    fn svg_of_ride_is_decimated() {
+        let ride = Workout::new(
+            "Ride",
+            "",
+            (0..4 * 3600)
+                .map(|second| Effort::new(1.0 / 60.0, 150.0 + (second % 30) as f64, None))
+                .collect(),
+        );
+        let svg = workout_to_svg(&ride, &ChartOptions::default());
+        assert!(svg.matches("<polygon").count() <= 2 * (2 * 380 + 1));
+        assert!(!svg.contains("NaN"));
+    }
+    #[test]
+    fn svg_shows_names_of_efforts() {
+        let mut workout = test_workout();
+        workout.efforts[0] = Effort::new(10.0, 100.0, None).with_name("Warm & easy");
+        let svg = workout_to_svg(&workout, &ChartOptions::default());
+        assert!(svg.contains(">Warm &amp; easy</text>"));
+    }
+    #[test]
+    fn svg_shows_target_bands() {
+        let svg = workout_to_svg(&test_workout(), &ChartOptions::default());
+        assert_eq!(svg.matches("fill-opacity=\"0.35\" stroke=").count(), 1);
+    }
+    #[test]
+    fn svg_of_empty_test_workout() {
+        let svg = workout_to_svg(&Workout::empty("", ""), &ChartOptions::default());
+        assert_eq!(svg.matches("<polygon").count(), 0);
+        assert!(!svg.contains("NaN"));
+    }
+    #[test]
+    fn svg_with_zone_bands() {
+        let options = ChartOptions {
+            settings: ChartSettings {
+                ftp: Some(250.0),
+                show_zone_bands: true,
+                power_in_percent_of_ftp: false,
+                ..ChartSettings::default()
+            },
+            ..ChartOptions::default()
+        };
+        let without_zones = workout_to_svg(&test_workout(), &ChartOptions::default());
+        let with_zones = workout_to_svg(&test_workout(), &options);
+        assert_eq!(
+            with_zones.matches("<rect").count() - without_zones.matches("<rect").count(),
+            6
+        );
+    }
+    #[test]
+    fn svg_in_palette() {
+        let options = ChartOptions {
+            palette: Palette::LIGHT,
+            ..ChartOptions::default()
+        };
+        let svg = workout_to_svg(&test_workout(), &options);
+        assert!(svg.contains(&svg_color(Palette::LIGHT.chart_background)));
+        assert!(!svg.contains(&svg_color(Palette::DARK.chart_background)));
+    }
+    #[test]
+    fn svg_color_is_hex() {
+        assert_eq!(svg_color(Color::from_rgb8(255, 0, 16)), "#ff0010");
+    }
+    #[test]
+    fn escape_special_characters() {
+        assert_eq!(escape_xml("<a & b>"), "&lt;a &amp; b&gt;");
+    }
+    #[cfg(feature = "render")]
+    #[test]
+    fn render_png() {
+        let png = PngRenderer::default()
+            .render(
+                &test_workout(),
+                &ChartOptions {
+                    width: 320.0,
+                    height: 180.0,
+                    ..ChartOptions::default()
+                },
+            )
+            .expect("Rendering a simple workout works.");
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    }
+    #[cfg(feature = "render")]
+    #[test]
+    fn render_png_of_invalid_size() {
+        let options = ChartOptions {
+            width: 0.0,
+            ..ChartOptions::default()
+        };
+        assert!(PngRenderer::default()
+            .render(&test_workout(), &options)
+            .is_err());
+    }
+}
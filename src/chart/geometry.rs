@@ -0,0 +1,106 @@
+/// A color with its channels between 0 and 1.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Color {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::from_rgb(0.0, 0.0, 0.0);
+    pub const WHITE: Color = Color::from_rgb(1.0, 1.0, 1.0);
+
+    pub const fn from_rgb(r: f32, g: f32, b: f32) -> Self {
+        Self::from_rgba(r, g, b, 1.0)
+    }
+    pub const fn from_rgba(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+    pub fn from_rgb8(r: u8, g: u8, b: u8) -> Self {
+        Self::from_rgba8(r, g, b, 1.0)
+    }
+    pub fn from_rgba8(r: u8, g: u8, b: u8, a: f32) -> Self {
+        Self::from_rgba(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a)
+    }
+    /// The channels as bytes, as written in hex colors.
+    pub fn into_rgba8(self) -> [u8; 4] {
+        [self.r, self.g, self.b, self.a].map(|channel| (channel * 255.0).round() as u8)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point::new(0.0, 0.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Size {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Size {
+    pub const fn new(width: f32, height: f32) -> Self {
+        Self { width, height }
+    }
+}
+
+/// An area given by its top left corner and its size.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rectangle {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rectangle {
+    pub fn new(top_left: Point, size: Size) -> Self {
+        Self {
+            x: top_left.x,
+            y: top_left.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+    /// The area of `size` starting at the origin.
+    pub fn with_size(size: Size) -> Self {
+        Self::new(Point::ORIGIN, size)
+    }
+    pub fn position(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+    pub fn size(&self) -> Size {
+        Size::new(self.width, self.height)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bytes_round_trip() {
+        assert_eq!(Color::from_rgb8(255, 0, 16).into_rgba8(), [255, 0, 16, 255]);
+        assert_eq!(
+            Color::from_rgba8(1, 2, 3, 128.0 / 255.0).into_rgba8(),
+            [1, 2, 3, 128]
+        );
+    }
+    #[test]
+    fn rectangle_corners() {
+        let rectangle = Rectangle::new(Point::new(1.0, 2.0), Size::new(3.0, 4.0));
+        assert_eq!(rectangle.position(), Point::new(1.0, 2.0));
+        assert_eq!(rectangle.size(), Size::new(3.0, 4.0));
+    }
+}
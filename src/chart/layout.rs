@@ -0,0 +1,798 @@
+use super::axes::{axis_maximum, compute_minute_ticks, compute_ticks, format_minutes};
+use super::colors::{zone_bands, ZoneColors};
+use super::geometry::{Color, Point, Rectangle, Size};
+use super::palette::Palette;
+use super::viewport::Viewport;
+use crate::workout_data::effort;
+use crate::workout_data::workout::{extract_initial_starting_minutes, Workout};
+use serde::{Deserialize, Serialize};
+
+/// Horizontal gap in pixels between two drawn efforts.
+pub(crate) const OFFSET_BETWEEN_EFFORTS: f32 = 1.0;
+/// Space left of and below the efforts for the axis labels.
+pub(crate) const MARGIN_LEFT: f32 = 60.0;
+pub(crate) const MARGIN_BOTTOM: f32 = 30.0;
+pub(crate) const LABEL_SIZE: f32 = 14.0;
+/// Width of a character of a label, as part of its size.
+const LABEL_CHARACTER_WIDTH: f32 = 0.6;
+/// Minimal distance in pixels between two ticks of an axis.
+const PIXELS_PER_POWER_TICK: f32 = 40.0;
+const PIXELS_PER_TIME_TICK: f32 = 80.0;
+/// Width of the narrowest effort drawn, including the gap to the next one.
+const PIXELS_PER_DRAWN_EFFORT: f32 = 3.0;
+/// Size of the average wattage and duration in the upper right corner.
+pub(crate) const SUMMARY_SIZE: f32 = 22.0;
+
+/// The average wattage and the duration shown in the upper right corner,
+/// the duration in minutes like on the workout card.
+pub(crate) fn summary_lines(workout: &Workout) -> [String; 2] {
+    [
+        format!("Average Wattage: {:.1}", workout.average_intensity()),
+        format!(
+            "Duration: {}",
+            format_minutes(workout.total_time_of_workout() as f32)
+        ),
+    ]
+}
+
+/// What to show on the chart besides the efforts.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChartSettings {
+    pub ftp: Option<f64>,
+    pub show_zone_bands: bool,
+    pub power_in_percent_of_ftp: bool,
+    pub zone_colors: ZoneColors,
+}
+
+/// How many efforts can be told apart over `width` pixels.
+pub(crate) fn room_for_efforts(width: f32) -> usize {
+    (width / PIXELS_PER_DRAWN_EFFORT).max(1.0) as usize
+}
+
+/// Where the efforts and axes of a chart are placed.
+/// All coordinates are relative to the plot area, which
+/// starts `MARGIN_LEFT` pixels right of the frame.
+/// A zoomed in chart draws its efforts over `content_width` pixels,
+/// of which the ones from `shift` on are shown.
+pub struct ChartLayout {
+    pub plot: Size,
+    pub axis_maximum: f32,
+    total_minutes: f32,
+    starting_minutes: Vec<f32>,
+    durations: Vec<f32>,
+    ratio_duration_to_frame: f32,
+    pub(crate) content_width: f32,
+    pub(crate) shift: f32,
+}
+
+impl ChartLayout {
+    pub fn new(frame: Size, efforts: &[effort::Effort], ftp: Option<f64>) -> Self {
+        let plot = Size::new(
+            (frame.width - MARGIN_LEFT).max(1.0),
+            (frame.height - MARGIN_BOTTOM).max(1.0),
+        );
+        let durations: Vec<f32> = efforts
+            .iter()
+            .map(|effort| effort.duration_in_minutes as f32)
+            .collect();
+        let highest_value = match ftp {
+            Some(ftp) => max_value_of_efforts(efforts).max(ftp as f32),
+            None => max_value_of_efforts(efforts),
+        };
+        let starting_minutes = extract_initial_starting_minutes(efforts, 0.0)
+            .into_iter()
+            .map(|minute| minute as f32)
+            .collect();
+
+        Self {
+            plot,
+            axis_maximum: axis_maximum(
+                highest_value,
+                number_of_ticks(plot.height, PIXELS_PER_POWER_TICK),
+            ),
+            total_minutes: durations.iter().sum(),
+            starting_minutes,
+            ratio_duration_to_frame: compute_ratio_of_duration_to_frame(
+                plot.width,
+                OFFSET_BETWEEN_EFFORTS,
+                &durations,
+            ),
+            durations,
+            content_width: plot.width,
+            shift: 0.0,
+        }
+    }
+    /// The layout showing only the part of the time axis within `viewport`.
+    pub fn zoomed(self, viewport: Viewport) -> Self {
+        let content_width = self.plot.width / viewport.width();
+        Self {
+            ratio_duration_to_frame: compute_ratio_of_duration_to_frame(
+                content_width,
+                OFFSET_BETWEEN_EFFORTS,
+                &self.durations,
+            ),
+            shift: viewport.start() * content_width,
+            content_width,
+            ..self
+        }
+    }
+    pub fn value_to_y(&self, value: f32) -> f32 {
+        self.plot.height
+            - OFFSET_BETWEEN_EFFORTS
+            - value
+                * compute_ratio_of_effort_to_frame(
+                    self.plot.height,
+                    OFFSET_BETWEEN_EFFORTS,
+                    self.axis_maximum,
+                )
+    }
+    /// Efforts are separated by a small gap,
+    /// so the position depends on the efforts started so far.
+    pub fn minute_to_x(&self, minute: f32) -> f32 {
+        let started_efforts = self
+            .starting_minutes
+            .iter()
+            .filter(|&&starting_minute| starting_minute <= minute)
+            .count()
+            .max(1);
+        OFFSET_BETWEEN_EFFORTS * started_efforts as f32 + minute * self.ratio_duration_to_frame
+            - self.shift
+    }
+    /// Wattages to draw grid lines at, together with their labels.
+    pub fn power_ticks(&self, settings: &ChartSettings) -> Vec<(f32, String)> {
+        let maximal_number_of_ticks = number_of_ticks(self.plot.height, PIXELS_PER_POWER_TICK);
+        match (settings.power_in_percent_of_ftp, settings.ftp) {
+            (true, Some(ftp)) if ftp > 0.0 => compute_ticks(
+                self.axis_maximum / ftp as f32 * 100.0,
+                maximal_number_of_ticks,
+            )
+            .into_iter()
+            .map(|percent| (percent / 100.0 * ftp as f32, format!("{}%", percent)))
+            .collect(),
+            _ => compute_ticks(self.axis_maximum, maximal_number_of_ticks)
+                .into_iter()
+                .map(|wattage| (wattage, format!("{}", wattage)))
+                .collect(),
+        }
+    }
+    /// Horizontal positions of the visible time ticks, together with their labels.
+    pub fn time_ticks(&self) -> Vec<(f32, String)> {
+        compute_minute_ticks(
+            self.total_minutes,
+            number_of_ticks(self.content_width, PIXELS_PER_TIME_TICK),
+        )
+        .into_iter()
+        .map(|minute| (self.minute_to_x(minute), format_minutes(minute)))
+        .filter(|(x, _)| (0.0..=self.plot.width + OFFSET_BETWEEN_EFFORTS).contains(x))
+        .collect()
+    }
+    /// Top, bottom and color of the visible parts of the power zones.
+    pub fn zone_bands(&self, settings: &ChartSettings) -> Vec<(f32, f32, Color)> {
+        match (settings.show_zone_bands, settings.ftp) {
+            (true, Some(ftp)) => zone_bands(ftp, settings.zone_colors)
+                .into_iter()
+                .map(|band| {
+                    let top = self.value_to_y(band.upper_wattage as f32).max(0.0);
+                    let bottom = self
+                        .value_to_y(band.lower_wattage as f32)
+                        .min(self.plot.height);
+                    (
+                        top,
+                        bottom,
+                        Color {
+                            a: 0.15,
+                            ..band.color
+                        },
+                    )
+                })
+                .filter(|(top, bottom, _)| bottom > top)
+                .collect(),
+            _ => vec![],
+        }
+    }
+    /// Areas between the lowest and highest wattage of the visible efforts with a target range.
+    pub fn target_bands(&self, efforts: &[effort::Effort]) -> Vec<Rectangle> {
+        efforts
+            .iter()
+            .zip(&self.starting_minutes)
+            .filter_map(|(effort, &starting_minute)| {
+                let range = effort.target_range()?;
+                let x = self.minute_to_x(starting_minute);
+                let width = effort.duration_in_minutes as f32 * self.ratio_duration_to_frame;
+                let top = self.value_to_y(range.high as f32).max(0.0);
+                let bottom = self.value_to_y(range.low as f32).min(self.plot.height);
+                (x + width >= 0.0 && x <= self.plot.width && bottom > top)
+                    .then(|| Rectangle::new(Point::new(x, top), Size::new(width, bottom - top)))
+            })
+            .collect()
+    }
+    /// Names of the visible efforts wide enough to hold them,
+    /// centered above their boxes.
+    pub fn effort_labels(&self, efforts: &[effort::Effort]) -> Vec<(Point, String)> {
+        efforts
+            .iter()
+            .zip(&self.starting_minutes)
+            .filter_map(|(effort, &starting_minute)| {
+                let name = effort.name()?;
+                let width = effort.duration_in_minutes as f32 * self.ratio_duration_to_frame;
+                if width < name.chars().count() as f32 * LABEL_SIZE * LABEL_CHARACTER_WIDTH {
+                    return None;
+                }
+                let x = self.minute_to_x(starting_minute) + width / 2.0;
+                let top = self.value_to_y(effort.starting_value.max(effort.ending_value) as f32);
+                let y = (top - LABEL_SIZE).max(LABEL_SIZE / 2.0);
+                (0.0..=self.plot.width)
+                    .contains(&x)
+                    .then(|| (Point::new(x, y), String::from(name)))
+            })
+            .collect()
+    }
+    pub fn ftp_line(&self, settings: &ChartSettings) -> Option<f32> {
+        settings.ftp.map(|ftp| self.value_to_y(ftp as f32))
+    }
+    /// Lines through the power of a ride, broken where no power was recorded.
+    /// Seconds after the end of the workout are left out.
+    pub fn actual_power_lines(&self, powers: &[Option<f64>]) -> Vec<Vec<Point>> {
+        let mut lines: Vec<Vec<Point>> = Vec::new();
+        let mut previous_was_recorded = false;
+        for (second, power) in powers.iter().enumerate() {
+            let minute = second as f32 / 60.0;
+            if minute > self.total_minutes {
+                break;
+            }
+            match power {
+                Some(power) => {
+                    let point = Point::new(
+                        self.minute_to_x(minute),
+                        self.value_to_y(*power as f32).max(0.0),
+                    );
+                    match lines.last_mut() {
+                        Some(line) if previous_was_recorded => line.push(point),
+                        _ => lines.push(vec![point]),
+                    }
+                    previous_was_recorded = true;
+                }
+                None => previous_was_recorded = false,
+            }
+        }
+        lines
+    }
+}
+
+fn number_of_ticks(length: f32, pixels_per_tick: f32) -> usize {
+    ((length / pixels_per_tick) as usize).max(1)
+}
+
+/// Translucent, so the efforts stay visible beneath their target bands.
+pub(crate) fn target_band_color(palette: &Palette) -> Color {
+    Color {
+        a: 0.35,
+        ..palette.text
+    }
+}
+
+pub(crate) fn compute_boxes_for_efforts(
+    bounds: &'_ Rectangle,
+    efforts: &[effort::Effort],
+    axis_maximum: f32,
+    colors: ZoneColors,
+) -> Vec<(Box<dyn Drawable>, Color)> {
+    compute_shapes_to_draw(bounds, efforts, axis_maximum)
+        .into_iter()
+        .zip(duplicate_element_in_iterator(
+            &mut compute_colors_of_shapes(efforts, colors).into_iter(),
+        ))
+        .collect()
+}
+fn compute_colors_of_shapes(efforts: &[effort::Effort], colors: ZoneColors) -> Vec<Color> {
+    efforts
+        .iter()
+        .map(|effort| effort.to_color(colors))
+        .collect()
+}
+
+fn duplicate_element_in_iterator<T>(iterator: &mut dyn Iterator<Item = T>) -> Vec<T>
+where
+    T: Clone,
+{
+    iterator
+        .flat_map(|element| vec![element.clone(), element].into_iter())
+        .collect()
+}
+
+fn compute_shapes_to_draw(
+    bounds: &'_ Rectangle,
+    efforts: &[effort::Effort],
+    axis_maximum: f32,
+) -> Vec<Box<dyn Drawable>> {
+    let durations = efforts
+        .iter()
+        .map(|effort| effort.duration_in_minutes as f32)
+        .collect();
+    let starting_values: Vec<f32> = efforts
+        .iter()
+        .map(|effort| effort.starting_value as f32)
+        .collect();
+    let ending_values: Vec<f32> = efforts
+        .iter()
+        .map(|effort| effort.ending_value as f32)
+        .collect();
+
+    let offset_between_durations = OFFSET_BETWEEN_EFFORTS;
+
+    compute_starting_dimensions_x(bounds.size().width, durations, offset_between_durations)
+        .into_iter()
+        .zip(
+            compute_starting_dimensions_y(
+                bounds.size().height,
+                starting_values,
+                offset_between_durations,
+                axis_maximum,
+            ),
+        )
+        .zip(
+            compute_starting_dimensions_y(
+                bounds.size().height,
+                ending_values,
+                offset_between_durations,
+                axis_maximum,
+            ),
+        )
+        .flat_map(
+            |((x_dimensions, y_dimensions_starting), y_dimensions_ending)| -> Vec<Box<dyn Drawable+'static>>{
+                vec![
+                    Box::new(RectangleToDraw::new(
+                        x_dimensions,
+                        if y_dimensions_ending.height > y_dimensions_starting.height {y_dimensions_starting} else {y_dimensions_ending},
+                        bounds.size(),
+                    )),
+                    Box::new(TriangleToDraw::new(
+                        x_dimensions,
+                        y_dimensions_starting,
+                        y_dimensions_ending,
+                        bounds.size(),
+                    )),
+                ]
+            },
+        )
+        .collect::<Vec<Box<dyn Drawable>>>()
+}
+
+/// The highest wattage reached by any of the efforts.
+pub(crate) fn max_value_of_efforts(efforts: &[effort::Effort]) -> f32 {
+    efforts
+        .iter()
+        .flat_map(|effort| [effort.starting_value as f32, effort.ending_value as f32])
+        .fold(f32::NAN, f32::max)
+}
+
+fn compute_starting_dimensions_x(
+    length_of_frame: f32,
+    durations: Vec<f32>,
+    offset_between_durations: f32,
+) -> Vec<RectangleXDimensions> {
+    let ratio_duration_to_frame: f32 =
+        compute_ratio_of_duration_to_frame(length_of_frame, offset_between_durations, &durations);
+
+    let widths = durations
+        .iter()
+        .map(|&current_duration| current_duration * ratio_duration_to_frame);
+
+    compute_starting_points_of_efforts(
+        &offset_between_durations,
+        &ratio_duration_to_frame,
+        &durations,
+    )
+    .zip(widths)
+    .map(|(starting_point, width)| RectangleXDimensions::new(starting_point, width))
+    .collect()
+}
+
+fn compute_ratio_of_duration_to_frame(
+    length_of_frame: f32,
+    offset_between_durations: f32,
+    durations: &[f32],
+) -> f32 {
+    (length_of_frame - (offset_between_durations * durations.len() as f32))
+        / durations.iter().sum::<f32>()
+}
+
+fn compute_starting_points_of_efforts<'a>(
+    offset_between_durations: &'a f32,
+    ratio_duration_to_frame: &'a f32,
+    durations: &'a [f32],
+) -> impl Iterator<Item = f32> + 'a {
+    std::iter::once(&0.0_f32).chain(durations.iter()).scan(
+        0.0_f32,
+        move |last_starting_point, &current_duration| {
+            *last_starting_point = *last_starting_point
+                + offset_between_durations
+                + current_duration * ratio_duration_to_frame;
+            Some(*last_starting_point)
+        },
+    )
+}
+
+fn compute_starting_dimensions_y(
+    length_of_frame: f32,
+    efforts: Vec<f32>,
+    offset_between_efforts: f32,
+    axis_maximum: f32,
+) -> Vec<RectangleYDimensions> {
+    let ratio_effort_to_frame =
+        compute_ratio_of_effort_to_frame(length_of_frame, offset_between_efforts, axis_maximum);
+    let heigths = efforts
+        .iter()
+        .map(|&current_effort| current_effort * ratio_effort_to_frame);
+
+    let starting_points_y = vec![offset_between_efforts; efforts.len()].into_iter();
+
+    starting_points_y
+        .zip(heigths)
+        .map(|(starting_point, height)| RectangleYDimensions::new(starting_point, height))
+        .collect()
+}
+
+/// Pixels per watt when the axis up to `axis_maximum` fills the frame.
+pub(crate) fn compute_ratio_of_effort_to_frame(
+    length_of_frame: f32,
+    offset_between_efforts: f32,
+    axis_maximum: f32,
+) -> f32 {
+    (length_of_frame - offset_between_efforts) / axis_maximum
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Copy)]
+struct RectangleXDimensions {
+    starting_point: f32,
+    width: f32,
+}
+
+impl RectangleXDimensions {
+    fn new(starting_point: f32, width: f32) -> Self {
+        Self {
+            starting_point,
+            width,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Copy)]
+struct RectangleYDimensions {
+    starting_point: f32,
+    height: f32,
+}
+
+impl RectangleYDimensions {
+    fn new(starting_point: f32, height: f32) -> Self {
+        Self {
+            starting_point,
+            height,
+        }
+    }
+}
+
+pub(crate) trait Drawable {
+    /// The corners of the shape, to be joined by any renderer.
+    fn corners(&self) -> Vec<Point>;
+}
+#[derive(Clone, Debug, PartialEq)]
+struct RectangleToDraw {
+    top_left: Point,
+    size: Size,
+}
+
+impl RectangleToDraw {
+    fn new(
+        dimensions_x: RectangleXDimensions,
+        dimensions_y: RectangleYDimensions,
+        size_of_frame: Size,
+    ) -> Self {
+        Self {
+            top_left: Point::new(
+                dimensions_x.starting_point,
+                size_of_frame.height - dimensions_y.height - dimensions_y.starting_point,
+            ),
+            size: Size::new(dimensions_x.width, dimensions_y.height),
+        }
+    }
+}
+
+impl Drawable for RectangleToDraw {
+    fn corners(&self) -> Vec<Point> {
+        vec![
+            self.top_left,
+            Point::new(self.top_left.x + self.size.width, self.top_left.y),
+            Point::new(
+                self.top_left.x + self.size.width,
+                self.top_left.y + self.size.height,
+            ),
+            Point::new(self.top_left.x, self.top_left.y + self.size.height),
+        ]
+    }
+}
+
+struct TriangleToDraw {
+    point_1: Point,
+    point_2: Point,
+    point_3: Point,
+}
+
+impl TriangleToDraw {
+    fn new(
+        x_dimensions: RectangleXDimensions,
+        y_dimensions_starting: RectangleYDimensions,
+        y_dimensions_ending: RectangleYDimensions,
+        frame: Size,
+    ) -> Self {
+        if y_dimensions_starting.height > y_dimensions_ending.height {
+            Self {
+                point_1: Point::new(
+                    x_dimensions.starting_point,
+                    mirror_y(
+                        y_dimensions_ending.starting_point + y_dimensions_ending.height,
+                        frame,
+                    ),
+                ),
+                point_2: Point::new(
+                    x_dimensions.starting_point,
+                    mirror_y(
+                        y_dimensions_starting.starting_point + y_dimensions_starting.height,
+                        frame,
+                    ),
+                ),
+
+                point_3: Point::new(
+                    x_dimensions.starting_point + x_dimensions.width,
+                    mirror_y(
+                        y_dimensions_ending.starting_point + y_dimensions_ending.height,
+                        frame,
+                    ),
+                ),
+            }
+        } else {
+            Self {
+                point_1: Point::new(
+                    x_dimensions.starting_point,
+                    mirror_y(
+                        y_dimensions_starting.starting_point + y_dimensions_starting.height,
+                        frame,
+                    ),
+                ),
+                point_2: Point::new(
+                    x_dimensions.starting_point + x_dimensions.width,
+                    mirror_y(
+                        y_dimensions_starting.starting_point + y_dimensions_starting.height,
+                        frame,
+                    ),
+                ),
+                point_3: Point::new(
+                    x_dimensions.starting_point + x_dimensions.width,
+                    mirror_y(
+                        y_dimensions_ending.starting_point + y_dimensions_ending.height,
+                        frame,
+                    ),
+                ),
+            }
+        }
+    }
+}
+
+fn mirror_y(point: f32, frame: Size) -> f32 {
+    frame.height - point
+}
+
+impl Drawable for TriangleToDraw {
+    fn corners(&self) -> Vec<Point> {
+        vec![self.point_1, self.point_2, self.point_3]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        compute_ratio_of_duration_to_frame, compute_starting_dimensions_x,
+        compute_starting_dimensions_y, compute_starting_points_of_efforts, RectangleToDraw,
+        RectangleXDimensions, RectangleYDimensions,
+    };
+    use super::{ChartLayout, ChartSettings, MARGIN_BOTTOM, MARGIN_LEFT, OFFSET_BETWEEN_EFFORTS};
+    use crate::chart::geometry::{Point, Size};
+    use crate::chart::viewport::Viewport;
+    use crate::workout_data::effort::Effort;
+
+    #[test]
+    fn test_get_starting_coordinates_x() {
+        assert_eq!(
+            compute_starting_dimensions_x(100.0, vec![10.0, 20.0, 40.0, 10.0], 0.1),
+            vec![
+                RectangleXDimensions::new(0.1, 12.45),
+                RectangleXDimensions::new(12.65, 24.9),
+                RectangleXDimensions::new(37.65, 49.8),
+                RectangleXDimensions::new(87.55, 12.45)
+            ]
+        )
+    }
+    #[test]
+    fn test_compute_ratio_of_duration_to_frame() {
+        assert_eq!(
+            compute_ratio_of_duration_to_frame(100.0, 0.1, &[10.0, 20.0, 40.0, 10.0]),
+            1.245
+        )
+    }
+    #[test]
+    fn test_compute_starting_points_of_efforts() {
+        assert_eq!(
+            compute_starting_points_of_efforts(&0.1, &1.245, &[10.0, 20.0, 40.0, 10.0])
+                .collect::<Vec<f32>>(),
+            vec![0.1, 12.65, 37.65, 87.55, 100.1]
+        )
+    }
+    #[test]
+    fn test_get_starting_coordinates_y() {
+        assert_eq!(
+            compute_starting_dimensions_y(501.0, vec![100.0, 200.0, 250.0, 100.0], 1.0, 250.0),
+            vec![
+                RectangleYDimensions::new(1.0, 200.0),
+                RectangleYDimensions::new(1.0, 400.0),
+                RectangleYDimensions::new(1.0, 500.0),
+                RectangleYDimensions::new(1.0, 200.0)
+            ]
+        )
+    }
+
+    #[test]
+    fn test_rectangle_to_draw() {
+        assert_eq!(
+            RectangleToDraw::new(
+                RectangleXDimensions {
+                    starting_point: 0.1,
+                    width: 10.0
+                },
+                RectangleYDimensions {
+                    starting_point: 0.1,
+                    height: 30.0
+                },
+                Size::new(100.0, 300.0)
+            ),
+            RectangleToDraw {
+                top_left: Point::new(0.1, 269.9),
+                size: Size::new(10.0, 30.0)
+            }
+        )
+    }
+
+    fn layout(ftp: Option<f64>) -> ChartLayout {
+        ChartLayout::new(
+            Size::new(MARGIN_LEFT + 302.0, MARGIN_BOTTOM + 401.0),
+            &[
+                Effort::new(10.0, 100.0, None),
+                Effort::new(20.0, 300.0, None),
+            ],
+            ftp,
+        )
+    }
+
+    #[test]
+    fn layout_leaves_headroom_above_highest_effort() {
+        assert_eq!(layout(None).axis_maximum, 350.0);
+        assert!(layout(None).value_to_y(350.0).abs() < 1e-3);
+        assert_eq!(layout(None).value_to_y(0.0), 400.0);
+    }
+    #[test]
+    fn layout_includes_ftp() {
+        assert_eq!(layout(Some(500.0)).axis_maximum, 600.0);
+    }
+    #[test]
+    fn minute_to_x_skips_gaps_between_efforts() {
+        let layout = layout(None);
+        assert_eq!(layout.minute_to_x(0.0), OFFSET_BETWEEN_EFFORTS);
+        assert_eq!(
+            layout.minute_to_x(10.0),
+            2.0 * OFFSET_BETWEEN_EFFORTS + 100.0
+        );
+    }
+    #[test]
+    fn zoomed_layout_shows_part_of_the_time_axis() {
+        let zoomed = layout(None).zoomed(Viewport::FULL.zoomed(2.0, 1.0));
+        assert!((zoomed.minute_to_x(30.0) - 302.0).abs() < 1e-3);
+        assert!(zoomed.minute_to_x(10.0) < 0.0);
+        assert!(!zoomed.time_ticks().is_empty());
+        assert!(zoomed
+            .time_ticks()
+            .iter()
+            .all(|(x, _)| (0.0..=302.0 + OFFSET_BETWEEN_EFFORTS).contains(x)));
+    }
+    #[test]
+    fn target_bands_span_the_target_range() {
+        let efforts = [
+            Effort::new(10.0, 100.0, None),
+            Effort::new(20.0, 300.0, None).with_target_range(280.0, 320.0),
+        ];
+        let layout = ChartLayout::new(
+            Size::new(MARGIN_LEFT + 302.0, MARGIN_BOTTOM + 401.0),
+            &efforts,
+            None,
+        );
+        let bands = layout.target_bands(&efforts);
+        assert_eq!(bands.len(), 1);
+        assert!((bands[0].x - layout.minute_to_x(10.0)).abs() < 1e-3);
+        assert!((bands[0].y - layout.value_to_y(320.0)).abs() < 1e-3);
+        assert!((bands[0].y + bands[0].height - layout.value_to_y(280.0)).abs() < 1e-3);
+        let zoomed = layout.zoomed(Viewport::FULL.zoomed(4.0, 0.0));
+        assert!(zoomed.target_bands(&efforts).is_empty());
+    }
+    #[test]
+    fn named_efforts_are_labeled_when_wide_enough() {
+        let efforts = [
+            Effort::new(10.0, 100.0, None).with_name("Warmup"),
+            Effort::new(0.5, 300.0, None).with_name("Sprint"),
+            Effort::new(19.5, 300.0, None),
+        ];
+        let layout = ChartLayout::new(
+            Size::new(MARGIN_LEFT + 302.0, MARGIN_BOTTOM + 401.0),
+            &efforts,
+            None,
+        );
+        let labels = layout.effort_labels(&efforts);
+        assert_eq!(labels.len(), 1);
+        let (position, name) = &labels[0];
+        assert_eq!(name, "Warmup");
+        assert!((position.x - layout.minute_to_x(5.0)).abs() < 1e-3);
+        assert!(position.y < layout.value_to_y(100.0));
+        let zoomed = layout.zoomed(Viewport::FULL.zoomed(2.0, 1.0));
+        assert!(zoomed.effort_labels(&efforts).is_empty());
+    }
+    #[test]
+    fn power_ticks_in_percent_of_ftp() {
+        let settings = ChartSettings {
+            ftp: Some(200.0),
+            show_zone_bands: false,
+            power_in_percent_of_ftp: true,
+            ..ChartSettings::default()
+        };
+        let ticks = layout(Some(200.0)).power_ticks(&settings);
+        assert_eq!(ticks[1], (40.0, String::from("20%")));
+    }
+    #[test]
+    fn zone_bands_only_when_enabled() {
+        let settings = ChartSettings {
+            ftp: Some(200.0),
+            show_zone_bands: false,
+            power_in_percent_of_ftp: false,
+            ..ChartSettings::default()
+        };
+        assert!(layout(Some(200.0)).zone_bands(&settings).is_empty());
+        assert_eq!(
+            layout(Some(200.0))
+                .zone_bands(&ChartSettings {
+                    show_zone_bands: true,
+                    ..settings
+                })
+                .len(),
+            6
+        );
+    }
+    #[test]
+    fn actual_power_breaks_at_gaps() {
+        let lines = layout(None).actual_power_lines(&[
+            Some(100.0),
+            Some(150.0),
+            None,
+            Some(200.0),
+            Some(1000.0),
+        ]);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].len(), 2);
+        assert_eq!(lines[0][0].y, layout(None).value_to_y(100.0));
+        assert_eq!(lines[1][1].y, 0.0);
+    }
+    #[test]
+    fn actual_power_ends_with_workout() {
+        let powers = vec![Some(100.0); 31 * 60];
+        assert_eq!(
+            layout(None).actual_power_lines(&powers)[0].len(),
+            30 * 60 + 1
+        );
+    }
+}
@@ -0,0 +1,9 @@
+pub mod axes;
+pub mod card;
+pub mod colors;
+pub mod decimation;
+pub mod export;
+pub mod geometry;
+pub mod layout;
+pub mod palette;
+pub mod viewport;
@@ -0,0 +1,205 @@
+use super::geometry::Color;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+
+/// The theme used if none or an unknown one is chosen.
+pub const DEFAULT_THEME: &str = "Dark";
+
+/// Colors of the designer, its charts and the exported images.
+/// Colors are written as `#rrggbb` or `#rrggbbaa` in the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Palette {
+    #[serde(with = "hex")]
+    pub background: Color,
+    #[serde(with = "hex")]
+    pub text: Color,
+    /// Buttons, borders of inputs and the FTP line.
+    #[serde(with = "hex")]
+    pub accent: Color,
+    /// Text on the accent color.
+    #[serde(with = "hex")]
+    pub on_accent: Color,
+    /// Hovered and pressed buttons and the border of the chart.
+    #[serde(with = "hex")]
+    pub highlight: Color,
+    /// Grid lines and placeholders.
+    #[serde(with = "hex")]
+    pub faint: Color,
+    /// Selected text.
+    #[serde(with = "hex")]
+    pub selection: Color,
+    #[serde(with = "hex")]
+    pub chart_background: Color,
+    #[serde(with = "hex")]
+    pub error: Color,
+    /// Warnings and the power of an imported ride.
+    #[serde(with = "hex")]
+    pub warning: Color,
+}
+
+impl Palette {
+    /// The pink and purple retro look.
+    pub const DARK: Palette = Palette {
+        background: Color::from_rgb(
+            0x20 as f32 / 255.0,
+            0x22 as f32 / 255.0,
+            0x25 as f32 / 255.0,
+        ),
+        text: Color::from_rgba(1.0, 1.0, 1.0, 0.8),
+        accent: Color::from_rgb(1.0, 0.0, 1.0),
+        on_accent: Color::BLACK,
+        highlight: Color::from_rgb(171.0 / 255.0, 32.0 / 255.0, 253.0 / 255.0),
+        faint: Color::from_rgba(1.0, 1.0, 1.0, 0.08),
+        selection: Color::from_rgb(40.0 / 255.0, 50.0 / 255.0, 52.0 / 255.0),
+        chart_background: Color::from_rgb(64.0 / 255.0, 68.0 / 255.0, 75.0 / 255.0),
+        error: Color::from_rgb(1.0, 0.25, 0.25),
+        warning: Color::from_rgb(1.0, 0.85, 0.2),
+    };
+    pub const LIGHT: Palette = Palette {
+        background: rgba(245, 245, 247, 255),
+        text: rgba(26, 26, 33, 230),
+        accent: rgba(194, 0, 143, 255),
+        on_accent: Color::WHITE,
+        highlight: rgba(122, 31, 209, 255),
+        faint: rgba(0, 0, 0, 31),
+        selection: rgba(204, 217, 222, 255),
+        chart_background: Color::WHITE,
+        error: rgba(199, 41, 41, 255),
+        warning: rgba(179, 128, 0, 255),
+    };
+    /// Pure colors on black, for riders who need the most contrast.
+    pub const HIGH_CONTRAST: Palette = Palette {
+        background: Color::BLACK,
+        text: Color::WHITE,
+        accent: rgba(255, 255, 0, 255),
+        on_accent: Color::BLACK,
+        highlight: rgba(0, 255, 255, 255),
+        faint: rgba(255, 255, 255, 89),
+        selection: rgba(0, 80, 255, 255),
+        chart_background: Color::BLACK,
+        error: rgba(255, 77, 77, 255),
+        warning: rgba(255, 166, 0, 255),
+    };
+}
+
+/// A color given by bytes, as written in the settings.
+const fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Color {
+    Color::from_rgba(
+        red as f32 / 255.0,
+        green as f32 / 255.0,
+        blue as f32 / 255.0,
+        alpha as f32 / 255.0,
+    )
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette::DARK
+    }
+}
+
+const BUILT_IN: [(&str, Palette); 3] = [
+    (DEFAULT_THEME, Palette::DARK),
+    ("Light", Palette::LIGHT),
+    ("High Contrast", Palette::HIGH_CONTRAST),
+];
+
+/// Names of the built-in themes followed by the ones of the custom palettes.
+pub fn theme_names(custom: &BTreeMap<String, Palette>) -> Vec<String> {
+    BUILT_IN
+        .iter()
+        .map(|(name, _)| String::from(*name))
+        .chain(
+            custom
+                .keys()
+                .filter(|name| !BUILT_IN.iter().any(|(built_in, _)| built_in == name))
+                .cloned(),
+        )
+        .collect()
+}
+
+/// The palette of the theme called `name`, the dark one if there is none.
+/// Built-in themes can not be replaced by custom palettes.
+pub fn palette(name: &str, custom: &BTreeMap<String, Palette>) -> Palette {
+    BUILT_IN
+        .iter()
+        .find(|(built_in, _)| *built_in == name)
+        .map(|(_, palette)| *palette)
+        .or_else(|| custom.get(name).copied())
+        .unwrap_or_default()
+}
+
+/// Colors as hex strings, with an optional alpha channel.
+mod hex {
+    use super::*;
+    use serde::de::Error;
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        let [red, green, blue, alpha] = color.into_rgba8();
+        let hex = match alpha {
+            255 => format!("#{:02x}{:02x}{:02x}", red, green, blue),
+            _ => format!("#{:02x}{:02x}{:02x}{:02x}", red, green, blue, alpha),
+        };
+        serializer.serialize_str(&hex)
+    }
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        parse(&hex).ok_or_else(|| D::Error::custom(format!("{:?} is no hex color", hex)))
+    }
+    pub(super) fn parse(hex: &str) -> Option<Color> {
+        let digits = hex.strip_prefix('#')?;
+        if !matches!(digits.len(), 6 | 8) || !digits.is_ascii() {
+            return None;
+        }
+        let channels = (0..digits.len())
+            .step_by(2)
+            .map(|start| u8::from_str_radix(&digits[start..start + 2], 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        let alpha = channels.get(3).map_or(1.0, |&alpha| alpha as f32 / 255.0);
+        Some(Color::from_rgba8(
+            channels[0],
+            channels[1],
+            channels[2],
+            alpha,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hex_colors_are_parsed() {
+        assert_eq!(hex::parse("#ff0010"), Some(Color::from_rgb8(255, 0, 16)));
+        assert_eq!(
+            hex::parse("#ffffff80"),
+            Some(Color::from_rgba8(255, 255, 255, 128.0 / 255.0))
+        );
+        assert_eq!(hex::parse("ff0010"), None);
+        assert_eq!(hex::parse("#ff00"), None);
+        assert_eq!(hex::parse("#gg0010"), None);
+    }
+    #[test]
+    fn palette_round_trip() {
+        let json = serde_json::to_string(&Palette::DARK).unwrap();
+        assert!(json.contains(r##""accent":"#ff00ff""##));
+        let read: Palette = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.accent, Palette::DARK.accent);
+        assert_eq!(read.faint.a, 20.0 / 255.0);
+    }
+    #[test]
+    fn custom_palettes_follow_built_in_themes() {
+        let custom = BTreeMap::from([
+            (String::from("Solarized"), Palette::LIGHT),
+            (String::from("Light"), Palette::HIGH_CONTRAST),
+        ]);
+        assert_eq!(
+            theme_names(&custom),
+            vec!["Dark", "Light", "High Contrast", "Solarized"]
+        );
+        assert_eq!(palette("Solarized", &custom), Palette::LIGHT);
+        assert_eq!(palette("Light", &custom), Palette::LIGHT);
+        assert_eq!(palette("Unknown", &custom), Palette::DARK);
+    }
+}
@@ -0,0 +1,146 @@
+use crate::workout_data::effort::Effort;
+use crate::workout_data::workout::extract_initial_starting_minutes;
+
+/// How far the time axis can be stretched.
+pub const MAX_ZOOM: f32 = 64.0;
+/// Factor by which a zoom step or a line of the mouse wheel stretches the time axis.
+pub const ZOOM_STEP: f32 = 1.25;
+
+/// Zooming requested from outside of the chart, e.g. by a key chord.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Zoom {
+    In,
+    Out,
+    Reset,
+}
+
+/// The part of the time axis shown by the chart,
+/// as fractions of the whole workout.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    start: f32,
+    end: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport::FULL
+    }
+}
+
+impl Viewport {
+    pub const FULL: Viewport = Viewport {
+        start: 0.0,
+        end: 1.0,
+    };
+
+    pub fn start(self) -> f32 {
+        self.start
+    }
+    pub fn end(self) -> f32 {
+        self.end
+    }
+    pub fn width(self) -> f32 {
+        self.end - self.start
+    }
+    pub fn is_zoomed(self) -> bool {
+        self.width() < 1.0
+    }
+    /// Stretch the time axis by `factor`, keeping the point `anchor` of the way
+    /// through the viewport in place.
+    pub fn zoomed(self, factor: f32, anchor: f32) -> Self {
+        let width = (self.width() / factor).clamp(1.0 / MAX_ZOOM, 1.0);
+        let fixed = self.start + anchor.clamp(0.0, 1.0) * self.width();
+        Self::from_start(fixed - anchor.clamp(0.0, 1.0) * width, width)
+    }
+    /// Move the viewport by `distance`, a fraction of the whole workout.
+    pub fn panned(self, distance: f32) -> Self {
+        Self::from_start(self.start + distance, self.width())
+    }
+    /// Move the viewport to have its center at `position`.
+    pub fn centered_at(self, position: f32) -> Self {
+        Self::from_start(position - self.width() / 2.0, self.width())
+    }
+    pub fn apply(self, zoom: Zoom) -> Self {
+        match zoom {
+            Zoom::In => self.zoomed(ZOOM_STEP, 0.5),
+            Zoom::Out => self.zoomed(1.0 / ZOOM_STEP, 0.5),
+            Zoom::Reset => Viewport::FULL,
+        }
+    }
+    /// Index of the effort shown first, to scroll the list of efforts to.
+    pub fn first_effort(self, efforts: &[Effort]) -> usize {
+        let total_minutes: f64 = efforts
+            .iter()
+            .map(|effort| effort.duration_in_minutes)
+            .sum();
+        let first_minute = self.start as f64 * total_minutes;
+        extract_initial_starting_minutes(efforts, 0.0)
+            .iter()
+            .filter(|&&starting_minute| starting_minute <= first_minute)
+            .count()
+            .saturating_sub(1)
+    }
+    fn from_start(start: f32, width: f32) -> Self {
+        let start = start.clamp(0.0, 1.0 - width);
+        Self {
+            start,
+            end: start + width,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn close(left: f32, right: f32) -> bool {
+        (left - right).abs() < 1e-6
+    }
+
+    #[test]
+    fn zooming_keeps_the_anchor_in_place() {
+        let viewport = Viewport::FULL.zoomed(4.0, 0.5);
+        assert!(close(viewport.start(), 0.375) && close(viewport.end(), 0.625));
+        let viewport = viewport.zoomed(2.0, 0.0);
+        assert!(close(viewport.start(), 0.375) && close(viewport.width(), 0.125));
+    }
+    #[test]
+    fn viewport_stays_within_the_workout() {
+        let viewport = Viewport::FULL.zoomed(2.0, 1.0);
+        assert_eq!(
+            viewport,
+            Viewport {
+                start: 0.5,
+                end: 1.0
+            }
+        );
+        assert_eq!(viewport.panned(0.3), viewport);
+        assert_eq!(viewport.centered_at(0.0).start(), 0.0);
+        assert_eq!(viewport.zoomed(0.1, 0.5), Viewport::FULL);
+        assert!(close(
+            Viewport::FULL.zoomed(1000.0, 0.5).width(),
+            1.0 / MAX_ZOOM
+        ));
+    }
+    #[test]
+    fn zoom_steps() {
+        let zoomed = Viewport::FULL.apply(Zoom::In);
+        assert!(zoomed.is_zoomed());
+        assert!(close(zoomed.apply(Zoom::Out).width(), 1.0));
+        assert!(!zoomed.apply(Zoom::Reset).is_zoomed());
+    }
+    #[test]
+    fn first_effort_in_viewport() {
+        let efforts = [
+            Effort::new(10.0, 100.0, None),
+            Effort::new(20.0, 200.0, None),
+            Effort::new(10.0, 100.0, None),
+        ];
+        assert_eq!(Viewport::FULL.first_effort(&efforts), 0);
+        assert_eq!(Viewport::FULL.centered_at(0.5).first_effort(&efforts), 0);
+        assert_eq!(Viewport::FULL.zoomed(4.0, 0.5).first_effort(&efforts), 1);
+        assert_eq!(Viewport::FULL.zoomed(4.0, 1.0).first_effort(&efforts), 2);
+        assert_eq!(Viewport::FULL.first_effort(&[]), 0);
+    }
+}
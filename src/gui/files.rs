@@ -0,0 +1,224 @@
+use iced::Task;
+#[cfg(target_arch = "wasm32")]
+use rfd::AsyncFileDialog;
+#[cfg(not(target_arch = "wasm32"))]
+use rfd::FileDialog;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+use std::path::PathBuf;
+
+/// A file the user picked, read completely.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenedFile {
+    pub name: String,
+    pub contents: Vec<u8>,
+    /// Where the file was read from, unknown for uploads.
+    pub path: Option<PathBuf>,
+}
+
+impl OpenedFile {
+    pub fn extension(&self) -> Option<&str> {
+        std::path::Path::new(&self.name)
+            .extension()
+            .and_then(|extension| extension.to_str())
+    }
+    pub fn text(&self) -> Option<&str> {
+        std::str::from_utf8(&self.contents).ok()
+    }
+}
+
+/// Kinds of files offered when picking one, as a name and the extensions belonging to it.
+pub type Filters<'a> = &'a [(&'a str, &'a [&'a str])];
+
+/// Let the user pick a file to open: with the file dialog of the system
+/// on desktops, with an upload in browsers.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn open<Message: Send + 'static>(
+    filters: Filters,
+    on_open: impl FnOnce(Option<OpenedFile>) -> Message,
+) -> Task<Message> {
+    let dialog = filters
+        .iter()
+        .fold(FileDialog::new(), |dialog, (name, extensions)| {
+            dialog.add_filter(*name, extensions)
+        })
+        .set_directory(default_directory());
+    let opened = dialog.pick_file().and_then(|path| read(&path));
+    Task::done(on_open(opened))
+}
+
+/// Read the file at `path` completely, e.g. to reopen a recent file.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn read(path: &Path) -> Option<OpenedFile> {
+    Some(OpenedFile {
+        name: path.file_name()?.to_string_lossy().into_owned(),
+        contents: std::fs::read(path)
+            .map_err(|error| eprintln!("Could not read {:?} because of: {}", path, error))
+            .ok()?,
+        path: Some(path.to_path_buf()),
+    })
+}
+
+/// Let the user pick a file to open: with the file dialog of the system
+/// on desktops, with an upload in browsers.
+#[cfg(target_arch = "wasm32")]
+pub fn open<Message: Send + 'static>(
+    filters: Filters,
+    on_open: impl FnOnce(Option<OpenedFile>) -> Message + Send + 'static,
+) -> Task<Message> {
+    let dialog = filters
+        .iter()
+        .fold(AsyncFileDialog::new(), |dialog, (name, extensions)| {
+            dialog.add_filter(*name, extensions)
+        });
+    Task::future(async move {
+        let opened = match dialog.pick_file().await {
+            Some(file) => Some(OpenedFile {
+                name: file.file_name(),
+                contents: file.read().await,
+                path: None,
+            }),
+            None => None,
+        };
+        on_open(opened)
+    })
+}
+
+/// Let the user choose where to save a file. The extension of the chosen path
+/// tells which format to write. Only available on desktops,
+/// browsers download with `download` instead.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn pick_save_path(filters: Filters) -> Option<PathBuf> {
+    filters
+        .iter()
+        .fold(FileDialog::new(), |dialog, (name, extensions)| {
+            dialog.add_filter(*name, extensions)
+        })
+        .set_directory(default_directory())
+        .save_file()
+}
+
+/// Replace the file at `path` by `contents`. They are written to a temporary file
+/// next to it first, which is then renamed, so a failed write leaves the old file intact.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_atomically(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or(std::io::ErrorKind::InvalidInput)?
+        .to_string_lossy();
+    let temporary = path.with_file_name(format!(".{}.tmp", file_name));
+    std::fs::write(&temporary, contents)
+        .and_then(|_| std::fs::rename(&temporary, path))
+        .inspect_err(|_| {
+            let _ = std::fs::remove_file(&temporary);
+        })
+}
+
+/// Offer `contents` as download named `file_name`.
+#[cfg(target_arch = "wasm32")]
+pub fn download<Message: Send + 'static>(file_name: String, contents: Vec<u8>) -> Task<Message> {
+    Task::future(async move {
+        if let Some(file) = AsyncFileDialog::new()
+            .set_file_name(file_name)
+            .save_file()
+            .await
+        {
+            let _ = file.write(&contents).await;
+        }
+    })
+    .discard()
+}
+
+/// The plans directory of a connected bike computer, or the home directory.
+#[cfg(not(target_arch = "wasm32"))]
+fn default_directory() -> PathBuf {
+    find_bike_computer().unwrap_or(dirs::home_dir().unwrap_or_default())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn find_bike_computer() -> Option<PathBuf> {
+    list_all_mounted_devices()
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .find(|path| is_relevant_computer(path))
+        .map(|computer| computer.join("Internal shared storage/plans"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn list_all_mounted_devices() -> Option<Vec<PathBuf>> {
+    // Only works for linux right now:
+    let potential_location = std::path::Path::new("/run/user/1000/gvfs/");
+    if !potential_location.exists() {
+        return None;
+    }
+    std::fs::read_dir(potential_location)
+        .ok()?
+        .map(|file| match file {
+            Ok(file) => Some(file.path()),
+            Err(_) => None,
+        })
+        .collect()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn is_relevant_computer(path: &std::path::Path) -> bool {
+    ["exports", "factory", "plans", "logs", "maps", "routes"]
+        .iter()
+        .all(|directory| {
+            path.join("Internal shared storage")
+                .join(directory)
+                .exists()
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extension_of_opened_file() {
+        let file = OpenedFile {
+            name: String::from("sweet-spot.plan"),
+            contents: b"=HEADER=".to_vec(),
+            path: None,
+        };
+        assert_eq!(file.extension(), Some("plan"));
+        assert_eq!(file.text(), Some("=HEADER="));
+    }
+    #[test]
+    fn file_without_extension() {
+        let file = OpenedFile {
+            name: String::from("README"),
+            contents: vec![0xff],
+            path: None,
+        };
+        assert_eq!(file.extension(), None);
+        assert_eq!(file.text(), None);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn atomic_write_replaces_file() {
+        let directory =
+            std::env::temp_dir().join(format!("mrc-workout-creator-files-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("tempo.plan");
+        std::fs::write(&path, "old").unwrap();
+        write_atomically(&path, b"new").unwrap();
+        assert_eq!(read(&path).map(|file| file.contents), Some(b"new".to_vec()));
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+        let _ = std::fs::remove_dir_all(directory);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn failed_atomic_write_leaves_nothing_behind() {
+        let path = std::env::temp_dir()
+            .join(format!(
+                "mrc-workout-creator-missing-{}",
+                std::process::id()
+            ))
+            .join("tempo.plan");
+        assert!(write_atomically(&path, b"new").is_err());
+        assert!(!path.exists());
+    }
+}
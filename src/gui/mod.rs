@@ -1,10 +1,24 @@
+/// Opening and saving files, with file dialogs on desktops
+/// and uploads and downloads in browsers.
+pub mod files;
+
 /// The main application for creating workout in
 /// the mrc format.
 pub mod mrc_creator;
 
+/// Values kept between runs, in the data directory on desktops
+/// and the `localStorage` in browsers.
+pub mod storage;
+
+/// Several workouts open at once, one of them shown.
+pub mod tabs;
+
 /// Internal styling
 pub(crate) mod style;
 
+/// The iced themes of the palettes.
+pub mod theme;
+
 /// The second page of the WorkoutCreator on which
 /// the individual efforts of the workouts are designed.
 pub mod workout_design;
@@ -1,24 +1,75 @@
+use crate::chart::palette::DEFAULT_THEME;
+use crate::gui::storage::{memory_storage, platform_storage, SharedStorage};
+use crate::gui::style::{notice, pink_button, SMALL_BUTTON};
+use crate::gui::tabs::Tabs;
 use crate::gui::workout_design::app::{WorkoutDesigner, WorkoutDesignerMessage};
-use iced::event::listen_with;
+use crate::gui::workout_design::autosave::{self, Autosave, AUTOSAVE_INTERVAL};
+use crate::gui::workout_design::settings::Settings as DesignSettings;
+use crate::workout_data::effort::Effort;
+use iced::event::{listen_with, Status};
+use iced::widget::{pick_list, Column, Row};
 use iced::window::settings::PlatformSpecific;
 use iced::Task;
 use iced::{window, Element, Settings, Theme};
 use iced_core::Size;
+use std::fmt;
+use std::time::Duration;
+
+/// How often a running session is redrawn.
+const SESSION_TICK: Duration = Duration::from_millis(250);
+
 /// Holding the state of the overall CRMCreator Application.
-pub enum MRCCreator {
-    WorkoutDesign(WorkoutDesigner),
+pub struct MRCCreator {
+    tabs: Tabs<WorkoutDesigner>,
+    storage: SharedStorage,
+    /// The text last copied in any tab with the efforts it was copied from,
+    /// which are pasted instead if the clipboard still holds that text.
+    copied: Option<(String, Vec<Effort>)>,
+    /// Autosaves of a run that did not end cleanly, offered to recover.
+    recovery: Vec<Autosave>,
+    /// Waits for the user to confirm that unsaved changes can be discarded.
+    pending_close: Option<PendingClose>,
+    /// Name of the chosen theme and the ones to choose from.
+    theme_name: String,
+    theme_names: Vec<String>,
+    theme: Theme,
 }
 
 impl Default for MRCCreator {
     fn default() -> Self {
-        MRCCreator::WorkoutDesign(WorkoutDesigner::default())
+        Self::with_storage(memory_storage())
     }
 }
 
+/// Closing something with unsaved changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PendingClose {
+    Tab(usize),
+    Window(window::Id),
+}
+
 #[derive(Debug, Clone)]
 pub enum WorkoutMessage {
     Design(WorkoutDesignerMessage),
-    IcedEvent(iced::Event),
+    IcedEvent(iced::Event, Status),
+    Tab(TabMessage),
+    ThemeSelected(String),
+    EffortsCopied(String, Vec<Effort>),
+    ClipboardRead(Option<String>),
+    AutosaveTick,
+    RecoverPressed,
+    DiscardRecoveryPressed,
+    WindowCloseRequested(window::Id),
+    CloseConfirmed,
+    CloseCancelled,
+}
+
+#[derive(Debug, Clone)]
+pub enum TabMessage {
+    Opened,
+    Selected(usize),
+    Closed(usize),
+    ComparedWith(Option<usize>),
 }
 
 impl From<WorkoutDesignerMessage> for WorkoutMessage {
@@ -27,50 +78,330 @@ impl From<WorkoutDesignerMessage> for WorkoutMessage {
     }
 }
 
+impl From<TabMessage> for WorkoutMessage {
+    fn from(tab_message: TabMessage) -> Self {
+        Self::Tab(tab_message)
+    }
+}
+
+/// A tab to pick, e.g. to compare it with the active one.
+#[derive(Debug, Clone, PartialEq)]
+struct TabChoice {
+    index: usize,
+    name: String,
+}
+
+impl fmt::Display for TabChoice {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.name)
+    }
+}
+
 impl MRCCreator {
     pub fn new() -> (Self, Task<WorkoutMessage>) {
-        (Self::default(), Task::none())
+        (Self::with_storage(platform_storage()), Task::none())
+    }
+
+    fn with_storage(storage: SharedStorage) -> Self {
+        let recovery = autosave::load(&*storage.borrow());
+        let mut creator = Self {
+            tabs: Tabs::new(WorkoutDesigner::with_storage(storage.clone())),
+            storage,
+            copied: None,
+            recovery,
+            pending_close: None,
+            theme_name: String::from(DEFAULT_THEME),
+            theme_names: Vec::new(),
+            theme: Theme::Dark,
+        };
+        creator.load_theme();
+        creator
+    }
+
+    /// Read the chosen theme and the custom palettes from the settings.
+    fn load_theme(&mut self) {
+        let settings = DesignSettings::load(&*self.storage.borrow());
+        self.theme_names = settings.theme_names();
+        self.theme_name = if self.theme_names.contains(&settings.theme) {
+            settings.theme.clone()
+        } else {
+            String::from(DEFAULT_THEME)
+        };
+        self.theme = settings.palette().theme(&self.theme_name);
     }
 
     pub fn update(&mut self, message: WorkoutMessage) -> Task<WorkoutMessage> {
         match message {
-            WorkoutMessage::Design(_) => self.handle_subpage_messages(message),
-            WorkoutMessage::IcedEvent(event) => self.handle_iced_events(event),
+            WorkoutMessage::Design(design_message) => self.tabs.active_mut().update(design_message),
+            WorkoutMessage::IcedEvent(event, status) => self
+                .tabs
+                .active_mut()
+                .update(WorkoutDesignerMessage::IcedEvent(event, status)),
+            WorkoutMessage::Tab(tab_message) => self.handle_tab_message(tab_message),
+            WorkoutMessage::ThemeSelected(name) => {
+                if let Err(error) =
+                    DesignSettings::update(&mut *self.storage.borrow_mut(), |settings| {
+                        settings.theme = name
+                    })
+                {
+                    eprintln!("Could not store the theme because of: {:?}", error);
+                }
+                self.load_theme();
+                self.tabs
+                    .iter_mut()
+                    .for_each(WorkoutDesigner::reload_from_storage);
+                Task::none()
+            }
+            WorkoutMessage::EffortsCopied(text, efforts) => {
+                self.copied = Some((text, efforts));
+                Task::none()
+            }
+            WorkoutMessage::ClipboardRead(text) => match (text, &self.copied) {
+                (Some(text), Some((copied, efforts))) if text == *copied => self
+                    .tabs
+                    .active_mut()
+                    .update(WorkoutDesignerMessage::EffortsPasted(efforts.clone())),
+                (Some(text), _) => self
+                    .tabs
+                    .active_mut()
+                    .update(WorkoutDesignerMessage::EffortsTextPasted(text)),
+                (None, _) => Task::none(),
+            },
+            WorkoutMessage::AutosaveTick => {
+                self.autosave();
+                Task::none()
+            }
+            WorkoutMessage::RecoverPressed => {
+                for autosave in std::mem::take(&mut self.recovery) {
+                    self.tabs
+                        .open(WorkoutDesigner::recovered(self.storage.clone(), autosave));
+                }
+                self.forget_pending_tab_close();
+                Task::none()
+            }
+            WorkoutMessage::DiscardRecoveryPressed => {
+                self.recovery.clear();
+                self.store_autosaves();
+                Task::none()
+            }
+            WorkoutMessage::WindowCloseRequested(id) => self.close_or_ask(PendingClose::Window(id)),
+            WorkoutMessage::CloseConfirmed => match self.pending_close.take() {
+                Some(pending_close) => self.close(pending_close),
+                None => Task::none(),
+            },
+            WorkoutMessage::CloseCancelled => {
+                self.pending_close = None;
+                Task::none()
+            }
         }
     }
 
-    pub fn view(&'_ self) -> Element<'_, WorkoutMessage> {
-        match self {
-            MRCCreator::WorkoutDesign(workout_designer) => workout_designer.view(),
+    fn handle_tab_message(&mut self, tab_message: TabMessage) -> Task<WorkoutMessage> {
+        match tab_message {
+            TabMessage::Opened => {
+                self.tabs
+                    .open(WorkoutDesigner::with_storage(self.storage.clone()));
+                self.forget_pending_tab_close();
+            }
+            TabMessage::Selected(index) => {
+                self.tabs.select(index);
+                self.tabs.active_mut().reload_from_storage();
+            }
+            TabMessage::Closed(index) => return self.close_or_ask(PendingClose::Tab(index)),
+            TabMessage::ComparedWith(index) => self.tabs.compare(index),
         }
+        Task::none()
     }
-    pub fn theme(&self) -> Theme {
-        Theme::Dark
+
+    /// Close right away, or ask first if there are unsaved changes that would be lost.
+    fn close_or_ask(&mut self, pending_close: PendingClose) -> Task<WorkoutMessage> {
+        let has_unsaved_changes = match pending_close {
+            PendingClose::Tab(index) => self
+                .tabs
+                .get(index)
+                .is_some_and(WorkoutDesigner::has_unsaved_changes),
+            PendingClose::Window(_) => self.tabs.iter().any(WorkoutDesigner::has_unsaved_changes),
+        };
+        if has_unsaved_changes {
+            self.pending_close = Some(pending_close);
+            Task::none()
+        } else {
+            self.close(pending_close)
+        }
     }
 
-    pub fn subscription(&self) -> iced::Subscription<WorkoutMessage> {
-        listen_with(|event, _, _| Some(event)).map(WorkoutMessage::IcedEvent)
+    /// A pending close refers to its tab by index, which changes
+    /// or belongs to another tab once tabs are opened or closed.
+    fn forget_pending_tab_close(&mut self) {
+        if matches!(self.pending_close, Some(PendingClose::Tab(_))) {
+            self.pending_close = None;
+        }
     }
-}
 
-impl MRCCreator {
-    fn handle_subpage_messages(&mut self, message: WorkoutMessage) -> Task<WorkoutMessage> {
-        match self {
-            MRCCreator::WorkoutDesign(workout_designer) => {
-                if let WorkoutMessage::Design(design_message) = message {
-                    workout_designer.update(design_message)
-                } else {
-                    Task::none()
+    fn close(&mut self, pending_close: PendingClose) -> Task<WorkoutMessage> {
+        match pending_close {
+            PendingClose::Tab(index) => {
+                if self.tabs.close(index).is_none() {
+                    // The last tab is replaced by an empty one instead.
+                    *self.tabs.active_mut() = WorkoutDesigner::with_storage(self.storage.clone());
                 }
+                self.forget_pending_tab_close();
+                self.store_autosaves();
+                Task::none()
+            }
+            PendingClose::Window(id) => {
+                // Only what was not recovered yet is left to recover, the changes were discarded.
+                if let Err(error) =
+                    autosave::store(self.recovery.clone(), &mut *self.storage.borrow_mut())
+                {
+                    eprintln!("Could not autosave the workouts because of: {:?}", error);
+                }
+                window::close(id)
             }
         }
     }
 
-    fn handle_iced_events(&mut self, event: iced::Event) -> Task<WorkoutMessage> {
-        match self {
-            MRCCreator::WorkoutDesign(workout_designer) => {
-                workout_designer.update(WorkoutDesignerMessage::IcedEvent(event))
-            }
+    /// Write the autosaves of all tabs, if any of them changed since the last time.
+    fn autosave(&mut self) {
+        let pending = self
+            .tabs
+            .iter_mut()
+            .fold(false, |pending, tab| tab.take_autosave_pending() | pending);
+        if pending {
+            self.store_autosaves();
+        }
+    }
+
+    /// Store the autosaves of all tabs with unsaved changes,
+    /// and those of the last run not recovered yet.
+    fn store_autosaves(&mut self) {
+        let autosaves = self
+            .recovery
+            .iter()
+            .cloned()
+            .chain(self.tabs.iter().filter_map(WorkoutDesigner::autosave))
+            .collect();
+        if let Err(error) = autosave::store(autosaves, &mut *self.storage.borrow_mut()) {
+            eprintln!("Could not autosave the workouts because of: {:?}", error);
+        }
+    }
+
+    pub fn view(&'_ self) -> Element<'_, WorkoutMessage> {
+        let content: Element<'_, WorkoutMessage> = match self.tabs.compared() {
+            Some(compared) => Row::new()
+                .padding(20)
+                .spacing(40)
+                .push(self.tabs.active().comparison_view())
+                .push(compared.comparison_view())
+                .into(),
+            None => self.tabs.active().view(),
+        };
+        Column::new()
+            .push(self.tab_bar())
+            .push_maybe(self.recovery_notice())
+            .push_maybe(self.close_notice())
+            .push(content)
+            .into()
+    }
+
+    fn tab_bar(&self) -> Row<'_, WorkoutMessage> {
+        let tab_choices: Vec<TabChoice> = self
+            .tabs
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != self.tabs.active_index())
+            .map(|(index, tab)| TabChoice {
+                index,
+                name: String::from(tab.name()),
+            })
+            .collect();
+        let selected_choice = self.tabs.compared_index().and_then(|compared| {
+            tab_choices
+                .iter()
+                .find(|choice| choice.index == compared)
+                .cloned()
+        });
+        self.tabs
+            .iter()
+            .enumerate()
+            .fold(Row::new().spacing(5).padding(10), |row, (index, tab)| {
+                let marker = if index == self.tabs.active_index() {
+                    "> "
+                } else {
+                    ""
+                };
+                let unsaved = if tab.has_unsaved_changes() { " *" } else { "" };
+                row.push(
+                    pink_button(&format!("{}{}{}", marker, tab.name(), unsaved))
+                        .on_press(WorkoutMessage::from(TabMessage::Selected(index))),
+                )
+                .push(pink_button("x").on_press(WorkoutMessage::from(TabMessage::Closed(index))))
+            })
+            .push(pink_button("+").on_press(WorkoutMessage::from(TabMessage::Opened)))
+            .push(
+                pick_list(tab_choices, selected_choice, |choice| {
+                    WorkoutMessage::from(TabMessage::ComparedWith(Some(choice.index)))
+                })
+                .placeholder("Compare with"),
+            )
+            .push_maybe(self.tabs.compared().map(|_| {
+                pink_button("Stop Comparing")
+                    .width(SMALL_BUTTON * 2.0)
+                    .on_press(WorkoutMessage::from(TabMessage::ComparedWith(None)))
+            }))
+            .push(pick_list(
+                self.theme_names.as_slice(),
+                Some(&self.theme_name),
+                |name| WorkoutMessage::ThemeSelected(name.clone()),
+            ))
+    }
+
+    fn recovery_notice(&self) -> Option<Row<'_, WorkoutMessage>> {
+        (!self.recovery.is_empty()).then(|| {
+            notice(
+                format!(
+                    "{} workouts were not saved when the application closed.",
+                    self.recovery.len()
+                ),
+                ("Recover", WorkoutMessage::RecoverPressed),
+                ("Discard", WorkoutMessage::DiscardRecoveryPressed),
+            )
+        })
+    }
+
+    fn close_notice(&self) -> Option<Row<'_, WorkoutMessage>> {
+        self.pending_close.map(|pending_close| {
+            let question = match pending_close {
+                PendingClose::Tab(_) => String::from("The workout has unsaved changes."),
+                PendingClose::Window(_) => String::from("Some workouts have unsaved changes."),
+            };
+            notice(
+                question,
+                ("Discard Changes", WorkoutMessage::CloseConfirmed),
+                ("Cancel", WorkoutMessage::CloseCancelled),
+            )
+        })
+    }
+
+    pub fn theme(&self) -> Theme {
+        self.theme.clone()
+    }
+
+    pub fn subscription(&self) -> iced::Subscription<WorkoutMessage> {
+        let events = listen_with(|event, status, _| Some((event, status)))
+            .map(|(event, status)| WorkoutMessage::IcedEvent(event, status));
+        let autosave = iced::time::every(AUTOSAVE_INTERVAL).map(|_| WorkoutMessage::AutosaveTick);
+        let close_requests = window::close_requests().map(WorkoutMessage::WindowCloseRequested);
+        if self.tabs.active().is_in_session() {
+            iced::Subscription::batch([
+                events,
+                autosave,
+                close_requests,
+                iced::time::every(SESSION_TICK)
+                    .map(|_| WorkoutMessage::from(WorkoutDesignerMessage::SessionTick)),
+            ])
+        } else {
+            iced::Subscription::batch([events, autosave, close_requests])
         }
     }
 }
@@ -99,6 +430,7 @@ pub fn window_settings() -> window::settings::Settings {
         level: window::Level::AlwaysOnTop,
         platform_specific: PlatformSpecific::default(),
         icon: None,
-        exit_on_close_request: true,
+        // Closing asks about unsaved changes first.
+        exit_on_close_request: false,
     }
 }
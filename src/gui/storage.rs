@@ -0,0 +1,181 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StorageError {
+    CouldNotWrite(String),
+}
+
+/// Text values kept between runs of the application, by key.
+pub trait Storage {
+    fn read(&self, key: &str) -> Option<String>;
+    fn write(&mut self, key: &str, value: &str) -> Result<(), StorageError>;
+    fn remove(&mut self, key: &str);
+}
+
+/// One storage used by all open workouts.
+pub type SharedStorage = Rc<RefCell<dyn Storage>>;
+
+/// Storage that is gone when the application closes.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage(HashMap<String, String>);
+
+impl Storage for MemoryStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        self.0.get(key).cloned()
+    }
+    fn write(&mut self, key: &str, value: &str) -> Result<(), StorageError> {
+        self.0.insert(String::from(key), String::from(value));
+        Ok(())
+    }
+    fn remove(&mut self, key: &str) {
+        self.0.remove(key);
+    }
+}
+
+/// One JSON file per key in a directory.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct DirectoryStorage {
+    directory: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl DirectoryStorage {
+    pub fn new(directory: PathBuf) -> Self {
+        Self { directory }
+    }
+    /// The data directory of the application, e.g. `~/.local/share/mrc-workout-creator`.
+    pub fn in_data_directory() -> Option<Self> {
+        directories_next::ProjectDirs::from("", "", "mrc-workout-creator")
+            .map(|directories| Self::new(directories.data_dir().to_path_buf()))
+    }
+    fn path(&self, key: &str) -> PathBuf {
+        self.directory.join(format!("{}.json", key))
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Storage for DirectoryStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(key)).ok()
+    }
+    fn write(&mut self, key: &str, value: &str) -> Result<(), StorageError> {
+        std::fs::create_dir_all(&self.directory)
+            .and_then(|_| crate::gui::files::write_atomically(&self.path(key), value.as_bytes()))
+            .map_err(|error| StorageError::CouldNotWrite(error.to_string()))
+    }
+    fn remove(&mut self, key: &str) {
+        let _ = std::fs::remove_file(self.path(key));
+    }
+}
+
+/// The `localStorage` of the browser, with keys prefixed by the application name.
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage {
+    storage: web_sys::Storage,
+}
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    const PREFIX: &'static str = "mrc-workout-creator.";
+
+    pub fn new() -> Option<Self> {
+        let storage = web_sys::window()?.local_storage().ok()??;
+        Some(Self { storage })
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for LocalStorage {
+    fn read(&self, key: &str) -> Option<String> {
+        self.storage
+            .get_item(&format!("{}{}", Self::PREFIX, key))
+            .ok()?
+    }
+    fn write(&mut self, key: &str, value: &str) -> Result<(), StorageError> {
+        self.storage
+            .set_item(&format!("{}{}", Self::PREFIX, key), value)
+            .map_err(|error| StorageError::CouldNotWrite(format!("{:?}", error)))
+    }
+    fn remove(&mut self, key: &str) {
+        let _ = self
+            .storage
+            .remove_item(&format!("{}{}", Self::PREFIX, key));
+    }
+}
+
+/// The storage of the platform: the data directory on desktops, `localStorage` in browsers.
+/// Falls back to memory if neither is available.
+pub fn platform_storage() -> SharedStorage {
+    #[cfg(not(target_arch = "wasm32"))]
+    let storage = DirectoryStorage::in_data_directory();
+    #[cfg(target_arch = "wasm32")]
+    let storage = LocalStorage::new();
+    match storage {
+        Some(storage) => Rc::new(RefCell::new(storage)),
+        None => memory_storage(),
+    }
+}
+
+pub fn memory_storage() -> SharedStorage {
+    Rc::new(RefCell::new(MemoryStorage::default()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn round_trip(storage: &mut dyn Storage) {
+        assert_eq!(storage.read("settings"), None);
+        storage.write("settings", "{\"ftp\": 250}").unwrap();
+        assert_eq!(storage.read("settings").as_deref(), Some("{\"ftp\": 250}"));
+        storage.remove("settings");
+        assert_eq!(storage.read("settings"), None);
+    }
+
+    #[test]
+    fn memory_storage() {
+        round_trip(&mut MemoryStorage::default());
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn directory_storage() {
+        let directory = std::env::temp_dir().join(format!(
+            "mrc-workout-creator-storage-{}",
+            std::process::id()
+        ));
+        round_trip(&mut DirectoryStorage::new(directory.clone()));
+        let _ = std::fs::remove_dir_all(directory);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    #[test]
+    fn directory_storage_replaces_files() {
+        let directory = std::env::temp_dir().join(format!(
+            "mrc-workout-creator-replace-{}",
+            std::process::id()
+        ));
+        let mut storage = DirectoryStorage::new(directory.clone());
+        storage.write("autosave", "old").unwrap();
+        storage.write("autosave", "new").unwrap();
+        assert_eq!(storage.read("autosave").as_deref(), Some("new"));
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+        let _ = std::fs::remove_dir_all(directory);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    mod browser {
+        use super::*;
+        use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+        wasm_bindgen_test_configure!(run_in_browser);
+
+        #[wasm_bindgen_test]
+        fn local_storage() {
+            round_trip(&mut LocalStorage::new().expect("Browsers have a localStorage."));
+        }
+    }
+}
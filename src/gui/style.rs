@@ -1,7 +1,6 @@
 use crate::gui::mrc_creator::WorkoutMessage;
-use iced::theme::palette::EXTENDED_DARK;
 use iced::{
-    widget::{button, text_input, Text},
+    widget::{button, text, text_input, Row, Text},
     Element,
 };
 use iced::{Background, Color, Font, Theme};
@@ -11,37 +10,6 @@ pub const TEXT_SIZE: f32 = 22.0;
 pub const LARGE_BUTTON: Length = Length::Fixed(180.0);
 pub const SMALL_BUTTON: f32 = 90.0;
 
-pub const PINK: Color = Color {
-    r: 1.0,
-    g: 0.0,
-    b: 1.0,
-    a: 1.0,
-};
-pub const PURPLE: Color = Color {
-    r: 171.0 / 255.0,
-    g: 32.0 / 255.0,
-    b: 253.0 / 255.0,
-    a: 1.0,
-};
-pub const GRAY: Color = Color {
-    r: 40.0 / 255.0,
-    g: 50.0 / 255.0,
-    b: 52.0 / 255.0,
-    a: 1.0,
-};
-pub const WHITE: Color = Color {
-    r: 1.0,
-    g: 1.0,
-    b: 1.0,
-    a: 0.8,
-};
-pub const LIGHT_WHITE: Color = Color {
-    r: 1.0,
-    g: 1.0,
-    b: 1.0,
-    a: 0.08,
-};
-
 pub fn default_font() -> Font {
     Font {
         family: iced::font::Family::Monospace,
@@ -53,52 +21,54 @@ pub fn text_with_default_font<'a>(text: String) -> Text<'a> {
     Text::new(text).font(default_font())
 }
 
+/// Buttons in the accent color, highlighted when hovered or pressed.
 struct PinkRetroButton {}
 impl PinkRetroButton {
-    pub fn style(_theme: &Theme, status: button::Status) -> button::Style {
+    pub fn style(theme: &Theme, status: button::Status) -> button::Style {
         match status {
-            button::Status::Active => PinkRetroButton::active(),
-            button::Status::Pressed => PinkRetroButton::pressed(),
-            button::Status::Hovered => PinkRetroButton::hovered(),
+            button::Status::Active => PinkRetroButton::active(theme),
+            button::Status::Pressed => PinkRetroButton::pressed(theme),
+            button::Status::Hovered => PinkRetroButton::hovered(theme),
             button::Status::Disabled => PinkRetroButton::disabled(),
         }
     }
-    fn active() -> button::Style {
+    fn active(theme: &Theme) -> button::Style {
+        let accent = theme.extended_palette().primary.base;
         button::Style {
             // shadow_offset: Vector::default(),
-            background: Some(Background::Color(PINK)),
+            background: Some(Background::Color(accent.color)),
             border: Border {
                 radius: 0.0.into(),
                 width: 0.0,
                 color: Color::TRANSPARENT,
             },
-            text_color: Color::BLACK,
+            text_color: accent.text,
             ..button::Style::default()
         }
     }
     fn disabled() -> button::Style {
         button::Style::default()
     }
-    fn hovered() -> button::Style {
+    fn hovered(theme: &Theme) -> button::Style {
         button::Style {
             border: Border {
-                color: PURPLE,
+                color: highlight(theme),
                 width: 5.0,
                 radius: 5.0.into(),
             },
-            ..PinkRetroButton::active()
+            ..PinkRetroButton::active(theme)
         }
     }
-    fn pressed() -> button::Style {
+    fn pressed(theme: &Theme) -> button::Style {
         button::Style {
             // shadow_offset: Vector { x: 5.0, y: 5.0 },
-            background: Some(Background::Color(PURPLE)),
-            ..PinkRetroButton::active()
+            background: Some(Background::Color(highlight(theme))),
+            ..PinkRetroButton::active(theme)
         }
     }
 }
 
-pub(crate) fn pink_button(text: &str) -> button::Button<'_, WorkoutMessage> {
+pub(crate) fn pink_button<'a>(text: &str) -> button::Button<'a, WorkoutMessage> {
     button::Button::new(
         text_with_default_font(String::from(text))
             .size(19.0)
@@ -108,68 +78,65 @@ pub(crate) fn pink_button(text: &str) -> button::Button<'_, WorkoutMessage> {
     .style(PinkRetroButton::style)
 }
 
-fn active_border() -> Border {
+/// The highlight color of the palette the theme was made from.
+fn highlight(theme: &Theme) -> Color {
+    theme.extended_palette().secondary.base.color
+}
+
+fn active_border(theme: &Theme) -> Border {
     Border {
         radius: 2.0.into(),
         width: 1.0,
-        color: PINK,
+        color: theme.palette().primary,
     }
 }
 
 struct PinkRetroTextInput {}
 
 impl PinkRetroTextInput {
-    pub fn style(_theme: &Theme, status: text_input::Status) -> text_input::Style {
+    pub fn style(theme: &Theme, status: text_input::Status) -> text_input::Style {
         match status {
-            text_input::Status::Active => PinkRetroTextInput::active(),
-            text_input::Status::Focused => PinkRetroTextInput::focused(),
-            text_input::Status::Hovered => PinkRetroTextInput::hovered(),
-            text_input::Status::Disabled => PinkRetroTextInput::disabled(),
+            text_input::Status::Active => PinkRetroTextInput::active(theme),
+            text_input::Status::Focused => PinkRetroTextInput::focused(theme),
+            text_input::Status::Hovered => PinkRetroTextInput::hovered(theme),
+            text_input::Status::Disabled => PinkRetroTextInput::disabled(theme),
         }
     }
 
-    fn active() -> text_input::Style {
+    fn active(theme: &Theme) -> text_input::Style {
+        let extended = theme.extended_palette();
         text_input::Style {
-            background: EXTENDED_DARK.background.base.color.into(),
-            border: active_border(),
-            icon: WHITE,
-            placeholder: PinkRetroTextInput::placeholder_color(),
-            value: PinkRetroTextInput::value_color(),
-            selection: PinkRetroTextInput::selection_color(),
+            background: extended.background.base.color.into(),
+            border: active_border(theme),
+            icon: theme.palette().text,
+            placeholder: extended.background.weak.text,
+            value: theme.palette().text,
+            selection: extended.background.strong.color,
         }
     }
-    fn disabled() -> text_input::Style {
+    fn disabled(theme: &Theme) -> text_input::Style {
         text_input::Style {
-            ..PinkRetroTextInput::active()
+            ..PinkRetroTextInput::active(theme)
         }
     }
-    fn focused() -> text_input::Style {
+    fn focused(theme: &Theme) -> text_input::Style {
         text_input::Style {
             border: Border {
-                color: PURPLE,
-                ..active_border()
+                color: highlight(theme),
+                ..active_border(theme)
             },
-            ..PinkRetroTextInput::active()
+            ..PinkRetroTextInput::active(theme)
         }
     }
-    fn hovered() -> text_input::Style {
+    fn hovered(theme: &Theme) -> text_input::Style {
         text_input::Style {
             border: Border {
-                color: PURPLE,
-                ..active_border()
+                color: highlight(theme),
+                ..active_border(theme)
             },
-            ..PinkRetroTextInput::active()
+            ..PinkRetroTextInput::active(theme)
         }
     }
-    fn placeholder_color() -> Color {
-        LIGHT_WHITE
-    }
-    fn selection_color() -> Color {
-        GRAY
-    }
-    fn value_color() -> Color {
-        WHITE
-    }
 }
 
 pub fn pink_text_input<'a>(
@@ -190,7 +157,7 @@ impl WhiteText<'_> {
         Self {
             text: text_with_default_font(white_text)
                 .size(TEXT_SIZE)
-                .color(WHITE),
+                .style(text::base),
         }
     }
     pub fn width(self, new_width: u16) -> Self {
@@ -210,3 +177,18 @@ impl<'a> From<WhiteText<'a>> for Element<'a, WorkoutMessage> {
         white_text.text.into()
     }
 }
+
+/// A question with a button to confirm and one to decline.
+pub(crate) fn notice<'a>(
+    question: String,
+    (confirm, on_confirm): (&'a str, WorkoutMessage),
+    (decline, on_decline): (&'a str, WorkoutMessage),
+) -> Row<'a, WorkoutMessage> {
+    Row::new()
+        .spacing(20)
+        .padding(10)
+        .align_y(iced::Alignment::Center)
+        .push(WhiteText::new(question))
+        .push(pink_button(confirm).on_press(on_confirm))
+        .push(pink_button(decline).on_press(on_decline))
+}
@@ -0,0 +1,130 @@
+/// Open documents, one of them active and optionally another one compared to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tabs<T> {
+    tabs: Vec<T>,
+    active: usize,
+    compared: Option<usize>,
+}
+
+impl<T> Tabs<T> {
+    pub fn new(first: T) -> Self {
+        Self {
+            tabs: vec![first],
+            active: 0,
+            compared: None,
+        }
+    }
+    /// Add `tab` at the end and make it the active one.
+    pub fn open(&mut self, tab: T) {
+        self.tabs.push(tab);
+        self.select(self.tabs.len() - 1);
+    }
+    /// Close the tab at `index`, unless it is the last one.
+    pub fn close(&mut self, index: usize) -> Option<T> {
+        if self.tabs.len() <= 1 || index >= self.tabs.len() {
+            return None;
+        }
+        let closed = self.tabs.remove(index);
+        if index < self.active || self.active == self.tabs.len() {
+            self.active -= 1;
+        }
+        self.compared = match self.compared {
+            Some(compared) if compared == index => None,
+            Some(compared) if compared > index => Some(compared - 1),
+            compared => compared,
+        };
+        Some(closed)
+    }
+    pub fn select(&mut self, index: usize) {
+        if index < self.tabs.len() {
+            self.active = index;
+            if self.compared == Some(index) {
+                self.compared = None;
+            }
+        }
+    }
+    /// Compare the tab at `index` to the active one, or stop comparing with `None`.
+    pub fn compare(&mut self, index: Option<usize>) {
+        self.compared = index.filter(|&index| index < self.tabs.len() && index != self.active);
+    }
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+    pub fn active(&self) -> &T {
+        &self.tabs[self.active]
+    }
+    pub fn active_mut(&mut self) -> &mut T {
+        &mut self.tabs[self.active]
+    }
+    pub fn compared_index(&self) -> Option<usize> {
+        self.compared
+    }
+    pub fn compared(&self) -> Option<&T> {
+        self.compared.map(|compared| &self.tabs[compared])
+    }
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.tabs.get(index)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.tabs.iter()
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.tabs.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn tabs() -> Tabs<&'static str> {
+        let mut tabs = Tabs::new("tempo");
+        tabs.open("vo2");
+        tabs.open("recovery");
+        tabs
+    }
+
+    #[test]
+    fn opened_tab_is_active() {
+        let tabs = tabs();
+        assert_eq!(tabs.active(), &"recovery");
+        assert_eq!(tabs.iter().count(), 3);
+    }
+    #[test]
+    fn closing_keeps_active_tab() {
+        let mut tabs = tabs();
+        tabs.select(1);
+        assert_eq!(tabs.close(0), Some("tempo"));
+        assert_eq!(tabs.active(), &"vo2");
+    }
+    #[test]
+    fn closing_last_active_tab_activates_previous() {
+        let mut tabs = tabs();
+        tabs.close(2);
+        assert_eq!(tabs.active(), &"vo2");
+    }
+    #[test]
+    fn last_tab_stays_open() {
+        let mut tabs = Tabs::new("tempo");
+        assert_eq!(tabs.close(0), None);
+        assert_eq!(tabs.active(), &"tempo");
+    }
+    #[test]
+    fn compared_tab_follows_closing() {
+        let mut tabs = tabs();
+        tabs.compare(Some(1));
+        tabs.close(0);
+        assert_eq!(tabs.compared(), Some(&"vo2"));
+        tabs.close(0);
+        assert_eq!(tabs.compared(), None);
+    }
+    #[test]
+    fn active_tab_is_not_compared_to_itself() {
+        let mut tabs = tabs();
+        tabs.compare(Some(2));
+        assert_eq!(tabs.compared(), None);
+        tabs.compare(Some(0));
+        tabs.select(0);
+        assert_eq!(tabs.compared(), None);
+    }
+}
@@ -0,0 +1,42 @@
+use crate::chart::geometry;
+use crate::chart::palette::Palette;
+use iced::theme::palette::{Extended, Pair};
+use iced::{Color, Theme};
+
+impl Palette {
+    /// The iced theme styling the widgets in this palette.
+    /// Besides the usual colors, the highlight becomes the secondary color,
+    /// the selection the strong and the faint color the text of the weak background.
+    pub fn theme(self, name: &str) -> Theme {
+        Theme::custom_with_fn(
+            String::from(name),
+            iced::theme::Palette {
+                background: self.background.into(),
+                text: self.text.into(),
+                primary: self.accent.into(),
+                success: iced::theme::Palette::DARK.success,
+                danger: self.error.into(),
+            },
+            move |iced_palette| {
+                let mut extended = Extended::generate(iced_palette);
+                extended.primary.base = Pair {
+                    color: self.accent.into(),
+                    text: self.on_accent.into(),
+                };
+                extended.secondary.base = Pair {
+                    color: self.highlight.into(),
+                    text: self.on_accent.into(),
+                };
+                extended.background.strong.color = self.selection.into();
+                extended.background.weak.text = self.faint.into();
+                extended
+            },
+        )
+    }
+}
+
+impl From<geometry::Color> for Color {
+    fn from(color: geometry::Color) -> Self {
+        Color::from_rgba(color.r, color.g, color.b, color.a)
+    }
+}
@@ -1,14 +1,41 @@
 use super::elements::EffortUnitInput;
+use crate::activity::segmentation::{workout_from_recording, SegmentationSettings};
+use crate::activity::Recording;
+use crate::chart::card::{summary_metrics, workout_to_html};
+use crate::chart::colors::ZoneColors;
+use crate::chart::export::{workout_to_svg, ChartOptions, PngRenderer};
+use crate::chart::layout::ChartSettings;
+use crate::chart::viewport::{Viewport, Zoom};
+use crate::gui::files::{self, Filters, OpenedFile};
 use crate::gui::mrc_creator::WorkoutMessage;
-use crate::gui::style::{pink_button, LARGE_BUTTON};
-use crate::gui::workout_design::elements;
+use crate::gui::storage::{memory_storage, SharedStorage};
+use crate::gui::style::{
+    notice, pink_button, pink_text_input, WhiteText, LARGE_BUTTON, SMALL_BUTTON,
+};
+use crate::gui::workout_design::autosave::Autosave;
+use crate::gui::workout_design::compliance::RideComparison;
+use crate::gui::workout_design::edit::EffortEdits;
+use crate::gui::workout_design::history::History;
+use crate::gui::workout_design::keymap::{Action, Keymap};
+use crate::gui::workout_design::library::Library;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::gui::workout_design::recent_files::RecentFiles;
+use crate::gui::workout_design::settings::Settings;
 use crate::gui::workout_design::visualization::core::Visualizer;
+use crate::gui::workout_design::{elements, session};
+use crate::workout_data::clipboard::{self, CopyFormat};
+use crate::workout_data::player::{SystemClock, WorkoutPlayer};
+use crate::workout_data::text_format::Thresholds;
+use crate::workout_data::validation::{contains_errors, Finding, ValidationRules};
 use crate::workout_data::workout::Workout;
 use crate::workout_data::{effort, workout};
-use dirs::home_dir;
+use iced::event::Status;
+use iced::keyboard::key::Named;
 use iced::keyboard::Event::KeyPressed;
+use iced::keyboard::Key;
 use iced::keyboard::Modifiers;
-use iced::widget::{button, container, Column, Row};
+use iced::widget::scrollable::{self, RelativeOffset};
+use iced::widget::{button, checkbox, container, pick_list, text_input, Column, Row};
 use iced::widget::{focus_next, focus_previous};
 use iced::Event::Keyboard;
 use iced::{Element, Event, Length, Task};
@@ -18,26 +45,141 @@ use iced_core::widget::{
 };
 use iced_core::Rectangle;
 use iced_runtime::task::widget;
-use rfd::FileDialog;
-use std::fs;
-use std::fs::{remove_file, File, OpenOptions};
-use std::io::Write;
-use std::path::{Path, PathBuf};
-
+use std::collections::BTreeSet;
+#[cfg(not(target_arch = "wasm32"))]
 use std::path;
 
+/// How many matching actions the command palette lists.
+const PALETTE_LENGTH: usize = 8;
+
+const WORKOUT_FILTERS: Filters<'static> = &[
+    ("MRC, Plan or JSON files", &["mrc", "plan", "json"]),
+    #[cfg(feature = "zwo")]
+    ("Zwift Workout", &["zwo"]),
+    #[cfg(feature = "fit")]
+    ("FIT Workout", &["fit"]),
+];
+/// Extensions of the formats a workout can be loaded from again,
+/// unlike images and workout cards which are only exported.
+const WORKOUT_EXTENSIONS: &[&str] = &[
+    "mrc",
+    "plan",
+    "json",
+    #[cfg(feature = "zwo")]
+    "zwo",
+    #[cfg(feature = "fit")]
+    "fit",
+];
+const RIDE_FILTERS: Filters<'static> = &[(
+    "Rides",
+    &[
+        #[cfg(feature = "fit")]
+        "fit",
+        "tcx",
+        "csv",
+    ],
+)];
+#[cfg(not(target_arch = "wasm32"))]
+const EXPORT_FILTERS: Filters<'static> = &[
+    ("MRC or Plan Files", &["mrc", "plan"]),
+    ("JSON Workout", &["json"]),
+    #[cfg(feature = "zwo")]
+    ("Zwift Workout", &["zwo"]),
+    #[cfg(feature = "fit")]
+    ("FIT Workout", &["fit"]),
+    ("Images", &["svg", "png"]),
+    ("Printable Workout Card", &["html"]),
+];
+/// Extensions of the formats offered for downloads,
+/// as browsers have no save dialog to choose one in.
+#[cfg(target_arch = "wasm32")]
+const EXPORT_EXTENSIONS: &[&str] = &[
+    "plan",
+    "mrc",
+    "json",
+    #[cfg(feature = "zwo")]
+    "zwo",
+    #[cfg(feature = "fit")]
+    "fit",
+    "svg",
+    "png",
+    "html",
+];
+
 pub struct WorkoutDesigner {
     workout: workout::Workout,
+    /// Counts the changes of the workout, to let the chart know when it needs to be redrawn.
+    workout_version: u64,
+    edits: EffortEdits,
+    /// Indices of the efforts selected to be copied.
+    selected: BTreeSet<usize>,
+    copy_format: CopyFormat,
     effort_unit_input: EffortUnitInput,
     visualizer: Visualizer,
+    ftp_input: String,
+    validation_rules: ValidationRules,
+    findings: Vec<Finding>,
+    session: Option<WorkoutPlayer<SystemClock>>,
+    ride: Option<RideComparison>,
+    storage: SharedStorage,
+    library: Library,
+    #[cfg(target_arch = "wasm32")]
+    export_extension: &'static str,
+    history: History,
+    /// Whether the workout changed since it was loaded or exported.
+    unsaved_changes: bool,
+    /// Whether the workout changed since it was autosaved.
+    autosave_pending: bool,
+    /// Waits for the user to confirm that the unsaved changes can be discarded.
+    pending_action: Option<PendingAction>,
+    keymap: Keymap,
+    /// What is searched for in the command palette, while it is open.
+    palette: Option<String>,
+    /// The workout file being edited, which is written to when saving.
+    #[cfg(not(target_arch = "wasm32"))]
+    current_file: Option<path::PathBuf>,
+    #[cfg(not(target_arch = "wasm32"))]
+    recent_files: RecentFiles,
+}
+
+/// Actions that discard unsaved changes.
+#[derive(Debug, Clone, PartialEq)]
+enum PendingAction {
+    Load,
+    OpenFromLibrary(String),
+    #[cfg(not(target_arch = "wasm32"))]
+    OpenRecent(path::PathBuf),
 }
 
 impl Default for WorkoutDesigner {
     fn default() -> Self {
         Self {
             workout: workout::Workout::new("untitled", "no description", vec![]),
+            edits: EffortEdits::default(),
+            selected: BTreeSet::new(),
+            copy_format: CopyFormat::default(),
             effort_unit_input: EffortUnitInput::default(),
             visualizer: Visualizer::default(),
+            workout_version: 0,
+            ftp_input: String::new(),
+            validation_rules: ValidationRules::default(),
+            findings: Vec::new(),
+            session: None,
+            ride: None,
+            storage: memory_storage(),
+            library: Library::default(),
+            #[cfg(target_arch = "wasm32")]
+            export_extension: EXPORT_EXTENSIONS[0],
+            history: History::default(),
+            unsaved_changes: false,
+            autosave_pending: false,
+            pending_action: None,
+            keymap: Keymap::default(),
+            palette: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            current_file: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            recent_files: RecentFiles::default(),
         }
     }
 }
@@ -48,10 +190,56 @@ pub enum WorkoutDesignerMessage {
     EffortUnitEndingValueChanged(String),
     EffortUnitInputDurationChanged(String),
     CreateTask,
-    ExportButtonPressed,
+    SavePressed,
+    SaveAsPressed,
     LoadWorkoutPressed,
-    IcedEvent(Event),
+    IcedEvent(Event, Status),
     Effort(usize, EffortMessage),
+    FtpChanged(String),
+    ZoneBandsToggled(bool),
+    PowerInPercentOfFtpToggled(bool),
+    ZoneColorsSelected(ZoneColors),
+    ZoomRequested(Zoom),
+    ChartViewportChanged(Viewport),
+    SessionStarted,
+    SessionPauseToggled,
+    SessionSkipped,
+    SessionExtended,
+    SessionStopped,
+    SessionTick,
+    ImportRidePressed,
+    RideCleared,
+    WorkoutFromRidePressed,
+    PasteWorkoutPressed,
+    WorkoutTextPasted(Option<String>),
+    WorkoutFileOpened(Option<OpenedFile>),
+    RideFileOpened(Option<OpenedFile>),
+    #[cfg(target_arch = "wasm32")]
+    ExportFormatSelected(&'static str),
+    SaveToLibraryPressed,
+    LibraryWorkoutSelected(String),
+    #[cfg(not(target_arch = "wasm32"))]
+    RecentFileSelected(path::PathBuf),
+    Undo,
+    Redo,
+    CopyFormatSelected(CopyFormat),
+    CopyEffortsPressed,
+    PasteEffortsPressed,
+    EffortsPasted(Vec<effort::Effort>),
+    EffortsTextPasted(String),
+    DiscardChangesConfirmed,
+    DiscardChangesCancelled,
+    DeleteSelectedPressed,
+    EditSelectedPressed,
+    MoveSelectedEarlierPressed,
+    MoveSelectedLaterPressed,
+    SelectAllPressed,
+    SelectionCleared,
+    PaletteOpened,
+    PaletteQueryChanged(String),
+    PaletteSubmitted,
+    PaletteClosed,
+    ActionChosen(Action),
 }
 
 #[derive(Debug, Clone)]
@@ -61,16 +249,21 @@ pub enum EffortMessage {
     UpdateStartingValue(String),
     UpdateEndingValue(String),
     UpdateDurationInMinutes(String),
+    UpdateName(String),
+    UpdateColor(String),
+    UpdateTargetRange(String),
     Delete,
+    SelectionToggled(bool),
 }
 
 impl From<Workout> for WorkoutDesigner {
     fn from(workout: Workout) -> Self {
-        Self {
+        let mut designer = Self {
             workout,
-            effort_unit_input: EffortUnitInput::default(),
-            visualizer: Visualizer::default(),
-        }
+            ..Self::default()
+        };
+        designer.validate();
+        designer
     }
 }
 
@@ -78,42 +271,321 @@ impl WorkoutDesigner {
     pub fn new(workout_name: &'_ str, workout_description: &'_ str) -> Self {
         Self {
             workout: workout::Workout::empty(workout_name, workout_description),
-            effort_unit_input: EffortUnitInput::default(),
-            visualizer: Visualizer::default(),
+            ..Self::default()
         }
     }
-    fn load_workout_from_file(&mut self) -> Task<WorkoutMessage> {
-        if let Some(file_to_read) = FileDialog::new()
-            .set_directory(path_or_home_directory(find_bike_computer()))
-            .add_filter("MRC or Plan files", &["mrc", "plan"])
-            .pick_file()
+    /// An empty designer keeping its library and chart settings in `storage`.
+    pub fn with_storage(storage: SharedStorage) -> Self {
+        let mut designer = Self {
+            storage,
+            ..Self::default()
+        };
+        designer.reload_from_storage();
+        designer
+    }
+    /// A designer continuing the autosaved workout, which is not saved yet.
+    pub fn recovered(storage: SharedStorage, autosave: Autosave) -> Self {
+        let mut designer = Self::with_storage(storage);
+        designer.replace_workout(autosave.workout);
+        designer.history = autosave.history;
+        designer.unsaved_changes = true;
+        designer.autosave_pending = true;
+        designer
+    }
+    /// Pick up what other designers changed in the shared storage.
+    pub fn reload_from_storage(&mut self) {
+        let storage = self.storage.borrow();
+        let settings = Settings::load(&*storage);
+        self.library = Library::load(&*storage);
+        #[cfg(not(target_arch = "wasm32"))]
         {
-            if let Ok(workout_to_load) = fs::read_to_string(&file_to_read) {
-                match file_to_read.extension().and_then(|e| e.to_str()) {
-                    Some("mrc") => match Workout::from_mrc(&workout_to_load) {
-                        Ok(loaded_workout) => {
-                            *self = WorkoutDesigner::from(loaded_workout);
-                        }
-                        Err(error) => {
-                            eprintln!("Could not read in the MRC file because of:");
-                            eprintln!("{:?}", error);
-                        }
-                    },
-                    Some("plan") => match Workout::from_plan_format(&workout_to_load) {
-                        Ok(loaded_workout) => {
-                            *self = WorkoutDesigner::from(loaded_workout);
-                        }
-                        Err(error) => {
-                            eprintln!("Could not read in the MRC file because of:");
-                            eprintln!("{:?}", error);
-                        }
-                    },
-                    Some(_) | None => panic!("Format not supported!"),
+            self.recent_files = RecentFiles::load(&*storage);
+        }
+        drop(storage);
+        self.ftp_input = settings
+            .chart
+            .ftp
+            .map(|ftp| ftp.to_string())
+            .unwrap_or_default();
+        self.visualizer.palette = settings.palette();
+        self.visualizer.settings = settings.chart;
+        self.keymap = Keymap::new(settings.keys);
+        self.validation_rules = settings.validation;
+        // Findings already shown follow the changed rules.
+        if !self.findings.is_empty() {
+            self.validate();
+        }
+    }
+    pub fn name(&self) -> &str {
+        self.workout.name()
+    }
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.unsaved_changes
+    }
+    /// The workout and its history to recover, if it has unsaved changes.
+    pub fn autosave(&self) -> Option<Autosave> {
+        self.unsaved_changes.then(|| Autosave {
+            workout: self.workout.clone(),
+            history: self.history.clone(),
+        })
+    }
+    /// Whether the autosave of this designer is outdated, marking it as up to date.
+    pub fn take_autosave_pending(&mut self) -> bool {
+        std::mem::take(&mut self.autosave_pending)
+    }
+    /// Start designing `workout`, keeping everything not belonging to the previous workout,
+    /// like the settings of the chart, the storage and the history.
+    fn replace_workout(&mut self, workout: Workout) {
+        self.findings = self.validation_rules.validate(&workout);
+        self.workout = workout;
+        self.workout_version += 1;
+        self.edits = EffortEdits::default();
+        self.selected.clear();
+        self.effort_unit_input = EffortUnitInput::default();
+        self.session = None;
+        self.ride = None;
+        self.visualizer.cursor_minute = None;
+        self.visualizer.set_actual_power(None);
+    }
+    /// Replace the workout by a changed version of it, which can be undone.
+    fn change_workout(&mut self, workout: Workout) {
+        let previous = self.workout.clone();
+        self.replace_workout(workout);
+        self.workout_modified(previous);
+    }
+    /// Start over with a workout read from a file or the library.
+    fn open_workout(&mut self, workout: Workout) {
+        self.replace_workout(workout);
+        self.history = History::default();
+        self.mark_saved();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.current_file = None;
+        }
+    }
+    fn mark_saved(&mut self) {
+        self.unsaved_changes = false;
+        // The autosave has to be updated to no longer contain this workout.
+        self.autosave_pending = true;
+    }
+    /// Run `action`, or wait for a confirmation first if it would discard unsaved changes.
+    fn discarding_changes(&mut self, action: PendingAction) -> Task<WorkoutMessage> {
+        if self.unsaved_changes {
+            self.pending_action = Some(action);
+            Task::none()
+        } else {
+            self.run(action)
+        }
+    }
+    fn run(&mut self, action: PendingAction) -> Task<WorkoutMessage> {
+        match action {
+            PendingAction::Load => files::open(WORKOUT_FILTERS, |file| {
+                WorkoutMessage::from(WorkoutDesignerMessage::WorkoutFileOpened(file))
+            }),
+            PendingAction::OpenFromLibrary(name) => {
+                if let Some(workout) = self.library.get(&name).cloned() {
+                    self.open_workout(workout);
                 }
+                Task::none()
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            PendingAction::OpenRecent(path) => {
+                if let Some(file) = files::read(&path) {
+                    self.load_workout(&file);
+                }
+                Task::none()
             }
         }
+    }
+    fn store_settings(&mut self) {
+        let chart = self.visualizer.settings.clone();
+        let stored = Settings::update(&mut *self.storage.borrow_mut(), |settings| {
+            settings.chart = chart;
+        });
+        if let Err(error) = stored {
+            eprintln!("Could not store the settings because of: {:?}", error);
+        }
+    }
+    fn save_to_library(&mut self) {
+        self.library.save(&self.workout);
+        let stored = self.library.store(&mut *self.storage.borrow_mut());
+        match stored {
+            Ok(()) => self.mark_saved(),
+            Err(error) => eprintln!("Could not store the library because of: {:?}", error),
+        }
+    }
+    /// Whether a workout is being played right now.
+    pub fn is_in_session(&self) -> bool {
+        self.session.is_some()
+    }
+    fn move_cursor_to_session(&mut self) {
+        self.visualizer.cursor_minute = self.session.as_ref().map(WorkoutPlayer::planned_minute);
+    }
+    fn validate(&mut self) {
+        self.findings = self.validation_rules.validate(&self.workout);
+    }
+    /// Remember the `previous` version of the modified workout to undo to.
+    fn workout_modified(&mut self, previous: Workout) {
+        self.history.record(previous);
+        self.workout_changed();
+    }
+    fn workout_changed(&mut self) {
+        self.workout_version += 1;
+        self.selected.clear();
+        self.unsaved_changes = true;
+        self.autosave_pending = true;
+        self.validate();
+        if let Some(ride) = &mut self.ride {
+            ride.reanalyze(&self.workout);
+        }
+    }
+    fn import_ride(&mut self, file: OpenedFile) {
+        let extension = file.extension().map(String::from);
+        match Recording::parse(extension.as_deref(), file.contents) {
+            Ok(recording) => {
+                let ride = RideComparison::new(&self.workout, recording);
+                self.visualizer.set_actual_power(Some(ride.actual_power()));
+                self.ride = Some(ride);
+            }
+            Err(error) => {
+                eprintln!("Could not read in the ride because of:");
+                eprintln!("{:?}", error);
+            }
+        }
+    }
+    fn load_workout(&mut self, file: &OpenedFile) {
+        if let Some(loaded_workout) = self.read_workout(file) {
+            self.open_workout(loaded_workout);
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(path) = &file.path {
+                self.current_file = Some(path.clone());
+                self.remember_recent_file(path.clone());
+            }
+        }
+    }
+    fn read_workout(&self, file: &OpenedFile) -> Option<Workout> {
+        Workout::from_file(
+            file.extension(),
+            &file.contents,
+            self.visualizer.settings.ftp,
+        )
+        .map_err(|error| {
+            eprintln!("Could not read in {} because of:", file.name);
+            eprintln!("{:?}", error);
+        })
+        .ok()
+    }
+    /// The workout written in the format belonging to `extension`.
+    fn export_contents(&self, extension: &str) -> Option<Vec<u8>> {
+        match extension {
+            "svg" => Some(workout_to_svg(&self.workout, &self.chart_options()).into_bytes()),
+            "html" => {
+                Some(workout_to_html(&self.workout, self.visualizer.settings.ftp).into_bytes())
+            }
+            "png" => PngRenderer::default()
+                .render(&self.workout, &self.chart_options())
+                .map_err(|error| {
+                    eprintln!("Could not render workout because of:");
+                    eprintln!("{:?}", error);
+                })
+                .ok(),
+            _ => self
+                .workout
+                .to_file(extension, self.visualizer.settings.ftp)
+                .map_err(|error| {
+                    eprintln!(
+                        "Could not write the workout as .{} file because of:",
+                        extension
+                    );
+                    eprintln!("{:?}", error);
+                })
+                .ok(),
+        }
+    }
+    /// Images are exported in the colors of the chart.
+    fn chart_options(&self) -> ChartOptions {
+        ChartOptions {
+            settings: ChartSettings {
+                zone_colors: self.visualizer.settings.zone_colors,
+                ..ChartSettings::default()
+            },
+            palette: self.visualizer.palette,
+            ..ChartOptions::default()
+        }
+    }
+    /// Whether the workout has no errors and can be saved.
+    fn can_be_saved(&mut self) -> bool {
+        self.validate();
+        !contains_errors(&self.findings)
+    }
+    /// Write to the file being edited, or ask where to save if there is none yet.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save(&mut self) -> Task<WorkoutMessage> {
+        match self.current_file.clone() {
+            Some(path) => {
+                self.write_to(path);
+                Task::none()
+            }
+            None => self.save_as(),
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn save_as(&mut self) -> Task<WorkoutMessage> {
+        if let Some(path) = files::pick_save_path(EXPORT_FILTERS) {
+            self.write_to(make_it_plan_if_none(path));
+        }
         Task::none()
     }
+    /// Write the workout in the format belonging to the extension of `path`.
+    /// Workout formats make `path` the file being edited, images and cards are only exported.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_to(&mut self, path: path::PathBuf) {
+        let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+            return;
+        };
+        let is_workout_format = WORKOUT_EXTENSIONS.contains(&extension);
+        if let Some(file_contents) = self.export_contents(extension) {
+            match files::write_atomically(&path, &file_contents) {
+                Ok(()) if is_workout_format => {
+                    self.current_file = Some(path.clone());
+                    self.remember_recent_file(path);
+                    self.mark_saved();
+                }
+                Ok(()) => {}
+                Err(error) => {
+                    eprintln!("Could not write workout because of:");
+                    eprintln!("{}", error);
+                }
+            }
+        }
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn remember_recent_file(&mut self, path: path::PathBuf) {
+        self.recent_files.add(path);
+        if let Err(error) = self.recent_files.store(&mut *self.storage.borrow_mut()) {
+            eprintln!("Could not store the recent files because of: {:?}", error);
+        }
+    }
+    /// Browsers cannot write to the uploaded file, so saving always downloads.
+    #[cfg(target_arch = "wasm32")]
+    fn save(&mut self) -> Task<WorkoutMessage> {
+        match self.export_contents(self.export_extension) {
+            Some(file_contents) => {
+                if WORKOUT_EXTENSIONS.contains(&self.export_extension) {
+                    self.mark_saved();
+                }
+                files::download(
+                    format!("{}.{}", self.workout.name(), self.export_extension),
+                    file_contents,
+                )
+            }
+            None => Task::none(),
+        }
+    }
+    #[cfg(target_arch = "wasm32")]
+    fn save_as(&mut self) -> Task<WorkoutMessage> {
+        self.save()
+    }
     pub fn update(&mut self, message: WorkoutDesignerMessage) -> Task<WorkoutMessage> {
         match message {
             WorkoutDesignerMessage::EffortUnitStartingValueChanged(value) => {
@@ -131,41 +603,383 @@ impl WorkoutDesigner {
             WorkoutDesignerMessage::CreateTask => {
                 if !self.effort_unit_input.is_empty() {
                     if let Ok(effort) = effort::Effort::try_from(self.effort_unit_input.clone()) {
+                        let previous = self.workout.clone();
                         self.workout.add_effort(effort);
                         self.effort_unit_input.clear();
+                        self.workout_modified(previous);
                     }
                 }
                 Task::none()
             }
-            WorkoutDesignerMessage::ExportButtonPressed => {
-                if let Some(file_to_write_to) = FileDialog::new()
-                    .set_directory(path_or_home_directory(find_bike_computer()))
-                    .add_filter("MRC or Plan Files", &["mrc", "plan"])
-                    .save_file()
-                {
-                    let file_to_write_to = &make_it_plan_if_none(file_to_write_to);
-                    if let Some(mut opened_file) = open_or_create(file_to_write_to) {
-                        if let Some(file_contents) =
-                            match file_to_write_to.extension().and_then(|e| e.to_str()) {
-                                Some("plan") => Some(self.workout.to_plan_format()),
-                                Some("mrc") => Some(self.workout.to_mrc()),
-                                Some(_) | None => None,
-                            }
-                        {
-                            if let Err(error) = opened_file.write(file_contents.as_bytes()) {
-                                eprintln!("Could not write workout because of:");
-                                eprintln!("{}", error);
-                            }
-                        }
-                    }
-                };
+            WorkoutDesignerMessage::SavePressed if self.can_be_saved() => self.save(),
+            WorkoutDesignerMessage::SaveAsPressed if self.can_be_saved() => self.save_as(),
+            WorkoutDesignerMessage::SavePressed | WorkoutDesignerMessage::SaveAsPressed => {
+                Task::none()
+            }
+            WorkoutDesignerMessage::LoadWorkoutPressed => {
+                self.discarding_changes(PendingAction::Load)
+            }
+            WorkoutDesignerMessage::WorkoutFileOpened(file) => {
+                if let Some(file) = file {
+                    self.load_workout(&file);
+                }
                 Task::none()
             }
-            WorkoutDesignerMessage::LoadWorkoutPressed => self.load_workout_from_file(),
-            WorkoutDesignerMessage::IcedEvent(event) => handle_keyboard_inputs(event),
+            WorkoutDesignerMessage::IcedEvent(event, status) => {
+                self.handle_keyboard_inputs(event, status)
+            }
             WorkoutDesignerMessage::Effort(index, effort_message) => {
                 self.handle_effort_message(index, effort_message)
             }
+            WorkoutDesignerMessage::FtpChanged(ftp) => {
+                self.visualizer.settings.ftp = ftp.parse().ok().filter(|&ftp: &f64| ftp > 0.0);
+                self.ftp_input = ftp;
+                self.store_settings();
+                Task::none()
+            }
+            WorkoutDesignerMessage::ZoneBandsToggled(show_zone_bands) => {
+                self.visualizer.settings.show_zone_bands = show_zone_bands;
+                self.store_settings();
+                Task::none()
+            }
+            WorkoutDesignerMessage::PowerInPercentOfFtpToggled(power_in_percent_of_ftp) => {
+                self.visualizer.settings.power_in_percent_of_ftp = power_in_percent_of_ftp;
+                self.store_settings();
+                Task::none()
+            }
+            WorkoutDesignerMessage::ZoneColorsSelected(zone_colors) => {
+                self.visualizer.settings.zone_colors = zone_colors;
+                self.store_settings();
+                Task::none()
+            }
+            WorkoutDesignerMessage::ZoomRequested(zoom) => {
+                self.visualizer.request_zoom(zoom);
+                Task::none()
+            }
+            WorkoutDesignerMessage::ChartViewportChanged(viewport) => {
+                let efforts = self.workout.efforts();
+                let first_effort = viewport.first_effort(efforts);
+                scrollable::snap_to(
+                    elements::effort_list_id(),
+                    RelativeOffset {
+                        x: 0.0,
+                        y: first_effort as f32 / efforts.len().saturating_sub(1).max(1) as f32,
+                    },
+                )
+            }
+            WorkoutDesignerMessage::SessionStarted => {
+                let mut player = WorkoutPlayer::new(&self.workout, SystemClock::default());
+                player.play();
+                self.session = Some(player);
+                self.move_cursor_to_session();
+                Task::none()
+            }
+            WorkoutDesignerMessage::SessionPauseToggled => {
+                if let Some(player) = &mut self.session {
+                    player.toggle_pause();
+                }
+                Task::none()
+            }
+            WorkoutDesignerMessage::SessionSkipped => {
+                if let Some(player) = &mut self.session {
+                    player.skip();
+                }
+                self.move_cursor_to_session();
+                Task::none()
+            }
+            WorkoutDesignerMessage::SessionExtended => {
+                if let Some(player) = &mut self.session {
+                    player.extend(session::EXTENSION);
+                }
+                Task::none()
+            }
+            WorkoutDesignerMessage::SessionStopped => {
+                self.session = None;
+                self.move_cursor_to_session();
+                Task::none()
+            }
+            WorkoutDesignerMessage::SessionTick => {
+                self.move_cursor_to_session();
+                Task::none()
+            }
+            WorkoutDesignerMessage::ImportRidePressed => files::open(RIDE_FILTERS, |file| {
+                WorkoutMessage::from(WorkoutDesignerMessage::RideFileOpened(file))
+            }),
+            WorkoutDesignerMessage::RideFileOpened(file) => {
+                if let Some(file) = file {
+                    self.import_ride(file);
+                }
+                Task::none()
+            }
+            WorkoutDesignerMessage::RideCleared => {
+                self.ride = None;
+                self.visualizer.set_actual_power(None);
+                Task::none()
+            }
+            WorkoutDesignerMessage::WorkoutFromRidePressed => {
+                if let Some(ride) = &self.ride {
+                    let workout = workout_from_recording(
+                        ride.recording(),
+                        self.workout.name(),
+                        &SegmentationSettings::default(),
+                    );
+                    self.change_workout(workout);
+                }
+                Task::none()
+            }
+            WorkoutDesignerMessage::PasteWorkoutPressed => iced::clipboard::read()
+                .map(|text| WorkoutMessage::from(WorkoutDesignerMessage::WorkoutTextPasted(text))),
+            WorkoutDesignerMessage::WorkoutTextPasted(text) => {
+                if let Some(text) = text {
+                    self.paste_workout(&text);
+                }
+                Task::none()
+            }
+            #[cfg(target_arch = "wasm32")]
+            WorkoutDesignerMessage::ExportFormatSelected(extension) => {
+                self.export_extension = extension;
+                Task::none()
+            }
+            WorkoutDesignerMessage::SaveToLibraryPressed => {
+                self.save_to_library();
+                Task::none()
+            }
+            WorkoutDesignerMessage::LibraryWorkoutSelected(name) => {
+                self.discarding_changes(PendingAction::OpenFromLibrary(name))
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            WorkoutDesignerMessage::RecentFileSelected(path) => {
+                self.discarding_changes(PendingAction::OpenRecent(path))
+            }
+            WorkoutDesignerMessage::Undo => {
+                if let Some(previous) = self.history.undo(self.workout.clone()) {
+                    self.workout = previous;
+                    self.edits = EffortEdits::default();
+                    self.workout_changed();
+                }
+                Task::none()
+            }
+            WorkoutDesignerMessage::Redo => {
+                if let Some(next) = self.history.redo(self.workout.clone()) {
+                    self.workout = next;
+                    self.edits = EffortEdits::default();
+                    self.workout_changed();
+                }
+                Task::none()
+            }
+            WorkoutDesignerMessage::CopyFormatSelected(format) => {
+                self.copy_format = format;
+                Task::none()
+            }
+            WorkoutDesignerMessage::CopyEffortsPressed => self.copy_efforts(),
+            WorkoutDesignerMessage::PasteEffortsPressed => {
+                iced::clipboard::read().map(WorkoutMessage::ClipboardRead)
+            }
+            WorkoutDesignerMessage::EffortsPasted(efforts) => {
+                self.paste_efforts(efforts);
+                Task::none()
+            }
+            WorkoutDesignerMessage::EffortsTextPasted(text) => {
+                match clipboard::paste(&text, &self.thresholds()) {
+                    Ok(pasted) => self.paste_efforts(pasted.efforts().to_vec()),
+                    Err(error) => eprintln!("Could not paste the efforts because of: {:?}", error),
+                }
+                Task::none()
+            }
+            WorkoutDesignerMessage::DiscardChangesConfirmed => match self.pending_action.take() {
+                Some(action) => self.run(action),
+                None => Task::none(),
+            },
+            WorkoutDesignerMessage::DiscardChangesCancelled => {
+                self.pending_action = None;
+                Task::none()
+            }
+            WorkoutDesignerMessage::DeleteSelectedPressed => {
+                self.delete_selected();
+                Task::none()
+            }
+            WorkoutDesignerMessage::EditSelectedPressed => {
+                for &index in &self.selected {
+                    self.edits.start(index, &self.workout.efforts[index]);
+                }
+                Task::none()
+            }
+            WorkoutDesignerMessage::MoveSelectedEarlierPressed => {
+                self.move_selected(Workout::move_efforts_earlier);
+                Task::none()
+            }
+            WorkoutDesignerMessage::MoveSelectedLaterPressed => {
+                self.move_selected(Workout::move_efforts_later);
+                Task::none()
+            }
+            WorkoutDesignerMessage::SelectAllPressed => {
+                self.selected = (0..self.workout.efforts().len()).collect();
+                Task::none()
+            }
+            WorkoutDesignerMessage::SelectionCleared => {
+                self.selected.clear();
+                Task::none()
+            }
+            WorkoutDesignerMessage::PaletteOpened => {
+                self.palette = Some(String::new());
+                text_input::focus(palette_id())
+            }
+            WorkoutDesignerMessage::PaletteQueryChanged(query) => {
+                self.palette = Some(query);
+                Task::none()
+            }
+            WorkoutDesignerMessage::PaletteSubmitted => {
+                let best_match = self
+                    .palette
+                    .take()
+                    .and_then(|query| self.keymap.search(&query).first().copied());
+                match best_match {
+                    Some(action) => self.perform(action),
+                    None => Task::none(),
+                }
+            }
+            WorkoutDesignerMessage::PaletteClosed => {
+                self.palette = None;
+                Task::none()
+            }
+            WorkoutDesignerMessage::ActionChosen(action) => {
+                self.palette = None;
+                self.perform(action)
+            }
+        }
+    }
+    /// The messages doing what `action` stands for.
+    fn perform(&self, action: Action) -> Task<WorkoutMessage> {
+        let message = match action {
+            Action::AddEffort => WorkoutDesignerMessage::CreateTask,
+            Action::DeleteSelected => WorkoutDesignerMessage::DeleteSelectedPressed,
+            Action::EditSelected => WorkoutDesignerMessage::EditSelectedPressed,
+            Action::MoveSelectedEarlier => WorkoutDesignerMessage::MoveSelectedEarlierPressed,
+            Action::MoveSelectedLater => WorkoutDesignerMessage::MoveSelectedLaterPressed,
+            Action::SelectAll => WorkoutDesignerMessage::SelectAllPressed,
+            Action::ClearSelection => WorkoutDesignerMessage::SelectionCleared,
+            Action::CopyEfforts => WorkoutDesignerMessage::CopyEffortsPressed,
+            Action::PasteEfforts => WorkoutDesignerMessage::PasteEffortsPressed,
+            Action::PasteWorkout => WorkoutDesignerMessage::PasteWorkoutPressed,
+            Action::Undo => WorkoutDesignerMessage::Undo,
+            Action::Redo => WorkoutDesignerMessage::Redo,
+            Action::Open => WorkoutDesignerMessage::LoadWorkoutPressed,
+            Action::Save => WorkoutDesignerMessage::SavePressed,
+            Action::SaveAs => WorkoutDesignerMessage::SaveAsPressed,
+            Action::SaveToLibrary => WorkoutDesignerMessage::SaveToLibraryPressed,
+            Action::Play if self.session.is_none() => WorkoutDesignerMessage::SessionStarted,
+            Action::Play => return Task::none(),
+            Action::ImportRide => WorkoutDesignerMessage::ImportRidePressed,
+            Action::FocusDuration => return focus_id::<WorkoutMessage>(0),
+            Action::FocusStartingValue => return focus_id::<WorkoutMessage>(1),
+            Action::FocusEndingValue => return focus_id::<WorkoutMessage>(2),
+            Action::ZoomIn => WorkoutDesignerMessage::ZoomRequested(Zoom::In),
+            Action::ZoomOut => WorkoutDesignerMessage::ZoomRequested(Zoom::Out),
+            Action::ResetZoom => WorkoutDesignerMessage::ZoomRequested(Zoom::Reset),
+            Action::CommandPalette => WorkoutDesignerMessage::PaletteOpened,
+        };
+        Task::done(WorkoutMessage::from(message))
+    }
+    fn handle_keyboard_inputs(&self, event: Event, status: Status) -> Task<WorkoutMessage> {
+        let Keyboard(KeyPressed { key, modifiers, .. }) = event else {
+            return ignore_event();
+        };
+        match key {
+            Key::Named(Named::Escape) if self.palette.is_some() => {
+                Task::done(WorkoutMessage::from(WorkoutDesignerMessage::PaletteClosed))
+            }
+            Key::Named(Named::Tab) if modifiers == Modifiers::SHIFT => {
+                focus_previous::<WorkoutMessage>()
+            }
+            Key::Named(Named::Tab) => focus_next::<WorkoutMessage>(),
+            _ => match self
+                .keymap
+                .action(&key, modifiers, status == Status::Captured)
+            {
+                Some(action) => self.perform(action),
+                None => ignore_event(),
+            },
+        }
+    }
+    fn delete_selected(&mut self) {
+        if self.selected.is_empty() {
+            return;
+        }
+        let previous = self.workout.clone();
+        for &index in self.selected.iter().rev() {
+            self.workout.remove(index);
+            self.edits.effort_removed(index);
+        }
+        self.workout_modified(previous);
+    }
+    /// Move the selected efforts with `move_efforts`, keeping them selected.
+    fn move_selected(
+        &mut self,
+        move_efforts: fn(&mut Workout, &BTreeSet<usize>) -> BTreeSet<usize>,
+    ) {
+        let previous = self.workout.clone();
+        let moved = move_efforts(&mut self.workout, &self.selected);
+        if self.workout != previous {
+            self.edits = EffortEdits::default();
+            self.workout_modified(previous);
+        }
+        self.selected = moved;
+    }
+    /// The selected efforts, or all of them if none is selected.
+    fn selected_efforts(&self) -> Vec<effort::Effort> {
+        if self.selected.is_empty() {
+            return self.workout.efforts().to_vec();
+        }
+        self.selected
+            .iter()
+            .filter_map(|&index| self.workout.efforts().get(index).cloned())
+            .collect()
+    }
+    /// Write the selected efforts to the clipboard in the chosen format.
+    /// The application keeps them as well, to paste them exactly as they were copied.
+    fn copy_efforts(&self) -> Task<WorkoutMessage> {
+        let efforts = self.selected_efforts();
+        let copied = Workout::new(
+            self.workout.name(),
+            self.workout.description(),
+            efforts.clone(),
+        );
+        let text = clipboard::copy(&copied, self.copy_format, self.visualizer.settings.ftp);
+        Task::batch([
+            iced::clipboard::write(text.clone()),
+            Task::done(WorkoutMessage::EffortsCopied(text, efforts)),
+        ])
+    }
+    fn thresholds(&self) -> Thresholds {
+        Thresholds {
+            ftp: self.visualizer.settings.ftp,
+            lthr: None,
+        }
+    }
+    /// Insert `efforts` after the last selected effort, or at the end if none is selected.
+    fn paste_efforts(&mut self, efforts: Vec<effort::Effort>) {
+        if efforts.is_empty() {
+            return;
+        }
+        let previous = self.workout.clone();
+        let position = self
+            .selected
+            .last()
+            .map_or(self.workout.efforts.len(), |&index| index + 1);
+        self.edits.efforts_inserted(position, efforts.len());
+        self.workout.efforts.splice(position..position, efforts);
+        self.workout_modified(previous);
+    }
+    /// Replace the efforts by the ones of the pasted text in any format it is recognised in,
+    /// keeping name and description.
+    fn paste_workout(&mut self, text: &str) {
+        match clipboard::paste(text, &self.thresholds()) {
+            Ok(pasted) => self.change_workout(Workout::new(
+                self.workout.name(),
+                self.workout.description(),
+                pasted.efforts().to_vec(),
+            )),
+            Err(error) => eprintln!("Could not paste the workout because of: {:?}", error),
         }
     }
     pub fn handle_effort_message(
@@ -175,27 +989,71 @@ impl WorkoutDesigner {
     ) -> Task<WorkoutMessage> {
         match effort_message {
             EffortMessage::Delete => {
+                let previous = self.workout.clone();
                 self.workout.remove(index);
+                self.edits.effort_removed(index);
+                self.workout_modified(previous);
+                Task::none()
+            }
+            EffortMessage::SelectionToggled(true) => {
+                self.selected.insert(index);
+                Task::none()
+            }
+            EffortMessage::SelectionToggled(false) => {
+                self.selected.remove(&index);
                 Task::none()
             }
             EffortMessage::Edit => {
-                self.workout.to_edit(index);
+                self.edits.start(index, &self.workout.efforts[index]);
                 Task::none()
             }
             EffortMessage::ModificationDone => {
-                self.workout.to_idle(index);
+                if let Some(edited_effort) = self
+                    .edits
+                    .get(index)
+                    .and_then(|edit| edit.apply(&self.workout.efforts[index]))
+                {
+                    let previous = self.workout.clone();
+                    self.workout.efforts[index] = edited_effort;
+                    self.edits.finish(index);
+                    self.workout_modified(previous);
+                }
                 Task::none()
             }
             EffortMessage::UpdateDurationInMinutes(updated_duration_in_minutes) => {
-                self.workout.efforts[index].update_duration_of_effort(updated_duration_in_minutes);
+                if let Some(edit) = self.edits.get_mut(index) {
+                    edit.duration_in_minutes = updated_duration_in_minutes;
+                }
                 Task::none()
             }
             EffortMessage::UpdateStartingValue(updated_value) => {
-                self.workout.efforts[index].update_starting_value(updated_value);
+                if let Some(edit) = self.edits.get_mut(index) {
+                    edit.starting_value = updated_value;
+                }
                 Task::none()
             }
             EffortMessage::UpdateEndingValue(updated_value) => {
-                self.workout.efforts[index].update_ending_value(updated_value);
+                if let Some(edit) = self.edits.get_mut(index) {
+                    edit.ending_value = updated_value;
+                }
+                Task::none()
+            }
+            EffortMessage::UpdateName(updated_name) => {
+                if let Some(edit) = self.edits.get_mut(index) {
+                    edit.name = updated_name;
+                }
+                Task::none()
+            }
+            EffortMessage::UpdateColor(updated_color) => {
+                if let Some(edit) = self.edits.get_mut(index) {
+                    edit.color = updated_color;
+                }
+                Task::none()
+            }
+            EffortMessage::UpdateTargetRange(updated_target_range) => {
+                if let Some(edit) = self.edits.get_mut(index) {
+                    edit.target_range = updated_target_range;
+                }
                 Task::none()
             }
         }
@@ -212,6 +1070,8 @@ impl WorkoutDesigner {
 
     fn elements(&self) -> Column<'_, WorkoutMessage> {
         elements::base_design()
+            .push_maybe(self.unsaved_changes_notice())
+            .push_maybe(self.command_palette())
             .push(self.effort_unit_input.view())
             .push(
                 Row::new()
@@ -220,28 +1080,139 @@ impl WorkoutDesigner {
                     .push(self.display_main_page()),
             )
     }
-    fn display_main_page(&self) -> Row<'_, WorkoutMessage> {
-        let cloned_workout = self.workout.clone();
-        Row::new()
-            .push(self.visualizer.view(cloned_workout))
+    fn unsaved_changes_notice(&self) -> Option<Row<'_, WorkoutMessage>> {
+        self.pending_action.as_ref().map(|_| {
+            notice(
+                String::from("The workout has unsaved changes."),
+                (
+                    "Discard Changes",
+                    WorkoutMessage::from(WorkoutDesignerMessage::DiscardChangesConfirmed),
+                ),
+                (
+                    "Cancel",
+                    WorkoutMessage::from(WorkoutDesignerMessage::DiscardChangesCancelled),
+                ),
+            )
+        })
+    }
+    /// Lists the actions matching the search, the first one being run on enter.
+    fn command_palette(&self) -> Option<Column<'_, WorkoutMessage>> {
+        let query = self.palette.as_ref()?;
+        let search = pink_text_input("Type a command", query)
+            .id(palette_id())
+            .on_input(|query| {
+                WorkoutMessage::from(WorkoutDesignerMessage::PaletteQueryChanged(query))
+            })
+            .on_submit(WorkoutMessage::from(
+                WorkoutDesignerMessage::PaletteSubmitted,
+            ));
+        Some(
+            self.keymap
+                .search(query)
+                .into_iter()
+                .take(PALETTE_LENGTH)
+                .fold(
+                    Column::new().spacing(5).padding(10).width(500).push(search),
+                    |palette, action| {
+                        let label = match self.keymap.chord(action) {
+                            Some(chord) => format!("{} ({})", action.label(), chord),
+                            None => String::from(action.label()),
+                        };
+                        palette.push(pink_button(&label).width(Length::Fill).on_press(
+                            WorkoutMessage::from(WorkoutDesignerMessage::ActionChosen(action)),
+                        ))
+                    },
+                ),
+        )
+    }
+    /// Name, chart and key figures of the workout, to compare it with another one.
+    pub fn comparison_view(&self) -> Column<'_, WorkoutMessage> {
+        summary_metrics(&self.workout, self.visualizer.settings.ftp)
+            .into_iter()
+            .fold(
+                Column::new()
+                    .spacing(10)
+                    .width(Length::FillPortion(1))
+                    .push(WhiteText::new(String::from(self.workout.name())))
+                    .push(self.visualizer.view(&self.workout, self.workout_version)),
+                |column, (metric, value)| {
+                    column.push(WhiteText::new(format!("{}: {}", metric, value)))
+                },
+            )
+    }
+    fn display_main_page(&self) -> Column<'_, WorkoutMessage> {
+        Column::new()
+            .spacing(10)
+            .push(self.chart_settings())
+            .push(self.visualizer.view(&self.workout, self.workout_version))
             .width(Length::FillPortion(2))
     }
+    fn chart_settings(&self) -> Row<'_, WorkoutMessage> {
+        Row::new()
+            .spacing(20)
+            .align_y(iced::Alignment::Center)
+            .push(
+                pink_text_input("FTP", &self.ftp_input)
+                    .width(120)
+                    .on_input(|ftp| WorkoutMessage::from(WorkoutDesignerMessage::FtpChanged(ftp))),
+            )
+            .push(
+                checkbox("Zones", self.visualizer.settings.show_zone_bands).on_toggle(
+                    |show_zone_bands| {
+                        WorkoutMessage::from(WorkoutDesignerMessage::ZoneBandsToggled(
+                            show_zone_bands,
+                        ))
+                    },
+                ),
+            )
+            .push(
+                checkbox("% FTP", self.visualizer.settings.power_in_percent_of_ftp).on_toggle(
+                    |power_in_percent_of_ftp| {
+                        WorkoutMessage::from(WorkoutDesignerMessage::PowerInPercentOfFtpToggled(
+                            power_in_percent_of_ftp,
+                        ))
+                    },
+                ),
+            )
+            .push(pick_list(
+                ZoneColors::ALL,
+                Some(self.visualizer.settings.zone_colors),
+                |zone_colors| {
+                    WorkoutMessage::from(WorkoutDesignerMessage::ZoneColorsSelected(zone_colors))
+                },
+            ))
+    }
 
     fn display_workout_and_buttons(&self) -> Column<'_, WorkoutMessage> {
         Column::new()
-            .push(self.workout.view())
-            .push(self.show_buttons())
+            .push(self.workout.view(
+                &self.findings,
+                &self.edits,
+                &self.selected,
+                &self.visualizer.palette,
+            ))
+            .push(match &self.session {
+                Some(player) => session::view(player),
+                None => Column::new().push(self.show_buttons()),
+            })
+            .push(self.clipboard_controls())
+            .push(self.library_controls())
+            .push_maybe(self.ride.as_ref().map(RideComparison::view))
             .width(Length::FillPortion(1))
             .spacing(20)
             .align_x(iced::Alignment::Center)
     }
-    fn visualize_export_button(&self) -> button::Button<'_, WorkoutMessage> {
-        pink_button("Export Workout")
+    fn visualize_save_button(&self) -> button::Button<'_, WorkoutMessage> {
+        pink_button("Save")
             .height(60.0)
-            .width(LARGE_BUTTON)
-            .on_press(WorkoutMessage::from(
-                WorkoutDesignerMessage::ExportButtonPressed,
-            ))
+            .width(SMALL_BUTTON)
+            .on_press(WorkoutMessage::from(WorkoutDesignerMessage::SavePressed))
+    }
+    fn visualize_save_as_button(&self) -> button::Button<'_, WorkoutMessage> {
+        pink_button("Save As")
+            .height(60.0)
+            .width(SMALL_BUTTON)
+            .on_press(WorkoutMessage::from(WorkoutDesignerMessage::SaveAsPressed))
     }
     fn visualize_load_button(&self) -> button::Button<'_, WorkoutMessage> {
         pink_button("Load Workout")
@@ -251,68 +1222,123 @@ impl WorkoutDesigner {
                 WorkoutDesignerMessage::LoadWorkoutPressed,
             ))
     }
+    fn visualize_play_button(&self) -> button::Button<'_, WorkoutMessage> {
+        pink_button("Play Workout")
+            .height(60.0)
+            .width(LARGE_BUTTON)
+            .on_press(WorkoutMessage::from(WorkoutDesignerMessage::SessionStarted))
+    }
+    fn visualize_import_ride_button(&self) -> button::Button<'_, WorkoutMessage> {
+        pink_button("Import Ride")
+            .height(60.0)
+            .width(LARGE_BUTTON)
+            .on_press(WorkoutMessage::from(
+                WorkoutDesignerMessage::ImportRidePressed,
+            ))
+    }
+    fn visualize_paste_button(&self) -> button::Button<'_, WorkoutMessage> {
+        pink_button("Paste Workout")
+            .height(60.0)
+            .width(LARGE_BUTTON)
+            .on_press(WorkoutMessage::from(
+                WorkoutDesignerMessage::PasteWorkoutPressed,
+            ))
+    }
+    /// Browsers download in the format picked here instead of choosing it in a save dialog.
+    #[cfg(target_arch = "wasm32")]
+    fn visualize_export_format(&self) -> Option<Element<'_, WorkoutMessage>> {
+        Some(
+            pick_list(
+                EXPORT_EXTENSIONS,
+                Some(self.export_extension),
+                |extension| {
+                    WorkoutMessage::from(WorkoutDesignerMessage::ExportFormatSelected(extension))
+                },
+            )
+            .into(),
+        )
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn visualize_export_format(&self) -> Option<Element<'_, WorkoutMessage>> {
+        None
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    fn visualize_recent_files(&self) -> Option<Element<'_, WorkoutMessage>> {
+        let paths: Vec<String> = self
+            .recent_files
+            .paths()
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        Some(
+            pick_list(paths, None::<String>, |path| {
+                WorkoutMessage::from(WorkoutDesignerMessage::RecentFileSelected(
+                    path::PathBuf::from(path),
+                ))
+            })
+            .placeholder("Open Recent")
+            .into(),
+        )
+    }
+    #[cfg(target_arch = "wasm32")]
+    fn visualize_recent_files(&self) -> Option<Element<'_, WorkoutMessage>> {
+        None
+    }
+    fn library_controls(&self) -> Row<'_, WorkoutMessage> {
+        Row::new()
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .push_maybe(self.visualize_recent_files())
+            .push(
+                pick_list(self.library.names(), None::<String>, |name| {
+                    WorkoutMessage::from(WorkoutDesignerMessage::LibraryWorkoutSelected(name))
+                })
+                .placeholder("Open from Library"),
+            )
+            .push(
+                pink_button("Save to Library").on_press(WorkoutMessage::from(
+                    WorkoutDesignerMessage::SaveToLibraryPressed,
+                )),
+            )
+    }
+    fn clipboard_controls(&self) -> Row<'_, WorkoutMessage> {
+        let copy = if self.selected.is_empty() {
+            "Copy Workout"
+        } else {
+            "Copy Efforts"
+        };
+        Row::new()
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .push(pick_list(
+                CopyFormat::ALL,
+                Some(self.copy_format),
+                |format| WorkoutMessage::from(WorkoutDesignerMessage::CopyFormatSelected(format)),
+            ))
+            .push(pink_button(copy).on_press(WorkoutMessage::from(
+                WorkoutDesignerMessage::CopyEffortsPressed,
+            )))
+            .push(pink_button("Paste Efforts").on_press(WorkoutMessage::from(
+                WorkoutDesignerMessage::PasteEffortsPressed,
+            )))
+    }
     fn show_buttons(&self) -> Row<'_, WorkoutMessage> {
         Row::new()
             .push(self.visualize_load_button())
-            .push(self.visualize_export_button())
+            .push(self.visualize_save_button())
+            .push(self.visualize_save_as_button())
+            .push_maybe(self.visualize_export_format())
+            .push(self.visualize_play_button())
+            .push(self.visualize_import_ride_button())
+            .push(self.visualize_paste_button())
             .spacing(10)
     }
 }
 
-fn open_or_create(path_to_file: &path::PathBuf) -> Option<File> {
-    if path_to_file.exists() {
-        let _ = remove_file(path_to_file);
-    }
-    OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .truncate(false)
-        .append(false)
-        .open(path_to_file)
-        .ok()
-}
-
 fn ignore_event() -> Task<WorkoutMessage> {
     Task::none()
 }
 
-fn handle_keyboard_inputs(event: Event) -> Task<WorkoutMessage> {
-    if let Keyboard(key) = event {
-        match key {
-            KeyPressed {
-                key: iced_core::keyboard::Key::Named(iced::keyboard::key::Named::F1),
-                modifiers: _,
-                ..
-            } => focus_id::<WorkoutMessage>(0),
-            KeyPressed {
-                key: iced_core::keyboard::Key::Named(iced::keyboard::key::Named::F2),
-                modifiers: _,
-                ..
-            } => focus_id::<WorkoutMessage>(1),
-            KeyPressed {
-                key: iced_core::keyboard::Key::Named(iced::keyboard::key::Named::F3),
-                modifiers: _,
-                ..
-            } => focus_id::<WorkoutMessage>(2),
-            KeyPressed {
-                key: iced_core::keyboard::Key::Named(iced::keyboard::key::Named::Tab),
-                modifiers: Modifiers::SHIFT,
-                ..
-            } => focus_previous::<WorkoutMessage>(),
-            KeyPressed {
-                key: iced_core::keyboard::Key::Named(iced::keyboard::key::Named::Tab),
-                modifiers: _,
-                ..
-            } => focus_next::<WorkoutMessage>(),
-
-            _ => ignore_event(),
-        }
-    } else {
-        ignore_event()
-    }
-}
-
 fn _focus_id<T>(id: usize) -> impl Operation<T> {
     struct FocusOn {
         current: usize,
@@ -346,6 +1372,10 @@ fn _focus_id<T>(id: usize) -> impl Operation<T> {
     }
 }
 
+fn palette_id() -> text_input::Id {
+    text_input::Id::new("command-palette")
+}
+
 pub fn focus_id<Message>(id: usize) -> Task<WorkoutMessage>
 where
     Message: 'static,
@@ -353,69 +1383,10 @@ where
     widget(_focus_id(id))
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 fn make_it_plan_if_none(mut path_to_workout_file: path::PathBuf) -> path::PathBuf {
     if path_to_workout_file.extension().is_none() {
         path_to_workout_file.set_extension("plan");
     }
     path_to_workout_file
 }
-
-fn find_bike_computer() -> Option<PathBuf> {
-    let mut potential_bike_computer: Vec<PathBuf> = list_all_mounted_devices()
-        .unwrap_or_default()
-        .into_iter()
-        .filter(|path| is_relevant_computer(path))
-        .collect();
-    match potential_bike_computer.len() {
-        0 => None,
-        _ => Some(
-            potential_bike_computer
-                .pop()
-                .expect("Because of match it has at least one entry.")
-                .join(Path::new("Internal shared storage/plans")),
-        ),
-    }
-}
-
-fn list_all_mounted_devices() -> Option<Vec<PathBuf>> {
-    // Only works for linux right now:
-    let potential_location = Path::new("/run/user/1000/gvfs/");
-    if !potential_location.exists() {
-        return None;
-    }
-    fs::read_dir(potential_location)
-        .ok()?
-        .map(|file| match file {
-            Ok(file) => Some(file.path()),
-            Err(_) => None,
-        })
-        .collect()
-}
-
-fn is_relevant_computer(path: &Path) -> bool {
-    all_top_level_directories_exist(path)
-}
-
-fn all_top_level_directories_exist(path: &Path) -> bool {
-    path.join(Path::new("Internal shared storage/exports"))
-        .exists()
-        && path
-            .join(Path::new("Internal shared storage/factory"))
-            .exists()
-        && path
-            .join(Path::new("Internal shared storage/plans"))
-            .exists()
-        && path
-            .join(Path::new("Internal shared storage/logs"))
-            .exists()
-        && path
-            .join(Path::new("Internal shared storage/maps"))
-            .exists()
-        && path
-            .join(Path::new("Internal shared storage/routes"))
-            .exists()
-}
-
-fn path_or_home_directory(path: Option<PathBuf>) -> PathBuf {
-    path.unwrap_or(home_dir().unwrap_or_default())
-}
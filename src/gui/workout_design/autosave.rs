@@ -0,0 +1,84 @@
+use crate::gui::storage::{Storage, StorageError};
+use crate::gui::workout_design::history::History;
+use crate::workout_data::json::SCHEMA_VERSION;
+use crate::workout_data::workout::Workout;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const AUTOSAVE_KEY: &str = "autosave";
+/// How often unsaved changes are written to the storage.
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A workout in progress with its history, kept to recover it after a crash.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Autosave {
+    pub workout: Workout,
+    pub history: History,
+}
+
+/// The autosaves of all open workouts with unsaved changes.
+#[derive(Serialize, Deserialize)]
+struct Autosaves {
+    version: u32,
+    workouts: Vec<Autosave>,
+}
+
+/// The autosaves left behind by the last run, if it did not end cleanly.
+pub fn load(storage: &dyn Storage) -> Vec<Autosave> {
+    storage
+        .read(AUTOSAVE_KEY)
+        .and_then(|autosaves| serde_json::from_str::<Autosaves>(&autosaves).ok())
+        .filter(|autosaves| autosaves.version <= SCHEMA_VERSION)
+        .map(|autosaves| autosaves.workouts)
+        .unwrap_or_default()
+}
+
+/// Replace the stored autosaves, removing them if there are none.
+pub fn store(workouts: Vec<Autosave>, storage: &mut dyn Storage) -> Result<(), StorageError> {
+    if workouts.is_empty() {
+        storage.remove(AUTOSAVE_KEY);
+        return Ok(());
+    }
+    let autosaves = serde_json::to_string(&Autosaves {
+        version: SCHEMA_VERSION,
+        workouts,
+    })
+    .expect("A workout only contains strings and numbers.");
+    storage.write(AUTOSAVE_KEY, &autosaves)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gui::storage::MemoryStorage;
+    use crate::workout_data::effort::Effort;
+
+    fn autosave() -> Autosave {
+        let mut history = History::default();
+        history.record(Workout::new("Tempo", "", vec![]));
+        Autosave {
+            workout: Workout::new("Tempo", "", vec![Effort::new(20.0, 220.0, None)]),
+            history,
+        }
+    }
+
+    #[test]
+    fn stored_autosaves_are_recovered() {
+        let mut storage = MemoryStorage::default();
+        store(vec![autosave(), autosave()], &mut storage).unwrap();
+        assert_eq!(load(&storage), vec![autosave(), autosave()]);
+        store(vec![], &mut storage).unwrap();
+        assert_eq!(load(&storage), vec![]);
+    }
+    #[test]
+    fn autosaves_of_newer_version_are_ignored() {
+        let mut storage = MemoryStorage::default();
+        let newer = serde_json::to_string(&Autosaves {
+            version: SCHEMA_VERSION + 1,
+            workouts: vec![autosave()],
+        })
+        .unwrap();
+        storage.write(AUTOSAVE_KEY, &newer).unwrap();
+        assert_eq!(load(&storage), vec![]);
+    }
+}
@@ -0,0 +1,84 @@
+use super::app::WorkoutDesignerMessage;
+use crate::activity::compliance::{analyze, best_offset, IntervalCompliance, DEFAULT_TOLERANCE};
+use crate::activity::Recording;
+use crate::gui::mrc_creator::WorkoutMessage;
+use crate::gui::style::{pink_button, WhiteText, LARGE_BUTTON};
+use crate::workout_data::workout::Workout;
+use iced::widget::{Column, Row};
+
+/// How long a ride may have gone on before the workout started.
+const MAXIMAL_OFFSET_IN_SECONDS: usize = 600;
+
+/// A ride imported to compare it with the designed workout.
+pub(super) struct RideComparison {
+    recording: Recording,
+    /// Second of the ride the workout started at.
+    offset: usize,
+    intervals: Vec<IntervalCompliance>,
+}
+
+impl RideComparison {
+    pub(super) fn new(workout: &Workout, recording: Recording) -> Self {
+        let offset = best_offset(workout, &recording, MAXIMAL_OFFSET_IN_SECONDS);
+        Self {
+            intervals: analyze(workout, &recording, offset, DEFAULT_TOLERANCE),
+            recording,
+            offset,
+        }
+    }
+    pub(super) fn recording(&self) -> &Recording {
+        &self.recording
+    }
+    /// Compare again after the workout was modified.
+    pub(super) fn reanalyze(&mut self, workout: &Workout) {
+        self.intervals = analyze(workout, &self.recording, self.offset, DEFAULT_TOLERANCE);
+    }
+    /// Power of every second of the ride, starting with the workout.
+    pub(super) fn actual_power(&self) -> Vec<Option<f64>> {
+        self.recording
+            .samples()
+            .iter()
+            .skip(self.offset)
+            .map(|sample| sample.power_in_watts.map(f64::from))
+            .collect()
+    }
+
+    pub(super) fn view(&self) -> Column<'_, WorkoutMessage> {
+        self.intervals
+            .iter()
+            .fold(Column::new().spacing(5), |column, interval| {
+                column.push(WhiteText::new(interval_summary(interval)))
+            })
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        pink_button("Workout from Ride")
+                            .width(LARGE_BUTTON)
+                            .on_press(WorkoutMessage::from(
+                                WorkoutDesignerMessage::WorkoutFromRidePressed,
+                            )),
+                    )
+                    .push(
+                        pink_button("Clear Ride")
+                            .width(LARGE_BUTTON)
+                            .on_press(WorkoutMessage::from(WorkoutDesignerMessage::RideCleared)),
+                    ),
+            )
+    }
+}
+
+fn interval_summary(interval: &IntervalCompliance) -> String {
+    let format_power = |power: Option<f64>| match power {
+        Some(power) => format!("{:.0} W", power),
+        None => String::from("-"),
+    };
+    format!(
+        "#{}: {} of {:.0} W, NP {}, {:.0}% on target",
+        interval.effort_index + 1,
+        format_power(interval.average_power),
+        interval.average_target,
+        format_power(interval.normalized_power),
+        interval.time_in_range * 100.0
+    )
+}
@@ -0,0 +1,206 @@
+use crate::workout_data::effort::Effort;
+use crate::workout_data::ToMRC;
+use std::collections::BTreeMap;
+
+/// The text in the inputs of an effort while it is edited.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EffortEdit {
+    pub(super) starting_value: String,
+    pub(super) ending_value: String,
+    pub(super) duration_in_minutes: String,
+    pub(super) name: String,
+    /// Custom color as `#rrggbb`, empty for the color of the power zone.
+    pub(super) color: String,
+    /// Target range as `low-high`, empty for none.
+    pub(super) target_range: String,
+}
+
+impl EffortEdit {
+    pub fn new(effort: &Effort) -> Self {
+        Self {
+            starting_value: effort.starting_value().to_mrc(),
+            ending_value: effort.ending_value().to_mrc(),
+            duration_in_minutes: effort.duration_in_minutes().to_mrc(),
+            name: String::from(effort.name().unwrap_or_default()),
+            color: effort.color().map(String::from).unwrap_or_default(),
+            target_range: effort
+                .target_range()
+                .map(|range| format!("{}-{}", range.low.to_mrc(), range.high.to_mrc()))
+                .unwrap_or_default(),
+        }
+    }
+    /// `effort` with the edited values, or `None` if one of them is not a number
+    /// or the color or target range is invalid.
+    /// An empty ending value makes it a steady effort,
+    /// an empty name, color or target range removes them.
+    pub fn apply(&self, effort: &Effort) -> Option<Effort> {
+        let starting_value = self.starting_value.parse().ok()?;
+        let ending_value = if self.ending_value.is_empty() {
+            starting_value
+        } else {
+            self.ending_value.parse().ok()?
+        };
+        let color = match self.color.trim() {
+            "" => None,
+            color => Some(color.parse().ok()?),
+        };
+        let name = match self.name.trim() {
+            "" => None,
+            name => Some(String::from(name)),
+        };
+        let edited = Effort {
+            duration_in_minutes: self.duration_in_minutes.parse().ok()?,
+            starting_value,
+            ending_value,
+            name,
+            color,
+            target_range: None,
+            ..effort.clone()
+        };
+        Some(match self.target_range.trim() {
+            "" => edited,
+            target_range => {
+                let (low, high) = target_range.split_once('-')?;
+                edited.with_target_range(low.trim().parse().ok()?, high.trim().parse().ok()?)
+            }
+        })
+    }
+}
+
+/// The efforts being edited, by their index in the workout.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EffortEdits(BTreeMap<usize, EffortEdit>);
+
+impl EffortEdits {
+    pub fn start(&mut self, index: usize, effort: &Effort) {
+        self.0.insert(index, EffortEdit::new(effort));
+    }
+    pub fn get(&self, index: usize) -> Option<&EffortEdit> {
+        self.0.get(&index)
+    }
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut EffortEdit> {
+        self.0.get_mut(&index)
+    }
+    pub fn finish(&mut self, index: usize) {
+        self.0.remove(&index);
+    }
+    /// Keep the edits at their efforts after the effort at `index` was removed.
+    pub fn effort_removed(&mut self, index: usize) {
+        self.0 = std::mem::take(&mut self.0)
+            .into_iter()
+            .filter(|(edited, _)| *edited != index)
+            .map(|(edited, edit)| {
+                if edited > index {
+                    (edited - 1, edit)
+                } else {
+                    (edited, edit)
+                }
+            })
+            .collect();
+    }
+    /// Keep the edits at their efforts after `count` efforts were inserted at `position`.
+    pub fn efforts_inserted(&mut self, position: usize, count: usize) {
+        self.0 = std::mem::take(&mut self.0)
+            .into_iter()
+            .map(|(edited, edit)| {
+                if edited >= position {
+                    (edited + count, edit)
+                } else {
+                    (edited, edit)
+                }
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workout_data::effort::Section;
+
+    #[test]
+    fn apply_keeps_other_fields() {
+        let effort = Effort::new(5.0, 200.0, None)
+            .with_section(Section::Warmup)
+            .with_cadence(90)
+            .with_cue("Spin");
+        let mut edit = EffortEdit::new(&effort);
+        edit.ending_value = String::from("250");
+        assert_eq!(
+            edit.apply(&effort),
+            Some(
+                Effort::new(5.0, 200.0, Some(250.0))
+                    .with_section(Section::Warmup)
+                    .with_cadence(90)
+                    .with_cue("Spin")
+            )
+        );
+    }
+    #[test]
+    fn empty_ending_value_is_steady() {
+        let effort = Effort::new(5.0, 200.0, Some(250.0));
+        let mut edit = EffortEdit::new(&effort);
+        edit.ending_value = String::new();
+        assert_eq!(edit.apply(&effort), Some(Effort::new(5.0, 200.0, None)));
+    }
+    #[test]
+    fn invalid_values_are_not_applied() {
+        let effort = Effort::new(5.0, 200.0, None);
+        let mut edit = EffortEdit::new(&effort);
+        edit.duration_in_minutes = String::from("five");
+        assert_eq!(edit.apply(&effort), None);
+    }
+    #[test]
+    fn name_and_color_are_applied() {
+        let effort = Effort::new(5.0, 200.0, None).with_name("VO2 #3");
+        let mut edit = EffortEdit::new(&effort);
+        assert_eq!(edit.name, "VO2 #3");
+        edit.name = String::new();
+        edit.color = String::from("#ff8800");
+        assert_eq!(
+            edit.apply(&effort),
+            Some(Effort::new(5.0, 200.0, None).with_color("#ff8800".parse().unwrap()))
+        );
+        edit.color = String::from("orange");
+        assert_eq!(edit.apply(&effort), None);
+    }
+    #[test]
+    fn target_range_is_applied() {
+        let effort = Effort::new(5.0, 180.0, None).with_target_range(176.0, 188.0);
+        let mut edit = EffortEdit::new(&effort);
+        assert_eq!(edit.target_range, "176.00-188.00");
+        edit.target_range = String::from("190 - 170");
+        assert_eq!(
+            edit.apply(&effort),
+            Some(Effort::new(5.0, 180.0, None).with_target_range(170.0, 190.0))
+        );
+        edit.target_range = String::new();
+        assert_eq!(edit.apply(&effort), Some(Effort::new(5.0, 180.0, None)));
+        edit.target_range = String::from("176");
+        assert_eq!(edit.apply(&effort), None);
+    }
+    #[test]
+    fn edits_follow_removed_efforts() {
+        let effort = Effort::new(5.0, 200.0, None);
+        let mut edits = EffortEdits::default();
+        edits.start(0, &effort);
+        edits.start(1, &effort);
+        edits.start(3, &effort);
+        edits.effort_removed(1);
+        assert!(edits.get(0).is_some());
+        assert!(edits.get(1).is_none());
+        assert!(edits.get(2).is_some());
+        assert!(edits.get(3).is_none());
+    }
+    #[test]
+    fn edits_follow_inserted_efforts() {
+        let effort = Effort::new(5.0, 200.0, None);
+        let mut edits = EffortEdits::default();
+        edits.start(0, &effort);
+        edits.start(1, &effort);
+        edits.efforts_inserted(1, 2);
+        assert!(edits.get(0).is_some());
+        assert!(edits.get(1).is_none());
+        assert!(edits.get(3).is_some());
+    }
+}
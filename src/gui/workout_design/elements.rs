@@ -1,13 +1,17 @@
+use std::collections::BTreeSet;
 use std::num::ParseFloatError;
 
 use super::app::{EffortMessage, WorkoutDesignerMessage};
+use super::edit::{EffortEdit, EffortEdits};
+use crate::chart::palette::Palette;
 use crate::gui::mrc_creator::WorkoutMessage;
 use crate::gui::style::{self, SMALL_BUTTON};
-use crate::gui::style::{pink_button, pink_text_input, WhiteText};
+use crate::gui::style::{pink_button, pink_text_input, text_with_default_font, WhiteText};
+use crate::workout_data::validation::{Finding, Severity};
 use crate::workout_data::ToMRC;
 use crate::workout_data::{effort, workout};
-use iced::widget::{container, scrollable, Column, Row, TextInput};
-use iced::{Alignment, Element};
+use iced::widget::{checkbox, container, scrollable, Column, Row, TextInput};
+use iced::{Alignment, Color, Element};
 
 #[derive(Debug, Clone)]
 pub struct EffortUnitInput {
@@ -151,6 +155,11 @@ impl TryFrom<EffortUnitInput> for effort::Effort {
     }
 }
 
+/// The scrollable list of efforts, scrolled along with the chart.
+pub(super) fn effort_list_id() -> scrollable::Id {
+    scrollable::Id::new("efforts")
+}
+
 pub(super) fn base_design<'a>() -> Column<'a, WorkoutMessage> {
     Column::new()
         .align_x(Alignment::Center)
@@ -159,33 +168,95 @@ pub(super) fn base_design<'a>() -> Column<'a, WorkoutMessage> {
 }
 
 impl<'a> workout::Workout {
-    pub fn view(&'a self) -> impl Into<Element<'a, WorkoutMessage>> {
+    pub fn view(
+        &'a self,
+        findings: &'a [Finding],
+        edits: &'a EffortEdits,
+        selected: &'a BTreeSet<usize>,
+        palette: &Palette,
+    ) -> impl Into<Element<'a, WorkoutMessage>> {
         container::Container::new(
             Column::new()
                 .spacing(20)
                 .push(effort_string_headers())
-                .push(scrollable(
-                    self.efforts.iter().enumerate().fold(
+                .push(
+                    scrollable(self.efforts.iter().enumerate().fold(
                         Column::new(),
                         |scrollable, (effort_index, effort)| {
-                            scrollable.push(effort.view(effort_index))
+                            scrollable.push(
+                                Row::new()
+                                    .spacing(10)
+                                    .push(effort.view(
+                                        effort_index,
+                                        edits.get(effort_index),
+                                        selected.contains(&effort_index),
+                                    ))
+                                    .push(findings_view(findings, Some(effort_index), palette)),
+                            )
                         },
-                    ),
-                )),
+                    ))
+                    .id(effort_list_id()),
+                )
+                .push(findings_view(findings, None, palette)),
         )
     }
 }
 
+/// The findings concerning the effort at `effort_index`,
+/// or the whole workout if `None`.
+fn findings_view<'a>(
+    findings: &'a [Finding],
+    effort_index: Option<usize>,
+    palette: &Palette,
+) -> Column<'a, WorkoutMessage> {
+    findings
+        .iter()
+        .filter(|finding| finding.effort_index == effort_index)
+        .fold(Column::new(), |column, finding| {
+            column.push(
+                text_with_default_font(finding.message.clone())
+                    .size(16.0)
+                    .color(match finding.severity {
+                        Severity::Error => palette.error,
+                        Severity::Warning => palette.warning,
+                    }),
+            )
+        })
+}
+
 impl<'a> effort::Effort {
-    fn view(&'a self, effort_index: usize) -> impl Into<Element<'a, WorkoutMessage>> {
-        match &self.gui_state {
-            effort::EffortState::Idle => Row::new()
+    fn view(
+        &'a self,
+        effort_index: usize,
+        edit: Option<&'a EffortEdit>,
+        is_selected: bool,
+    ) -> impl Into<Element<'a, WorkoutMessage>> {
+        match edit {
+            None => Row::new()
                 .spacing(15)
+                .align_y(Alignment::Center)
+                .push(checkbox("", is_selected).on_toggle(move |is_selected| {
+                    WorkoutMessage::Design(WorkoutDesignerMessage::Effort(
+                        effort_index,
+                        EffortMessage::SelectionToggled(is_selected),
+                    ))
+                }))
                 .push(effort_string_row(
                     self.duration_in_minutes.to_mrc(),
                     self.starting_value.to_mrc(),
                     self.ending_value.to_mrc(),
                 ))
+                .push_maybe(self.name().map(|name| {
+                    let name = text_with_default_font(String::from(name))
+                        .size(style::TEXT_SIZE)
+                        .width(120);
+                    match self.color() {
+                        Some(color) => {
+                            name.color(Color::from_rgb8(color.red, color.green, color.blue))
+                        }
+                        None => name,
+                    }
+                }))
                 .push(
                     Row::new()
                         .spacing(5)
@@ -202,13 +273,16 @@ impl<'a> effort::Effort {
                             )),
                         )),
                 ),
-            effort::EffortState::Editing {
+            Some(EffortEdit {
                 starting_value,
                 ending_value,
                 duration_in_minutes,
-            } => Row::new()
+                name,
+                color,
+                target_range,
+            }) => Row::new()
                 .spacing(5)
-                .width(300)
+                .width(625)
                 .push(
                     TextInput::new("", duration_in_minutes)
                         .on_input(move |updated_effort_in_minutes| {
@@ -253,6 +327,51 @@ impl<'a> effort::Effort {
                         )))
                         .width(90)
                         .size(25),
+                )
+                .push(
+                    TextInput::new("Name", name)
+                        .on_input(move |updated_name| {
+                            WorkoutMessage::Design(WorkoutDesignerMessage::Effort(
+                                effort_index,
+                                EffortMessage::UpdateName(updated_name),
+                            ))
+                        })
+                        .on_submit(WorkoutMessage::Design(WorkoutDesignerMessage::Effort(
+                            effort_index,
+                            EffortMessage::ModificationDone,
+                        )))
+                        .width(120)
+                        .size(25),
+                )
+                .push(
+                    TextInput::new("#rrggbb", color)
+                        .on_input(move |updated_color| {
+                            WorkoutMessage::Design(WorkoutDesignerMessage::Effort(
+                                effort_index,
+                                EffortMessage::UpdateColor(updated_color),
+                            ))
+                        })
+                        .on_submit(WorkoutMessage::Design(WorkoutDesignerMessage::Effort(
+                            effort_index,
+                            EffortMessage::ModificationDone,
+                        )))
+                        .width(90)
+                        .size(25),
+                )
+                .push(
+                    TextInput::new("low-high", target_range)
+                        .on_input(move |updated_target_range| {
+                            WorkoutMessage::Design(WorkoutDesignerMessage::Effort(
+                                effort_index,
+                                EffortMessage::UpdateTargetRange(updated_target_range),
+                            ))
+                        })
+                        .on_submit(WorkoutMessage::Design(WorkoutDesignerMessage::Effort(
+                            effort_index,
+                            EffortMessage::ModificationDone,
+                        )))
+                        .width(100)
+                        .size(25),
                 ),
         }
     }
@@ -0,0 +1,78 @@
+use crate::workout_data::workout::Workout;
+use serde::{Deserialize, Serialize};
+
+/// How many previous versions of a workout are kept.
+const MAXIMAL_LENGTH: usize = 100;
+
+/// Previous versions of the workout to undo to, and undone ones to redo.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct History {
+    undone: Vec<Workout>,
+    redone: Vec<Workout>,
+}
+
+impl History {
+    /// Remember the version before a change. A change makes undone versions unreachable.
+    pub fn record(&mut self, previous: Workout) {
+        self.undone.push(previous);
+        if self.undone.len() > MAXIMAL_LENGTH {
+            self.undone.remove(0);
+        }
+        self.redone.clear();
+    }
+    pub fn undo(&mut self, current: Workout) -> Option<Workout> {
+        let previous = self.undone.pop()?;
+        self.redone.push(current);
+        Some(previous)
+    }
+    pub fn redo(&mut self, current: Workout) -> Option<Workout> {
+        let next = self.redone.pop()?;
+        self.undone.push(current);
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workout_data::effort::Effort;
+
+    fn version(number_of_efforts: usize) -> Workout {
+        Workout::new(
+            "Tempo",
+            "",
+            vec![Effort::new(5.0, 200.0, None); number_of_efforts],
+        )
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let mut history = History::default();
+        history.record(version(0));
+        history.record(version(1));
+        assert_eq!(history.undo(version(2)), Some(version(1)));
+        assert_eq!(history.undo(version(1)), Some(version(0)));
+        assert_eq!(history.undo(version(0)), None);
+        assert_eq!(history.redo(version(0)), Some(version(1)));
+        assert_eq!(history.redo(version(1)), Some(version(2)));
+        assert_eq!(history.redo(version(2)), None);
+    }
+    #[test]
+    fn change_after_undo_drops_redo() {
+        let mut history = History::default();
+        history.record(version(0));
+        history.undo(version(1));
+        history.record(version(0));
+        assert_eq!(history.redo(version(3)), None);
+    }
+    #[test]
+    fn oldest_versions_are_forgotten() {
+        let mut history = History::default();
+        (0..=MAXIMAL_LENGTH).for_each(|length| history.record(version(length)));
+        let mut current = version(MAXIMAL_LENGTH + 1);
+        while let Some(previous) = history.undo(current.clone()) {
+            current = previous;
+        }
+        assert_eq!(current, version(1));
+    }
+}
@@ -0,0 +1,436 @@
+use iced::keyboard::key::Named;
+use iced::keyboard::{Key, Modifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Everything the designer can do from the keyboard or the command palette.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    AddEffort,
+    DeleteSelected,
+    EditSelected,
+    MoveSelectedEarlier,
+    MoveSelectedLater,
+    SelectAll,
+    ClearSelection,
+    CopyEfforts,
+    PasteEfforts,
+    PasteWorkout,
+    Undo,
+    Redo,
+    Open,
+    Save,
+    SaveAs,
+    SaveToLibrary,
+    Play,
+    ImportRide,
+    FocusDuration,
+    FocusStartingValue,
+    FocusEndingValue,
+    ZoomIn,
+    ZoomOut,
+    ResetZoom,
+    CommandPalette,
+}
+
+impl Action {
+    pub const ALL: [Action; 25] = [
+        Action::AddEffort,
+        Action::DeleteSelected,
+        Action::EditSelected,
+        Action::MoveSelectedEarlier,
+        Action::MoveSelectedLater,
+        Action::SelectAll,
+        Action::ClearSelection,
+        Action::CopyEfforts,
+        Action::PasteEfforts,
+        Action::PasteWorkout,
+        Action::Undo,
+        Action::Redo,
+        Action::Open,
+        Action::Save,
+        Action::SaveAs,
+        Action::SaveToLibrary,
+        Action::Play,
+        Action::ImportRide,
+        Action::FocusDuration,
+        Action::FocusStartingValue,
+        Action::FocusEndingValue,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::ResetZoom,
+        Action::CommandPalette,
+    ];
+
+    /// How the action is listed in the command palette.
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::AddEffort => "Add Effort",
+            Action::DeleteSelected => "Delete Selected Efforts",
+            Action::EditSelected => "Edit Selected Efforts",
+            Action::MoveSelectedEarlier => "Move Selected Efforts Earlier",
+            Action::MoveSelectedLater => "Move Selected Efforts Later",
+            Action::SelectAll => "Select All Efforts",
+            Action::ClearSelection => "Clear Selection",
+            Action::CopyEfforts => "Copy Efforts",
+            Action::PasteEfforts => "Paste Efforts",
+            Action::PasteWorkout => "Paste Workout",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::Open => "Open Workout",
+            Action::Save => "Save",
+            Action::SaveAs => "Save As or Export",
+            Action::SaveToLibrary => "Save to Library",
+            Action::Play => "Play Workout",
+            Action::ImportRide => "Import Ride",
+            Action::FocusDuration => "Focus Duration",
+            Action::FocusStartingValue => "Focus Starting Value",
+            Action::FocusEndingValue => "Focus Ending Value",
+            Action::ZoomIn => "Zoom In",
+            Action::ZoomOut => "Zoom Out",
+            Action::ResetZoom => "Reset Zoom",
+            Action::CommandPalette => "Command Palette",
+        }
+    }
+}
+
+/// The key chords bound to an action unless the settings override them.
+const DEFAULT_BINDINGS: &[(&str, Action)] = &[
+    ("Ctrl+Enter", Action::AddEffort),
+    ("Delete", Action::DeleteSelected),
+    ("Ctrl+E", Action::EditSelected),
+    ("Alt+Up", Action::MoveSelectedEarlier),
+    ("Alt+Down", Action::MoveSelectedLater),
+    ("Ctrl+Shift+A", Action::SelectAll),
+    ("Escape", Action::ClearSelection),
+    ("Ctrl+Shift+C", Action::CopyEfforts),
+    ("Ctrl+Shift+V", Action::PasteEfforts),
+    ("Ctrl+Z", Action::Undo),
+    ("Ctrl+Shift+Z", Action::Redo),
+    ("Ctrl+Y", Action::Redo),
+    ("Ctrl+O", Action::Open),
+    ("Ctrl+S", Action::Save),
+    ("Ctrl+Shift+S", Action::SaveAs),
+    ("F5", Action::Play),
+    ("F1", Action::FocusDuration),
+    ("F2", Action::FocusStartingValue),
+    ("F3", Action::FocusEndingValue),
+    ("Ctrl+=", Action::ZoomIn),
+    ("Ctrl+-", Action::ZoomOut),
+    ("Ctrl+0", Action::ResetZoom),
+    ("Ctrl+P", Action::CommandPalette),
+];
+
+#[derive(PartialEq, Debug)]
+pub enum ParseChordError {
+    MissingKey,
+    UnknownModifier(String),
+}
+
+/// A key pressed together with modifiers, written like `Ctrl+Shift+S`.
+/// `Ctrl` stands for the command key on macOS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chord {
+    command: bool,
+    alt: bool,
+    shift: bool,
+    /// Lowercase characters or the names of other keys, like `Delete` or `F1`.
+    key: String,
+}
+
+impl Chord {
+    pub fn parse(chord: &str) -> Result<Self, ParseChordError> {
+        // A trailing `++` binds the plus key itself.
+        let (modifiers, key) = match chord.trim().strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None => chord.trim().rsplit_once('+').unwrap_or(("", chord.trim())),
+        };
+        if key.is_empty() {
+            return Err(ParseChordError::MissingKey);
+        }
+        let mut parsed = Chord {
+            command: false,
+            alt: false,
+            shift: false,
+            key: normalize_key(key),
+        };
+        for modifier in modifiers.split('+').filter(|modifier| !modifier.is_empty()) {
+            match modifier.to_lowercase().as_str() {
+                "ctrl" | "cmd" | "command" => parsed.command = true,
+                "alt" | "option" => parsed.alt = true,
+                "shift" => parsed.shift = true,
+                _ => return Err(ParseChordError::UnknownModifier(String::from(modifier))),
+            }
+        }
+        Ok(parsed)
+    }
+    fn pressed(key: &Key, modifiers: Modifiers) -> Option<Self> {
+        let key = match key {
+            Key::Character(character) => character.to_lowercase(),
+            Key::Named(named) => String::from(named_key(*named)?),
+            Key::Unidentified => return None,
+        };
+        Some(Chord {
+            command: modifiers.command(),
+            alt: modifiers.alt(),
+            shift: modifiers.shift(),
+            key,
+        })
+    }
+    /// Whether a focused text input uses this chord for typing,
+    /// so that it must not trigger an action while one is focused.
+    fn types_text(&self) -> bool {
+        let function_key = self.key.len() > 1
+            && self.key.starts_with('F')
+            && self.key[1..].chars().all(|c| c.is_ascii_digit());
+        !self.command && !self.alt && !function_key && self.key != "Escape"
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.command {
+            formatter.write_str("Ctrl+")?;
+        }
+        if self.alt {
+            formatter.write_str("Alt+")?;
+        }
+        if self.shift {
+            formatter.write_str("Shift+")?;
+        }
+        match self.key.chars().count() {
+            1 => formatter.write_str(&self.key.to_uppercase()),
+            _ => formatter.write_str(&self.key),
+        }
+    }
+}
+
+/// Names of the keys that can be bound besides characters.
+const NAMED_KEYS: &[(Named, &str)] = &[
+    (Named::Enter, "Enter"),
+    (Named::Escape, "Escape"),
+    (Named::Delete, "Delete"),
+    (Named::Backspace, "Backspace"),
+    (Named::Insert, "Insert"),
+    (Named::Space, "Space"),
+    (Named::ArrowUp, "Up"),
+    (Named::ArrowDown, "Down"),
+    (Named::ArrowLeft, "Left"),
+    (Named::ArrowRight, "Right"),
+    (Named::Home, "Home"),
+    (Named::End, "End"),
+    (Named::PageUp, "PageUp"),
+    (Named::PageDown, "PageDown"),
+    (Named::F1, "F1"),
+    (Named::F2, "F2"),
+    (Named::F3, "F3"),
+    (Named::F4, "F4"),
+    (Named::F5, "F5"),
+    (Named::F6, "F6"),
+    (Named::F7, "F7"),
+    (Named::F8, "F8"),
+    (Named::F9, "F9"),
+    (Named::F10, "F10"),
+    (Named::F11, "F11"),
+    (Named::F12, "F12"),
+];
+
+fn named_key(named: Named) -> Option<&'static str> {
+    NAMED_KEYS
+        .iter()
+        .find(|(key, _)| *key == named)
+        .map(|(_, name)| *name)
+}
+
+fn normalize_key(key: &str) -> String {
+    NAMED_KEYS
+        .iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(key))
+        .map_or_else(|| key.to_lowercase(), |(_, name)| String::from(*name))
+}
+
+/// Which action each key chord triggers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Chord, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::new(BTreeMap::new())
+    }
+}
+
+impl Keymap {
+    /// The default bindings, where the chords of `overrides` replace all default chords
+    /// of their action. An empty chord leaves the action without one.
+    pub fn new(overrides: BTreeMap<Action, String>) -> Self {
+        let mut bindings: Vec<(Chord, Action)> = DEFAULT_BINDINGS
+            .iter()
+            .filter(|(_, action)| !overrides.contains_key(action))
+            .map(|(chord, action)| {
+                (
+                    Chord::parse(chord).expect("The default chords are valid."),
+                    *action,
+                )
+            })
+            .collect();
+        for (action, chord) in overrides.iter().filter(|(_, chord)| !chord.is_empty()) {
+            match Chord::parse(chord) {
+                Ok(chord) => {
+                    bindings.retain(|(bound, _)| *bound != chord);
+                    bindings.push((chord, *action));
+                }
+                Err(error) => eprintln!(
+                    "Could not bind {:?} to {:?} because of: {:?}",
+                    chord, action, error
+                ),
+            }
+        }
+        Self { bindings }
+    }
+    /// The action bound to the pressed key, if any.
+    /// `is_typing` tells whether a focused text input already handled the key press.
+    pub fn action(&self, key: &Key, modifiers: Modifiers, is_typing: bool) -> Option<Action> {
+        let pressed = Chord::pressed(key, modifiers)?;
+        if is_typing && pressed.types_text() {
+            return None;
+        }
+        self.bindings
+            .iter()
+            .find(|(chord, _)| *chord == pressed)
+            .map(|(_, action)| *action)
+    }
+    /// The first chord bound to `action`, to show it next to the action.
+    pub fn chord(&self, action: Action) -> Option<&Chord> {
+        self.bindings
+            .iter()
+            .find(|(_, bound)| *bound == action)
+            .map(|(chord, _)| chord)
+    }
+    /// All actions whose label fuzzily matches `query`, the best matches first.
+    pub fn search(&self, query: &str) -> Vec<Action> {
+        let mut matches: Vec<(usize, Action)> = Action::ALL
+            .into_iter()
+            .filter(|action| *action != Action::CommandPalette)
+            .filter_map(|action| fuzzy_score(query, action.label()).map(|score| (score, action)))
+            .collect();
+        matches.sort_by(|(score, _), (other_score, _)| other_score.cmp(score));
+        matches.into_iter().map(|(_, action)| action).collect()
+    }
+}
+
+/// How well `candidate` contains the characters of `query` in order, ignoring case,
+/// or `None` if it does not. Consecutive characters and starts of words count more.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<usize> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match: Option<usize> = None;
+    for character in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = position + candidate[position..].iter().position(|&c| c == character)?;
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == found) {
+            score += 2;
+        }
+        if found == 0 || candidate[found - 1] == ' ' {
+            score += 3;
+        }
+        previous_match = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn character(character: &str) -> Key {
+        Key::Character(character.into())
+    }
+
+    #[test]
+    fn chords_are_parsed() {
+        assert_eq!(
+            Chord::parse("ctrl+shift+s").map(|chord| chord.to_string()),
+            Ok(String::from("Ctrl+Shift+S"))
+        );
+        assert_eq!(
+            Chord::parse("Ctrl++").map(|chord| chord.to_string()),
+            Ok(String::from("Ctrl++"))
+        );
+        assert_eq!(
+            Chord::parse("alt+up").map(|chord| chord.to_string()),
+            Ok(String::from("Alt+Up"))
+        );
+        assert_eq!(Chord::parse("Ctrl+"), Err(ParseChordError::MissingKey));
+        assert_eq!(
+            Chord::parse("Hyper+S"),
+            Err(ParseChordError::UnknownModifier(String::from("Hyper")))
+        );
+    }
+    #[test]
+    fn pressed_chord_triggers_action() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.action(&character("S"), Modifiers::CTRL | Modifiers::SHIFT, false),
+            Some(Action::SaveAs)
+        );
+        assert_eq!(
+            keymap.action(&Key::Named(Named::F2), Modifiers::empty(), false),
+            Some(Action::FocusStartingValue)
+        );
+        assert_eq!(
+            keymap.action(&character("s"), Modifiers::empty(), false),
+            None
+        );
+    }
+    #[test]
+    fn typing_does_not_trigger_plain_keys() {
+        let keymap = Keymap::default();
+        let delete = Key::Named(Named::Delete);
+        assert_eq!(keymap.action(&delete, Modifiers::empty(), true), None);
+        assert_eq!(
+            keymap.action(&delete, Modifiers::empty(), false),
+            Some(Action::DeleteSelected)
+        );
+        assert_eq!(
+            keymap.action(&character("z"), Modifiers::CTRL, true),
+            Some(Action::Undo)
+        );
+    }
+    #[test]
+    fn overrides_replace_default_chords() {
+        let keymap = Keymap::new(BTreeMap::from([
+            (Action::Redo, String::from("Ctrl+R")),
+            (Action::Undo, String::from("Ctrl+Y")),
+            (Action::Play, String::new()),
+        ]));
+        assert_eq!(
+            keymap.action(&character("r"), Modifiers::CTRL, false),
+            Some(Action::Redo)
+        );
+        assert_eq!(
+            keymap.action(&character("y"), Modifiers::CTRL, false),
+            Some(Action::Undo)
+        );
+        assert_eq!(
+            keymap.action(&character("z"), Modifiers::CTRL | Modifiers::SHIFT, false),
+            None
+        );
+        assert_eq!(keymap.chord(Action::Play), None);
+    }
+    #[test]
+    fn search_finds_actions_by_their_initials() {
+        let keymap = Keymap::default();
+        let found = keymap.search("sav");
+        assert_eq!(found[0], Action::Save);
+        assert!(found.contains(&Action::SaveToLibrary));
+        assert!(!found.contains(&Action::Undo));
+        assert_eq!(keymap.search("dse")[0], Action::DeleteSelected);
+        assert_eq!(keymap.search("").len(), Action::ALL.len() - 1);
+    }
+}
@@ -0,0 +1,91 @@
+use crate::gui::storage::{Storage, StorageError};
+use crate::workout_data::json::SCHEMA_VERSION;
+use crate::workout_data::workout::Workout;
+use serde::{Deserialize, Serialize};
+
+const LIBRARY_KEY: &str = "library";
+
+/// Workouts saved in the storage of the platform, by name.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Library {
+    version: u32,
+    workouts: Vec<Workout>,
+}
+
+impl Library {
+    /// The stored library, or an empty one if there is none or it cannot be read.
+    pub fn load(storage: &dyn Storage) -> Self {
+        storage
+            .read(LIBRARY_KEY)
+            .and_then(|library| {
+                serde_json::from_str::<Library>(&library)
+                    .map_err(|error| eprintln!("Could not read the library because of: {}", error))
+                    .ok()
+            })
+            .filter(|library| library.version <= SCHEMA_VERSION)
+            .unwrap_or_default()
+    }
+    pub fn store(&self, storage: &mut dyn Storage) -> Result<(), StorageError> {
+        let library = serde_json::to_string(&Library {
+            version: SCHEMA_VERSION,
+            workouts: self.workouts.clone(),
+        })
+        .expect("A workout only contains strings and numbers.");
+        storage.write(LIBRARY_KEY, &library)
+    }
+    pub fn names(&self) -> Vec<String> {
+        self.workouts
+            .iter()
+            .map(|workout| String::from(workout.name()))
+            .collect()
+    }
+    pub fn get(&self, name: &str) -> Option<&Workout> {
+        self.workouts.iter().find(|workout| workout.name() == name)
+    }
+    /// Add the workout, replacing the one with the same name.
+    pub fn save(&mut self, workout: &Workout) {
+        match self
+            .workouts
+            .iter_mut()
+            .find(|saved| saved.name() == workout.name())
+        {
+            Some(saved) => *saved = workout.clone(),
+            None => self.workouts.push(workout.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gui::storage::MemoryStorage;
+    use crate::workout_data::effort::Effort;
+
+    fn workout(name: &str, wattage: f64) -> Workout {
+        Workout::new(name, "", vec![Effort::new(10.0, wattage, None)])
+    }
+
+    #[test]
+    fn saving_replaces_by_name() {
+        let mut library = Library::default();
+        library.save(&workout("Tempo", 200.0));
+        library.save(&workout("Recovery", 120.0));
+        library.save(&workout("Tempo", 210.0));
+        assert_eq!(library.names(), vec!["Tempo", "Recovery"]);
+        assert_eq!(library.get("Tempo"), Some(&workout("Tempo", 210.0)));
+    }
+    #[test]
+    fn stored_library_is_loaded() {
+        let mut storage = MemoryStorage::default();
+        let mut library = Library::default();
+        library.save(&workout("Tempo", 200.0));
+        library.store(&mut storage).unwrap();
+        assert_eq!(Library::load(&storage).get("Tempo"), library.get("Tempo"));
+    }
+    #[test]
+    fn unreadable_library_is_empty() {
+        let mut storage = MemoryStorage::default();
+        storage.write(LIBRARY_KEY, "not json").unwrap();
+        assert_eq!(Library::load(&storage), Library::default());
+    }
+}
@@ -1,3 +1,12 @@
 pub mod app;
+pub mod autosave;
+pub mod compliance;
+pub mod edit;
 pub mod elements;
+pub mod history;
+pub mod keymap;
+pub mod library;
+pub mod recent_files;
+pub mod session;
+pub mod settings;
 pub mod visualization;
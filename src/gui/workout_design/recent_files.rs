@@ -0,0 +1,70 @@
+use crate::gui::storage::{Storage, StorageError};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const RECENT_FILES_KEY: &str = "recent-files";
+/// How many recently opened or saved files are remembered.
+const MAXIMAL_LENGTH: usize = 10;
+
+/// Workout files opened or saved lately, the latest first.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct RecentFiles(Vec<PathBuf>);
+
+impl RecentFiles {
+    pub fn load(storage: &dyn Storage) -> Self {
+        storage
+            .read(RECENT_FILES_KEY)
+            .and_then(|recent_files| serde_json::from_str(&recent_files).ok())
+            .unwrap_or_default()
+    }
+    pub fn store(&self, storage: &mut dyn Storage) -> Result<(), StorageError> {
+        let recent_files =
+            serde_json::to_string(self).expect("Paths can be written as JSON strings.");
+        storage.write(RECENT_FILES_KEY, &recent_files)
+    }
+    /// Move `path` to the front, forgetting the oldest file if there are too many.
+    pub fn add(&mut self, path: PathBuf) {
+        self.0.retain(|recent| *recent != path);
+        self.0.insert(0, path);
+        self.0.truncate(MAXIMAL_LENGTH);
+    }
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gui::storage::MemoryStorage;
+
+    #[test]
+    fn latest_file_comes_first() {
+        let mut recent_files = RecentFiles::default();
+        recent_files.add(PathBuf::from("tempo.plan"));
+        recent_files.add(PathBuf::from("vo2.zwo"));
+        recent_files.add(PathBuf::from("tempo.plan"));
+        assert_eq!(
+            recent_files.paths(),
+            &[PathBuf::from("tempo.plan"), PathBuf::from("vo2.zwo")]
+        );
+    }
+    #[test]
+    fn oldest_file_is_forgotten() {
+        let mut recent_files = RecentFiles::default();
+        (0..=MAXIMAL_LENGTH).for_each(|index| recent_files.add(PathBuf::from(index.to_string())));
+        assert_eq!(recent_files.paths().len(), MAXIMAL_LENGTH);
+        assert_eq!(
+            recent_files.paths()[0],
+            PathBuf::from(MAXIMAL_LENGTH.to_string())
+        );
+    }
+    #[test]
+    fn stored_recent_files_are_loaded() {
+        let mut storage = MemoryStorage::default();
+        let mut recent_files = RecentFiles::default();
+        recent_files.add(PathBuf::from("tempo.plan"));
+        recent_files.store(&mut storage).unwrap();
+        assert_eq!(RecentFiles::load(&storage), recent_files);
+    }
+}
@@ -0,0 +1,86 @@
+use super::app::WorkoutDesignerMessage;
+use crate::gui::mrc_creator::WorkoutMessage;
+use crate::gui::style::{pink_button, WhiteText, SMALL_BUTTON};
+use crate::workout_data::player::{Clock, PlayerState, WorkoutPlayer};
+use iced::widget::{Column, Row};
+use std::time::Duration;
+
+/// Time added to an interval when extending it.
+pub(super) const EXTENSION: Duration = Duration::from_secs(60);
+
+/// Current state of a session together with the controls to steer it.
+pub(super) fn view<C: Clock>(player: &WorkoutPlayer<C>) -> Column<'_, WorkoutMessage> {
+    let mut information = Column::new().spacing(5).push(WhiteText::new(format!(
+        "Elapsed: {}",
+        format_duration(player.elapsed())
+    )));
+
+    if let Some(target) = player.current_target() {
+        information = information.push(WhiteText::new(format!("Target: {:.0} W", target)));
+    }
+    if let Some(time_left) = player.time_left_in_interval() {
+        information = information.push(WhiteText::new(format!(
+            "Left in interval: {}",
+            format_duration(time_left)
+        )));
+    }
+    if let Some(next_effort) = player.next_effort() {
+        information = information.push(WhiteText::new(format!(
+            "Next: {} at {:.0} W",
+            format_duration(Duration::from_secs_f64(
+                next_effort.duration_in_minutes() * 60.0
+            )),
+            next_effort.starting_value()
+        )));
+    }
+    if let Some(cue) = player.current_cue() {
+        information = information.push(WhiteText::new(String::from(cue)));
+    }
+    if player.state() == PlayerState::Finished {
+        information = information.push(WhiteText::new(String::from("Workout finished!")));
+    }
+
+    Column::new()
+        .spacing(20)
+        .push(information)
+        .push(controls(player.state()))
+}
+
+fn controls(state: PlayerState) -> Row<'static, WorkoutMessage> {
+    let pause_label = match state {
+        PlayerState::Running => "Pause",
+        PlayerState::Ready | PlayerState::Paused | PlayerState::Finished => "Play",
+    };
+    Row::new()
+        .spacing(10)
+        .push(
+            pink_button(pause_label)
+                .width(SMALL_BUTTON)
+                .on_press(WorkoutMessage::from(
+                    WorkoutDesignerMessage::SessionPauseToggled,
+                )),
+        )
+        .push(
+            pink_button("Skip")
+                .width(SMALL_BUTTON)
+                .on_press(WorkoutMessage::from(WorkoutDesignerMessage::SessionSkipped)),
+        )
+        .push(
+            pink_button("+1 min")
+                .width(SMALL_BUTTON)
+                .on_press(WorkoutMessage::from(
+                    WorkoutDesignerMessage::SessionExtended,
+                )),
+        )
+        .push(
+            pink_button("Stop")
+                .width(SMALL_BUTTON)
+                .on_press(WorkoutMessage::from(WorkoutDesignerMessage::SessionStopped)),
+        )
+}
+
+/// Render a duration as `mm:ss`.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
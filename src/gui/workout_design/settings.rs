@@ -0,0 +1,221 @@
+use crate::chart::layout::ChartSettings;
+use crate::chart::palette::{self, Palette};
+use crate::gui::storage::{Storage, StorageError};
+use crate::gui::workout_design::keymap::Action;
+use crate::workout_data::validation::ValidationRules;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+
+const SETTINGS_KEY: &str = "settings";
+
+/// Everything configured by the user, kept together so it can be edited as one file.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    #[serde(flatten)]
+    pub chart: ChartSettings,
+    /// Key chords replacing the default ones of actions, e.g. `"save": "Ctrl+W"`.
+    #[serde(deserialize_with = "valid_entries")]
+    pub keys: BTreeMap<Action, String>,
+    /// Name of the chosen theme, a built-in one or one of `palettes`.
+    pub theme: String,
+    /// Palettes defined by the user, to be chosen as theme by their name.
+    #[serde(deserialize_with = "valid_entries")]
+    pub palettes: BTreeMap<String, Palette>,
+    /// The rules workouts are checked against while designing them.
+    pub validation: ValidationRules,
+}
+
+/// The maps of the settings read strictly, to tell whether any of their entries is invalid.
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct StrictEntries {
+    #[serde(default)]
+    keys: BTreeMap<Action, String>,
+    #[serde(default)]
+    palettes: BTreeMap<String, Palette>,
+}
+
+#[derive(Debug)]
+pub enum SettingsError {
+    Storage(StorageError),
+    /// The stored settings could not be read, so they are not overwritten.
+    Invalid(String),
+}
+
+impl From<StorageError> for SettingsError {
+    fn from(value: StorageError) -> Self {
+        Self::Storage(value)
+    }
+}
+
+impl Settings {
+    /// The stored settings, or the default ones if there are none or they are invalid.
+    /// Invalid entries of `keys` and `palettes` are left out.
+    pub fn load(storage: &dyn Storage) -> Self {
+        Self::read(storage).unwrap_or_else(|error| {
+            eprintln!("Could not read the settings because of: {:?}", error);
+            Settings::default()
+        })
+    }
+    fn read(storage: &dyn Storage) -> Result<Self, SettingsError> {
+        match storage.read(SETTINGS_KEY) {
+            Some(settings) => serde_json::from_str(&settings)
+                .map_err(|error| SettingsError::Invalid(error.to_string())),
+            None => Ok(Settings::default()),
+        }
+    }
+    pub fn store(&self, storage: &mut dyn Storage) -> Result<(), StorageError> {
+        let settings = serde_json::to_string_pretty(self)
+            .expect("The settings only contain numbers, booleans and strings.");
+        storage.write(SETTINGS_KEY, &settings)
+    }
+    /// Change the settings in the storage by `change`, keeping all others.
+    /// Settings that could not be read completely are left alone,
+    /// rather than losing what the user wrote into them.
+    pub fn update(
+        storage: &mut dyn Storage,
+        change: impl FnOnce(&mut Settings),
+    ) -> Result<(), SettingsError> {
+        if let Some(settings) = storage.read(SETTINGS_KEY) {
+            serde_json::from_str::<StrictEntries>(&settings)
+                .map_err(|error| SettingsError::Invalid(error.to_string()))?;
+        }
+        let mut settings = Settings::read(storage)?;
+        change(&mut settings);
+        Ok(settings.store(storage)?)
+    }
+    pub fn palette(&self) -> Palette {
+        palette::palette(&self.theme, &self.palettes)
+    }
+    pub fn theme_names(&self) -> Vec<String> {
+        palette::theme_names(&self.palettes)
+    }
+}
+
+/// The entries of a map which can be read, the others are reported and left out.
+fn valid_entries<'de, D, K, V>(deserializer: D) -> Result<BTreeMap<K, V>, D::Error>
+where
+    D: Deserializer<'de>,
+    K: DeserializeOwned + Ord,
+    V: DeserializeOwned,
+{
+    let entries = BTreeMap::<String, serde_json::Value>::deserialize(deserializer)?;
+    Ok(entries
+        .into_iter()
+        .filter_map(|(key, value)| {
+            let entry = serde_json::from_value(serde_json::Value::String(key.clone()))
+                .and_then(|parsed_key| Ok((parsed_key, serde_json::from_value(value)?)));
+            match entry {
+                Ok(entry) => Some(entry),
+                Err(error) => {
+                    eprintln!("Ignoring the setting {:?} because of: {}", key, error);
+                    None
+                }
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::gui::storage::MemoryStorage;
+    use crate::workout_data::validation::Severity;
+
+    #[test]
+    fn stored_settings_are_loaded() {
+        let mut storage = MemoryStorage::default();
+        let settings = Settings {
+            chart: ChartSettings {
+                ftp: Some(250.0),
+                ..ChartSettings::default()
+            },
+            keys: BTreeMap::from([(Action::Save, String::from("Ctrl+W"))]),
+            theme: String::from("Solarized"),
+            palettes: BTreeMap::from([(String::from("Solarized"), Palette::LIGHT)]),
+            validation: ValidationRules {
+                workout_too_long: None,
+                ..ValidationRules::default()
+            },
+        };
+        settings.store(&mut storage).unwrap();
+        assert_eq!(Settings::load(&storage), settings);
+    }
+    #[test]
+    fn settings_without_keys_are_loaded() {
+        let mut storage = MemoryStorage::default();
+        storage
+            .write(SETTINGS_KEY, r#"{"ftp":200.0,"show_zone_bands":true}"#)
+            .unwrap();
+        let settings = Settings::load(&storage);
+        assert_eq!(settings.chart.ftp, Some(200.0));
+        assert!(settings.chart.show_zone_bands);
+        assert!(settings.keys.is_empty());
+        assert_eq!(settings.palette(), Palette::DARK);
+    }
+    #[test]
+    fn validation_rules_are_loaded() {
+        let mut storage = MemoryStorage::default();
+        storage
+            .write(
+                SETTINGS_KEY,
+                r#"{"validation":{"max_wattage":2000.0,"wattage_out_of_range":"error","no_efforts":null}}"#,
+            )
+            .unwrap();
+        assert_eq!(
+            Settings::load(&storage).validation,
+            ValidationRules {
+                max_wattage: 2000.0,
+                wattage_out_of_range: Some(Severity::Error),
+                no_efforts: None,
+                ..ValidationRules::default()
+            }
+        );
+    }
+    #[test]
+    fn updating_keeps_other_settings() {
+        let mut storage = MemoryStorage::default();
+        Settings::update(&mut storage, |settings| {
+            settings.theme = String::from("Light");
+        })
+        .unwrap();
+        Settings::update(&mut storage, |settings| settings.chart.ftp = Some(180.0)).unwrap();
+        let settings = Settings::load(&storage);
+        assert_eq!(settings.palette(), Palette::LIGHT);
+        assert_eq!(settings.chart.ftp, Some(180.0));
+    }
+    #[test]
+    fn invalid_entries_are_left_out() {
+        let mut storage = MemoryStorage::default();
+        let written = r##"{"ftp":200.0,"keys":{"sav":"Ctrl+W","undo":"Ctrl+U"},
+            "palettes":{"Broken":{"accent":"pink"}}}"##;
+        storage.write(SETTINGS_KEY, written).unwrap();
+        let settings = Settings::load(&storage);
+        assert_eq!(settings.chart.ftp, Some(200.0));
+        assert_eq!(
+            settings.keys,
+            BTreeMap::from([(Action::Undo, String::from("Ctrl+U"))])
+        );
+        assert!(settings.palettes.is_empty());
+        assert!(matches!(
+            Settings::update(&mut storage, |settings| settings.chart.ftp = Some(180.0)),
+            Err(SettingsError::Invalid(_))
+        ));
+        assert_eq!(storage.read(SETTINGS_KEY).as_deref(), Some(written));
+    }
+    #[test]
+    fn invalid_settings_are_not_overwritten() {
+        let mut storage = MemoryStorage::default();
+        storage.write(SETTINGS_KEY, r#"{"ftp":"fast"}"#).unwrap();
+        assert_eq!(Settings::load(&storage), Settings::default());
+        assert!(Settings::update(&mut storage, |settings| settings.theme =
+            String::from("Light"))
+        .is_err());
+        assert_eq!(
+            storage.read(SETTINGS_KEY).as_deref(),
+            Some(r#"{"ftp":"fast"}"#)
+        );
+    }
+}
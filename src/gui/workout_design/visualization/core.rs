@@ -1,104 +1,555 @@
+use crate::chart::colors::ZoneColors;
+use crate::chart::decimation::decimate;
+use crate::chart::geometry;
+use crate::chart::layout::{
+    compute_boxes_for_efforts, max_value_of_efforts, room_for_efforts, summary_lines,
+    target_band_color, ChartLayout, ChartSettings, LABEL_SIZE, MARGIN_BOTTOM, MARGIN_LEFT,
+};
+use crate::chart::palette::Palette;
+use crate::chart::viewport::{Viewport, Zoom, ZOOM_STEP};
 use crate::gui::style::{self, default_font};
+use crate::gui::workout_design::app::WorkoutDesignerMessage;
 use crate::workout_data::workout;
 use crate::{gui::mrc_creator::WorkoutMessage, workout_data::effort};
+use iced::mouse;
 use iced::widget::canvas;
 use iced::widget::text::Shaping;
-use iced::{Color, Element, Length, Point, Rectangle, Renderer, Size, Theme};
-use std::cell::RefCell;
+use iced::{Color, Element, Length, Point, Rectangle, Renderer, Size, Theme, Vector};
+use std::cell::{Cell, RefCell};
+
+/// Scrolled pixels counted as one line of the mouse wheel.
+const PIXELS_PER_SCROLLED_LINE: f32 = 40.0;
+/// Size of the minimap, its width as part of the plot.
+const MINIMAP_WIDTH: f32 = 0.3;
+const MINIMAP_HEIGHT: f32 = 40.0;
+const MINIMAP_MARGIN: f32 = 10.0;
+
 #[derive(Default)]
 pub struct Visualizer {
+    /// The efforts, axes and minimap, drawn again only when `drawn_from` changes.
     cache: canvas::Cache,
-    workout: RefCell<workout::Workout>,
+    drawn_from: RefCell<Option<DrawnFrom>>,
+    snapshot: RefCell<Option<Snapshot>>,
+    pub settings: ChartSettings,
+    pub palette: Palette,
+    /// Position of a running session in minutes.
+    pub cursor_minute: Option<f64>,
+    /// Power of an imported ride, one value per second.
+    actual_power: Option<Vec<Option<f64>>>,
+    /// Zooming to apply to the viewport the next time the chart is drawn.
+    zoom: Cell<Option<Zoom>>,
+}
+
+/// The workout as of a `version` of the designer,
+/// so it is only copied when it changed since the last view.
+struct Snapshot {
+    version: u64,
+    workout: workout::Workout,
+}
+
+/// Everything the cached geometry depends on.
+#[derive(PartialEq)]
+struct DrawnFrom {
+    version: u64,
+    settings: ChartSettings,
+    palette: Palette,
+    viewport: Viewport,
 }
 
 impl Visualizer {
-    pub fn view(&self, workout: workout::Workout) -> impl Into<Element<'_, WorkoutMessage>> {
-        self.overwrite_workout(workout);
-        self.cache.clear();
+    /// Show `workout`, which the designer changed whenever its `version` changed.
+    pub fn view(
+        &self,
+        workout: &workout::Workout,
+        version: u64,
+    ) -> impl Into<Element<'_, WorkoutMessage>> {
+        let mut snapshot = self.snapshot.borrow_mut();
+        if snapshot.as_ref().map(|snapshot| snapshot.version) != Some(version) {
+            *snapshot = Some(Snapshot {
+                version,
+                workout: workout.clone(),
+            });
+        }
         canvas::Canvas::new(self)
             .width(Length::Fill)
             .height(Length::Fill)
     }
-    fn overwrite_workout(&self, new_workout: workout::Workout) {
-        let mut workout = self.workout.borrow_mut();
-        *workout = new_workout;
+    pub fn set_actual_power(&mut self, actual_power: Option<Vec<Option<f64>>>) {
+        self.actual_power = actual_power;
+        self.cache.clear();
     }
+    /// Clear the cache if the chart would be drawn differently than it was,
+    /// telling whether it was cleared.
+    fn invalidate_unless_drawn_from(&self, drawn_from: DrawnFrom) -> bool {
+        let mut previous = self.drawn_from.borrow_mut();
+        let is_outdated = previous.as_ref() != Some(&drawn_from);
+        if is_outdated {
+            self.cache.clear();
+            *previous = Some(drawn_from);
+        }
+        is_outdated
+    }
+    /// Zoom the chart, whose viewport is only known to the canvas.
+    pub fn request_zoom(&self, zoom: Zoom) {
+        self.zoom.set(Some(zoom));
+    }
+}
+
+/// The part of the time axis shown and how it is being moved.
+#[derive(Default)]
+pub struct ChartState {
+    viewport: Cell<Viewport>,
+    drag: Option<Drag>,
+}
+
+impl ChartState {
+    /// The viewport, after applying any zooming requested since it was last read.
+    fn viewport(&self, visualizer: &Visualizer) -> Viewport {
+        if let Some(zoom) = visualizer.zoom.take() {
+            self.viewport.set(self.viewport.get().apply(zoom));
+        }
+        self.viewport.get()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Drag {
+    /// The chart was grabbed at `x` while showing `viewport`.
+    Chart {
+        x: f32,
+        viewport: Viewport,
+    },
+    Minimap,
 }
 
 impl canvas::Program<WorkoutMessage> for &Visualizer {
-    type State = ();
+    type State = ChartState;
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (canvas::event::Status, Option<WorkoutMessage>) {
+        let viewport = state.viewport(self);
+        let plot = plot_area(bounds.size());
+        let minimap = minimap_area(bounds.size());
+        let hovered = cursor.position_in(bounds);
+        let position = cursor.position_from(bounds.position());
+        let moved_viewport = match (event, hovered, state.drag) {
+            (canvas::Event::Mouse(mouse::Event::WheelScrolled { delta }), Some(point), _)
+                if plot.contains(point) =>
+            {
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_SCROLLED_LINE,
+                };
+                viewport.zoomed(ZOOM_STEP.powf(lines), (point.x - plot.x) / plot.width)
+            }
+            (
+                canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+                Some(point),
+                _,
+            ) if viewport.is_zoomed() && minimap.contains(point) => {
+                state.drag = Some(Drag::Minimap);
+                viewport.centered_at((point.x - minimap.x) / minimap.width)
+            }
+            (
+                canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)),
+                Some(point),
+                _,
+            ) if viewport.is_zoomed() && plot.contains(point) => {
+                state.drag = Some(Drag::Chart {
+                    x: point.x,
+                    viewport,
+                });
+                return (canvas::event::Status::Captured, None);
+            }
+            (
+                canvas::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)),
+                _,
+                Some(_),
+            ) => {
+                state.drag = None;
+                return (canvas::event::Status::Captured, None);
+            }
+            (
+                canvas::Event::Mouse(mouse::Event::CursorMoved { .. }),
+                _,
+                Some(Drag::Chart { x, viewport }),
+            ) => match position {
+                Some(point) => viewport.panned((x - point.x) / plot.width * viewport.width()),
+                None => viewport,
+            },
+            (canvas::Event::Mouse(mouse::Event::CursorMoved { .. }), _, Some(Drag::Minimap)) => {
+                match position {
+                    Some(point) => viewport.centered_at((point.x - minimap.x) / minimap.width),
+                    None => viewport,
+                }
+            }
+            _ => return (canvas::event::Status::Ignored, None),
+        };
+        if moved_viewport == viewport {
+            return (canvas::event::Status::Captured, None);
+        }
+        state.viewport.set(moved_viewport);
+        (
+            canvas::event::Status::Captured,
+            Some(WorkoutMessage::from(
+                WorkoutDesignerMessage::ChartViewportChanged(moved_viewport),
+            )),
+        )
+    }
     fn draw(
         &self,
-        _state: &Self::State,
+        state: &Self::State,
         renderer: &Renderer,
         _theme: &Theme,
         bounds: Rectangle,
-        _cursor: iced::mouse::Cursor,
+        _cursor: mouse::Cursor,
     ) -> Vec<canvas::Geometry> {
-        let draw_all = self.cache.draw(renderer, bounds.size(), |frame| {
-            draw_backround(frame);
-            draw_efforts(frame, bounds, &self.workout.borrow().efforts);
-            draw_pink_border(frame);
-            draw_summary_statistic(
-                frame,
-                &bounds,
-                self.workout.borrow().average_intensity(),
-                self.workout.borrow().total_time_of_workout(),
-            )
+        let viewport = state.viewport(self);
+        let snapshot = self.snapshot.borrow();
+        let Some(Snapshot { version, workout }) = snapshot.as_ref() else {
+            return vec![];
+        };
+        self.invalidate_unless_drawn_from(DrawnFrom {
+            version: *version,
+            settings: self.settings.clone(),
+            palette: self.palette,
+            viewport,
+        });
+        let room = room_for_efforts(plot_area(bounds.size()).width / viewport.width());
+        let layout_of = |efforts: &[effort::Effort]| {
+            ChartLayout::new(bounds.size().into(), efforts, self.settings.ftp).zoomed(viewport)
+        };
+        let palette = &self.palette;
+        let chart = self.cache.draw(renderer, bounds.size(), |frame| {
+            let efforts = decimate(&workout.efforts, room);
+            let layout = layout_of(&efforts);
+
+            draw_backround(frame, palette);
+            frame.with_save(|frame| {
+                frame.translate(Vector::new(MARGIN_LEFT, 0.0));
+                draw_zone_bands(frame, &layout, &self.settings);
+                draw_power_axis(frame, &layout, &self.settings, palette);
+                draw_time_axis(frame, &layout, palette);
+                draw_ftp_line(frame, &layout, &self.settings, palette);
+            });
+            frame.with_clip(plot_area(bounds.size()), |frame| {
+                draw_efforts(frame, &layout, &efforts, self.settings.zone_colors);
+                draw_target_bands(frame, &layout, &efforts, palette);
+                draw_effort_labels(frame, &layout, &efforts, palette);
+                if let Some(actual_power) = &self.actual_power {
+                    draw_actual_power(frame, &layout, actual_power, palette);
+                }
+            });
+            if viewport.is_zoomed() {
+                draw_minimap(
+                    frame,
+                    minimap_area(bounds.size()),
+                    &workout.efforts,
+                    viewport,
+                    self.settings.zone_colors,
+                    palette,
+                );
+            }
+            draw_pink_border(frame, palette);
+            draw_summary_statistic(frame, palette, &bounds, summary_lines(workout))
+        });
+        // The cursor of a running session moves on every tick, so it is drawn on its own.
+        let cursor = self.cursor_minute.map(|cursor_minute| {
+            let layout = layout_of(&decimate(&workout.efforts, room));
+            let mut frame = canvas::Frame::new(renderer, bounds.size());
+            frame.with_clip(plot_area(bounds.size()), |frame| {
+                draw_cursor(frame, &layout, cursor_minute as f32, palette)
+            });
+            frame.into_geometry()
         });
 
-        vec![draw_all]
+        std::iter::once(chart).chain(cursor).collect()
+    }
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        let viewport = state.viewport.get();
+        match (state.drag, cursor.position_in(bounds)) {
+            (Some(Drag::Chart { .. }), _) => mouse::Interaction::Grabbing,
+            (Some(Drag::Minimap), _) => mouse::Interaction::Pointer,
+            (None, Some(point))
+                if viewport.is_zoomed() && minimap_area(bounds.size()).contains(point) =>
+            {
+                mouse::Interaction::Pointer
+            }
+            (None, Some(point))
+                if viewport.is_zoomed() && plot_area(bounds.size()).contains(point) =>
+            {
+                mouse::Interaction::Grab
+            }
+            _ => mouse::Interaction::default(),
+        }
     }
 }
 
-fn draw_backround(frame: &mut canvas::Frame) {
+/// Where the efforts are drawn, relative to the frame.
+fn plot_area(frame: Size) -> Rectangle {
+    Rectangle::new(
+        Point::new(MARGIN_LEFT, 0.0),
+        Size::new(
+            (frame.width - MARGIN_LEFT).max(1.0),
+            (frame.height - MARGIN_BOTTOM).max(1.0),
+        ),
+    )
+}
+
+/// The overview of the whole workout in the upper left corner of the plot.
+fn minimap_area(frame: Size) -> Rectangle {
+    let plot = plot_area(frame);
+    Rectangle::new(
+        Point::new(plot.x + MINIMAP_MARGIN, MINIMAP_MARGIN),
+        Size::new(plot.width * MINIMAP_WIDTH, MINIMAP_HEIGHT),
+    )
+}
+
+fn draw_backround(frame: &mut canvas::Frame, palette: &Palette) {
     let background = canvas::Path::rectangle(Point::ORIGIN, frame.size());
-    frame.fill(&background, Color::from_rgb8(0x40, 0x44, 0x4B));
+    frame.fill(&background, Color::from(palette.chart_background));
+}
+fn draw_zone_bands(frame: &mut canvas::Frame, layout: &ChartLayout, settings: &ChartSettings) {
+    for (top, bottom, color) in layout.zone_bands(settings) {
+        frame.fill(
+            &canvas::Path::rectangle(
+                Point::new(0.0, top),
+                Size::new(layout.plot.width, bottom - top),
+            ),
+            Color::from(color),
+        );
+    }
+}
+fn draw_power_axis(
+    frame: &mut canvas::Frame,
+    layout: &ChartLayout,
+    settings: &ChartSettings,
+    palette: &Palette,
+) {
+    for (wattage, label) in layout.power_ticks(settings) {
+        let y = layout.value_to_y(wattage);
+        frame.stroke(
+            &canvas::Path::line(Point::new(0.0, y), Point::new(layout.plot.width, y)),
+            canvas::Stroke::default().with_color(palette.faint.into()),
+        );
+        frame.fill_text(label_text(
+            palette,
+            label,
+            Point::new(-6.0, y),
+            iced::alignment::Horizontal::Right,
+        ));
+    }
+}
+fn draw_time_axis(frame: &mut canvas::Frame, layout: &ChartLayout, palette: &Palette) {
+    for (x, label) in layout.time_ticks() {
+        frame.stroke(
+            &canvas::Path::line(
+                Point::new(x, layout.plot.height),
+                Point::new(x, layout.plot.height + 6.0),
+            ),
+            canvas::Stroke::default().with_color(palette.text.into()),
+        );
+        frame.fill_text(label_text(
+            palette,
+            label,
+            Point::new(x, layout.plot.height + 6.0 + LABEL_SIZE / 2.0),
+            iced::alignment::Horizontal::Center,
+        ));
+    }
+}
+fn draw_ftp_line(
+    frame: &mut canvas::Frame,
+    layout: &ChartLayout,
+    settings: &ChartSettings,
+    palette: &Palette,
+) {
+    if let Some(y) = layout.ftp_line(settings) {
+        frame.stroke(
+            &canvas::Path::line(Point::new(0.0, y), Point::new(layout.plot.width, y)),
+            canvas::Stroke::default()
+                .with_color(palette.accent.into())
+                .with_width(2.0),
+        );
+        frame.fill_text(label_text(
+            palette,
+            String::from("FTP"),
+            Point::new(layout.plot.width - 20.0, y - LABEL_SIZE / 2.0 - 2.0),
+            iced::alignment::Horizontal::Center,
+        ));
+    }
+}
+fn draw_actual_power(
+    frame: &mut canvas::Frame,
+    layout: &ChartLayout,
+    powers: &[Option<f64>],
+    palette: &Palette,
+) {
+    for line in layout.actual_power_lines(powers) {
+        let path = canvas::Path::new(|builder| {
+            if let Some((first, rest)) = line.split_first() {
+                builder.move_to((*first).into());
+                for point in rest {
+                    builder.line_to((*point).into());
+                }
+            }
+        });
+        frame.stroke(
+            &path,
+            canvas::Stroke::default()
+                .with_color(palette.warning.into())
+                .with_width(1.5),
+        );
+    }
+}
+fn draw_cursor(frame: &mut canvas::Frame, layout: &ChartLayout, minute: f32, palette: &Palette) {
+    let x = layout.minute_to_x(minute);
+    frame.stroke(
+        &canvas::Path::line(Point::new(x, 0.0), Point::new(x, layout.plot.height)),
+        canvas::Stroke::default()
+            .with_color(palette.text.into())
+            .with_width(3.0),
+    );
+}
+fn draw_efforts(
+    frame: &mut canvas::Frame,
+    layout: &ChartLayout,
+    efforts: &[effort::Effort],
+    colors: ZoneColors,
+) {
+    frame.with_save(|frame| {
+        frame.translate(Vector::new(-layout.shift, 0.0));
+        for (shape, color) in compute_boxes_for_efforts(
+            &geometry::Rectangle::with_size(geometry::Size::new(
+                layout.content_width,
+                layout.plot.height,
+            )),
+            efforts,
+            layout.axis_maximum,
+            colors,
+        ) {
+            frame.fill(&polygon(&shape.corners()), Color::from(color));
+        }
+    });
+}
+fn draw_target_bands(
+    frame: &mut canvas::Frame,
+    layout: &ChartLayout,
+    efforts: &[effort::Effort],
+    palette: &Palette,
+) {
+    for band in layout.target_bands(efforts) {
+        let area = canvas::Path::rectangle(band.position().into(), band.size().into());
+        frame.fill(&area, Color::from(target_band_color(palette)));
+        frame.stroke(
+            &area,
+            canvas::Stroke::default().with_color(palette.text.into()),
+        );
+    }
 }
-fn draw_efforts(frame: &mut canvas::Frame, bounds: Rectangle, efforts: &[effort::Effort]) {
-    for (shape, color) in compute_boxes_for_efforts(&bounds, efforts) {
-        let drawn_shape = shape.draw();
-        frame.fill(&drawn_shape, color);
+fn draw_effort_labels(
+    frame: &mut canvas::Frame,
+    layout: &ChartLayout,
+    efforts: &[effort::Effort],
+    palette: &Palette,
+) {
+    for (position, name) in layout.effort_labels(efforts) {
+        frame.fill_text(label_text(
+            palette,
+            name,
+            position.into(),
+            iced::alignment::Horizontal::Center,
+        ));
     }
 }
-fn draw_pink_border(frame: &mut canvas::Frame) {
+/// All efforts, shrunk into `area`, with the shown part of them outlined.
+fn draw_minimap(
+    frame: &mut canvas::Frame,
+    area: Rectangle,
+    efforts: &[effort::Effort],
+    viewport: Viewport,
+    colors: ZoneColors,
+    palette: &Palette,
+) {
+    frame.with_save(|frame| {
+        frame.translate(Vector::new(area.x, area.y));
+        let overview = canvas::Path::rectangle(Point::ORIGIN, area.size());
+        frame.fill(&overview, Color::from(palette.chart_background));
+        let efforts = decimate(efforts, room_for_efforts(area.width));
+        let axis_maximum = max_value_of_efforts(&efforts).max(1.0);
+        for (shape, color) in compute_boxes_for_efforts(
+            &geometry::Rectangle::with_size(area.size().into()),
+            &efforts,
+            axis_maximum,
+            colors,
+        ) {
+            frame.fill(&polygon(&shape.corners()), Color::from(color));
+        }
+        frame.stroke(
+            &overview,
+            canvas::Stroke::default().with_color(palette.faint.into()),
+        );
+        let shown = canvas::Path::rectangle(
+            Point::new(viewport.start() * area.width, 0.0),
+            Size::new(viewport.width() * area.width, area.height),
+        );
+        frame.fill(
+            &shown,
+            Color {
+                a: 0.2,
+                ..palette.highlight.into()
+            },
+        );
+        frame.stroke(
+            &shown,
+            canvas::Stroke::default()
+                .with_color(palette.highlight.into())
+                .with_width(2.0),
+        );
+    });
+}
+fn draw_pink_border(frame: &mut canvas::Frame, palette: &Palette) {
     frame.stroke(
         &canvas::Path::rectangle(Point::ORIGIN, frame.size()),
         canvas::Stroke::default()
-            .with_color(style::PURPLE)
+            .with_color(palette.highlight.into())
             .with_width(3.0),
     );
 }
 fn draw_summary_statistic(
     frame: &mut canvas::Frame,
+    palette: &Palette,
     bounds: &'_ Rectangle,
-    average_intensity: f64,
-    duration_in_minutes: f64,
+    summary: [String; 2],
 ) {
     let text_size_with_buffer = style::TEXT_SIZE * 1.25;
     let offset_from_left: f32 = bounds.width * 0.82;
 
-    frame.fill_text(pink_text(
-        format!("Average Wattage: {:.1}", average_intensity),
-        Point {
-            x: offset_from_left,
-            y: text_size_with_buffer,
-        },
-    ));
-    frame.fill_text(pink_text(
-        format!("Duration: {} ", duration_in_minutes),
-        Point {
-            x: offset_from_left,
-            y: 2.0 * text_size_with_buffer,
-        },
-    ));
+    for (line, text) in summary.into_iter().enumerate() {
+        frame.fill_text(pink_text(
+            palette,
+            text,
+            Point {
+                x: offset_from_left,
+                y: (line + 1) as f32 * text_size_with_buffer,
+            },
+        ));
+    }
 }
 
-fn pink_text(text: String, position: iced::Point) -> canvas::Text {
+fn pink_text(palette: &Palette, text: String, position: iced::Point) -> canvas::Text {
     canvas::Text {
         content: text,
         position,
-        color: style::PINK,
+        color: palette.accent.into(),
         size: iced::Pixels(style::TEXT_SIZE),
         font: default_font(),
         line_height: iced::widget::text::LineHeight::default(),
@@ -108,364 +559,88 @@ fn pink_text(text: String, position: iced::Point) -> canvas::Text {
     }
 }
 
-fn compute_boxes_for_efforts(
-    bounds: &'_ Rectangle,
-    efforts: &[effort::Effort],
-) -> Vec<(Box<dyn Drawable>, Color)> {
-    compute_shapes_to_draw(bounds, efforts)
-        .into_iter()
-        .zip(duplicate_element_in_iterator(
-            &mut compute_colors_of_shapes(efforts).into_iter(),
-        ))
-        .collect()
-}
-fn compute_colors_of_shapes(efforts: &[effort::Effort]) -> Vec<Color> {
-    efforts.iter().map(|effort| effort.to_color()).collect()
-}
-
-fn duplicate_element_in_iterator<T>(iterator: &mut dyn Iterator<Item = T>) -> Vec<T>
-where
-    T: Clone,
-{
-    iterator
-        .flat_map(|element| vec![element.clone(), element].into_iter())
-        .collect()
-}
-
-fn compute_shapes_to_draw(
-    bounds: &'_ Rectangle,
-    efforts: &[effort::Effort],
-) -> Vec<Box<dyn Drawable>> {
-    let durations = efforts
-        .iter()
-        .map(|effort| effort.duration_in_minutes as f32)
-        .collect();
-    let starting_values: Vec<f32> = efforts
-        .iter()
-        .map(|effort| effort.starting_value as f32)
-        .collect();
-    let ending_values: Vec<f32> = efforts
-        .iter()
-        .map(|effort| effort.ending_value as f32)
-        .collect();
-
-    let offset_between_durations = 1.0;
-
-    compute_starting_dimensions_x(bounds.size().width, durations, offset_between_durations)
-        .into_iter()
-        .zip(
-            compute_starting_dimensions_y(
-                bounds.size().height,
-                starting_values.clone(),
-                offset_between_durations,
-                starting_values.iter().chain(ending_values.iter()).copied().fold(f32::NAN, f32::max)
-            ),
-        )
-        .zip(
-            compute_starting_dimensions_y(
-                bounds.size().height,
-                ending_values.clone(),
-                offset_between_durations,
-                starting_values.iter().chain(ending_values.iter()).copied().fold(f32::NAN, f32::max)
-            ),
-        )
-        .flat_map(
-            |((x_dimensions, y_dimensions_starting), y_dimensions_ending)| -> Vec<Box<dyn Drawable+'static>>{
-                vec![
-                    Box::new(RectangleToDraw::new(
-                        x_dimensions,
-                        if y_dimensions_ending.height > y_dimensions_starting.height {y_dimensions_starting} else {y_dimensions_ending},
-                        bounds.size(),
-                    )),
-                    Box::new(TriangleToDraw::new(
-                        x_dimensions,
-                        y_dimensions_starting,
-                        y_dimensions_ending,
-                        bounds.size(),
-                    )),
-                ]
-            },
-        )
-        .collect::<Vec<Box<dyn Drawable>>>()
-}
-
-fn compute_starting_dimensions_x(
-    length_of_frame: f32,
-    durations: Vec<f32>,
-    offset_between_durations: f32,
-) -> Vec<RectangleXDimensions> {
-    let ratio_duration_to_frame: f32 =
-        compute_ratio_of_duration_to_frame(length_of_frame, offset_between_durations, &durations);
-
-    let widths = durations
-        .iter()
-        .map(|&current_duration| current_duration * ratio_duration_to_frame);
-
-    compute_starting_points_of_efforts(
-        &offset_between_durations,
-        &ratio_duration_to_frame,
-        &durations,
-    )
-    .zip(widths)
-    .map(|(starting_point, width)| RectangleXDimensions::new(starting_point, width))
-    .collect()
-}
-
-fn compute_ratio_of_duration_to_frame(
-    length_of_frame: f32,
-    offset_between_durations: f32,
-    durations: &[f32],
-) -> f32 {
-    (length_of_frame - (offset_between_durations * durations.len() as f32))
-        / durations.iter().sum::<f32>()
-}
-
-fn compute_starting_points_of_efforts<'a>(
-    offset_between_durations: &'a f32,
-    ratio_duration_to_frame: &'a f32,
-    durations: &'a [f32],
-) -> impl Iterator<Item = f32> + 'a {
-    std::iter::once(&0.0_f32).chain(durations.iter()).scan(
-        0.0_f32,
-        move |last_starting_point, &current_duration| {
-            *last_starting_point = *last_starting_point
-                + offset_between_durations
-                + current_duration * ratio_duration_to_frame;
-            Some(*last_starting_point)
-        },
-    )
-}
-
-fn compute_starting_dimensions_y(
-    length_of_frame: f32,
-    efforts: Vec<f32>,
-    offset_between_efforts: f32,
-    max: f32,
-) -> Vec<RectangleYDimensions> {
-    let ratio_effort_to_frame = ((length_of_frame * 0.90) - offset_between_efforts) / max;
-    let heigths = efforts
-        .iter()
-        .map(|&current_effort| current_effort * ratio_effort_to_frame);
-
-    let starting_points_y = vec![offset_between_efforts; efforts.len()].into_iter();
-
-    starting_points_y
-        .zip(heigths)
-        .map(|(starting_point, height)| RectangleYDimensions::new(starting_point, height))
-        .collect()
-}
-
-#[derive(Clone, Debug, Default, PartialEq, Copy)]
-struct RectangleXDimensions {
-    starting_point: f32,
-    width: f32,
-}
-
-impl RectangleXDimensions {
-    fn new(starting_point: f32, width: f32) -> Self {
-        Self {
-            starting_point,
-            width,
-        }
+fn label_text(
+    palette: &Palette,
+    text: String,
+    position: iced::Point,
+    horizontal_alignment: iced::alignment::Horizontal,
+) -> canvas::Text {
+    canvas::Text {
+        color: palette.text.into(),
+        size: iced::Pixels(LABEL_SIZE),
+        horizontal_alignment,
+        ..pink_text(palette, text, position)
     }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Copy)]
-struct RectangleYDimensions {
-    starting_point: f32,
-    height: f32,
-}
-
-impl RectangleYDimensions {
-    fn new(starting_point: f32, height: f32) -> Self {
-        Self {
-            starting_point,
-            height,
+/// The closed path through the corners of a shape laid out by the chart.
+fn polygon(corners: &[geometry::Point]) -> canvas::Path {
+    canvas::Path::new(|builder| {
+        if let Some((first, rest)) = corners.split_first() {
+            builder.move_to((*first).into());
+            for corner in rest {
+                builder.line_to((*corner).into());
+            }
+            builder.close();
         }
-    }
+    })
 }
 
-trait Drawable {
-    fn draw(&self) -> canvas::Path;
-}
-#[derive(Clone, Debug, PartialEq)]
-struct RectangleToDraw {
-    top_left: Point,
-    size: Size,
-}
-
-impl RectangleToDraw {
-    fn new(
-        dimensions_x: RectangleXDimensions,
-        dimensions_y: RectangleYDimensions,
-        size_of_frame: Size,
-    ) -> Self {
-        Self {
-            top_left: Point::new(
-                dimensions_x.starting_point,
-                size_of_frame.height - dimensions_y.height - dimensions_y.starting_point,
-            ),
-            size: Size::new(dimensions_x.width, dimensions_y.height),
-        }
+impl From<geometry::Point> for Point {
+    fn from(point: geometry::Point) -> Self {
+        Point::new(point.x, point.y)
     }
 }
 
-impl Drawable for RectangleToDraw {
-    fn draw(&self) -> canvas::Path {
-        canvas::Path::rectangle(self.top_left, self.size)
+impl From<geometry::Size> for Size {
+    fn from(size: geometry::Size) -> Self {
+        Size::new(size.width, size.height)
     }
 }
 
-struct TriangleToDraw {
-    point_1: Point,
-    point_2: Point,
-    point_3: Point,
-}
-
-impl TriangleToDraw {
-    fn new(
-        x_dimensions: RectangleXDimensions,
-        y_dimensions_starting: RectangleYDimensions,
-        y_dimensions_ending: RectangleYDimensions,
-        frame: Size,
-    ) -> Self {
-        if y_dimensions_starting.height > y_dimensions_ending.height {
-            Self {
-                point_1: Point::new(
-                    x_dimensions.starting_point,
-                    mirror_y(
-                        y_dimensions_ending.starting_point + y_dimensions_ending.height,
-                        frame,
-                    ),
-                ),
-                point_2: Point::new(
-                    x_dimensions.starting_point,
-                    mirror_y(
-                        y_dimensions_starting.starting_point + y_dimensions_starting.height,
-                        frame,
-                    ),
-                ),
-
-                point_3: Point::new(
-                    x_dimensions.starting_point + x_dimensions.width,
-                    mirror_y(
-                        y_dimensions_ending.starting_point + y_dimensions_ending.height,
-                        frame,
-                    ),
-                ),
-            }
-        } else {
-            Self {
-                point_1: Point::new(
-                    x_dimensions.starting_point,
-                    mirror_y(
-                        y_dimensions_starting.starting_point + y_dimensions_starting.height,
-                        frame,
-                    ),
-                ),
-                point_2: Point::new(
-                    x_dimensions.starting_point + x_dimensions.width,
-                    mirror_y(
-                        y_dimensions_starting.starting_point + y_dimensions_starting.height,
-                        frame,
-                    ),
-                ),
-                point_3: Point::new(
-                    x_dimensions.starting_point + x_dimensions.width,
-                    mirror_y(
-                        y_dimensions_ending.starting_point + y_dimensions_ending.height,
-                        frame,
-                    ),
-                ),
-            }
-        }
-    }
-}
-
-fn mirror_y(point: f32, frame: Size) -> f32 {
-    frame.height - point
-}
-
-impl Drawable for TriangleToDraw {
-    fn draw(&self) -> canvas::Path {
-        canvas::Path::new(|p| {
-            p.move_to(self.point_1);
-            p.line_to(self.point_2);
-            p.line_to(self.point_3);
-            p.line_to(self.point_1);
-            p.close();
-        })
+impl From<Size> for geometry::Size {
+    fn from(size: Size) -> Self {
+        geometry::Size::new(size.width, size.height)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{
-        compute_ratio_of_duration_to_frame, compute_starting_dimensions_x,
-        compute_starting_dimensions_y, compute_starting_points_of_efforts, RectangleToDraw,
-        RectangleXDimensions, RectangleYDimensions,
-    };
-    use iced::{Point, Size};
-
-    #[test]
-    fn test_get_starting_coordinates_x() {
-        assert_eq!(
-            compute_starting_dimensions_x(100.0, vec![10.0, 20.0, 40.0, 10.0], 0.1),
-            vec![
-                RectangleXDimensions::new(0.1, 12.45),
-                RectangleXDimensions::new(12.65, 24.9),
-                RectangleXDimensions::new(37.65, 49.8),
-                RectangleXDimensions::new(87.55, 12.45)
-            ]
-        )
-    }
-    #[test]
-    fn test_compute_ratio_of_duration_to_frame() {
-        assert_eq!(
-            compute_ratio_of_duration_to_frame(100.0, 0.1, &vec![10.0, 20.0, 40.0, 10.0]),
-            1.245
-        )
+    use super::*;
+    use crate::workout_data::effort::Effort;
+
+    fn drawn_from(version: u64) -> DrawnFrom {
+        DrawnFrom {
+            version,
+            settings: ChartSettings::default(),
+            palette: Palette::default(),
+            viewport: Viewport::FULL,
+        }
     }
-    #[test]
-    fn test_compute_starting_points_of_efforts() {
-        assert_eq!(
-            compute_starting_points_of_efforts(&0.1, &1.245, &[10.0, 20.0, 40.0, 10.0])
-                .collect::<Vec<f32>>(),
-            vec![0.1, 12.65, 37.65, 87.55, 100.1]
-        )
+    fn shown_workout(visualizer: &Visualizer) -> Option<workout::Workout> {
+        visualizer
+            .snapshot
+            .borrow()
+            .as_ref()
+            .map(|snapshot| snapshot.workout.clone())
     }
+
     #[test]
-    fn test_get_starting_coordinates_y() {
-        assert_eq!(
-            compute_starting_dimensions_y(278.888_9, vec![100.0, 200.0, 250.0, 100.0], 1.0, 250.0),
-            // TODO: Make comparison round.
-            vec![
-                RectangleYDimensions::new(1.0, 99.99999),
-                RectangleYDimensions::new(1.0, 199.99998),
-                RectangleYDimensions::new(1.0, 249.99998),
-                RectangleYDimensions::new(1.0, 99.99999)
-            ]
-        )
+    fn workout_is_copied_only_for_new_versions() {
+        let visualizer = Visualizer::default();
+        let first = workout::Workout::new("", "", vec![Effort::new(10.0, 100.0, None)]);
+        let second = workout::Workout::new("", "", vec![Effort::new(5.0, 300.0, None)]);
+        let _ = visualizer.view(&first, 1);
+        let _ = visualizer.view(&second, 1);
+        assert_eq!(shown_workout(&visualizer), Some(first));
+        let _ = visualizer.view(&second, 2);
+        assert_eq!(shown_workout(&visualizer), Some(second));
     }
-
     #[test]
-    fn test_rectangle_to_draw() {
-        assert_eq!(
-            RectangleToDraw::new(
-                RectangleXDimensions {
-                    starting_point: 0.1,
-                    width: 10.0
-                },
-                RectangleYDimensions {
-                    starting_point: 0.1,
-                    height: 30.0
-                },
-                Size::new(100.0, 300.0)
-            ),
-            RectangleToDraw {
-                top_left: Point::new(0.1, 269.9),
-                size: Size::new(10.0, 30.0)
-            }
-        )
+    fn cache_is_cleared_only_when_drawn_differently() {
+        let visualizer = Visualizer::default();
+        assert!(visualizer.invalidate_unless_drawn_from(drawn_from(1)));
+        assert!(!visualizer.invalidate_unless_drawn_from(drawn_from(1)));
+        assert!(visualizer.invalidate_unless_drawn_from(drawn_from(2)));
     }
 }
@@ -1,2 +1 @@
-pub mod colors;
 pub mod core;
@@ -4,5 +4,16 @@
 /// Definitions of all base types to construct a workout.
 pub mod workout_data;
 
+/// Controlling smart trainers while riding a workout.
+pub mod trainer;
+
+/// Recorded rides and the activity files they are stored in.
+pub mod activity;
+
+/// Charts of workouts, laid out independent of any renderer
+/// and written as SVG, PNG or printable HTML.
+pub mod chart;
+
 /// The GUI of the Workout Generator application.
+#[cfg(feature = "gui")]
 pub mod gui;
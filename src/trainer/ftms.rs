@@ -0,0 +1,404 @@
+/// Op code prefixing every response on the control point.
+const RESPONSE_CODE: u8 = 0x80;
+
+#[derive(Debug, PartialEq)]
+pub enum FtmsError {
+    Empty,
+    TooShort,
+    UnknownOpCode(u8),
+    UnknownResultCode(u8),
+    NotAResponse(u8),
+}
+
+/// Op codes of the fitness machine control point supported by this crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpCode {
+    RequestControl,
+    Reset,
+    SetTargetPower,
+    StartOrResume,
+    StopOrPause,
+}
+
+impl OpCode {
+    fn to_byte(self) -> u8 {
+        match self {
+            OpCode::RequestControl => 0x00,
+            OpCode::Reset => 0x01,
+            OpCode::SetTargetPower => 0x05,
+            OpCode::StartOrResume => 0x07,
+            OpCode::StopOrPause => 0x08,
+        }
+    }
+    fn from_byte(byte: u8) -> Result<Self, FtmsError> {
+        match byte {
+            0x00 => Ok(OpCode::RequestControl),
+            0x01 => Ok(OpCode::Reset),
+            0x05 => Ok(OpCode::SetTargetPower),
+            0x07 => Ok(OpCode::StartOrResume),
+            0x08 => Ok(OpCode::StopOrPause),
+            unknown => Err(FtmsError::UnknownOpCode(unknown)),
+        }
+    }
+}
+
+/// A request written to the fitness machine control point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlPointRequest {
+    RequestControl,
+    Reset,
+    SetTargetPower(i16),
+    StartOrResume,
+    Stop,
+    Pause,
+}
+
+impl ControlPointRequest {
+    pub fn op_code(&self) -> OpCode {
+        match self {
+            ControlPointRequest::RequestControl => OpCode::RequestControl,
+            ControlPointRequest::Reset => OpCode::Reset,
+            ControlPointRequest::SetTargetPower(_) => OpCode::SetTargetPower,
+            ControlPointRequest::StartOrResume => OpCode::StartOrResume,
+            ControlPointRequest::Stop | ControlPointRequest::Pause => OpCode::StopOrPause,
+        }
+    }
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![self.op_code().to_byte()];
+        match self {
+            ControlPointRequest::SetTargetPower(watts) => {
+                bytes.extend_from_slice(&watts.to_le_bytes())
+            }
+            ControlPointRequest::Stop => bytes.push(0x01),
+            ControlPointRequest::Pause => bytes.push(0x02),
+            ControlPointRequest::RequestControl
+            | ControlPointRequest::Reset
+            | ControlPointRequest::StartOrResume => {}
+        }
+        bytes
+    }
+    pub fn decode(bytes: &[u8]) -> Result<Self, FtmsError> {
+        let (op_code, parameters) = bytes.split_first().ok_or(FtmsError::Empty)?;
+        match OpCode::from_byte(*op_code)? {
+            OpCode::RequestControl => Ok(ControlPointRequest::RequestControl),
+            OpCode::Reset => Ok(ControlPointRequest::Reset),
+            OpCode::SetTargetPower => Ok(ControlPointRequest::SetTargetPower(
+                Reader::new(parameters).i16()?,
+            )),
+            OpCode::StartOrResume => Ok(ControlPointRequest::StartOrResume),
+            OpCode::StopOrPause => match Reader::new(parameters).u8()? {
+                0x02 => Ok(ControlPointRequest::Pause),
+                _ => Ok(ControlPointRequest::Stop),
+            },
+        }
+    }
+}
+
+/// Result of a control point request as reported by the trainer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultCode {
+    Success,
+    OpCodeNotSupported,
+    InvalidParameter,
+    OperationFailed,
+    ControlNotPermitted,
+}
+
+impl ResultCode {
+    fn to_byte(self) -> u8 {
+        match self {
+            ResultCode::Success => 0x01,
+            ResultCode::OpCodeNotSupported => 0x02,
+            ResultCode::InvalidParameter => 0x03,
+            ResultCode::OperationFailed => 0x04,
+            ResultCode::ControlNotPermitted => 0x05,
+        }
+    }
+    fn from_byte(byte: u8) -> Result<Self, FtmsError> {
+        match byte {
+            0x01 => Ok(ResultCode::Success),
+            0x02 => Ok(ResultCode::OpCodeNotSupported),
+            0x03 => Ok(ResultCode::InvalidParameter),
+            0x04 => Ok(ResultCode::OperationFailed),
+            0x05 => Ok(ResultCode::ControlNotPermitted),
+            unknown => Err(FtmsError::UnknownResultCode(unknown)),
+        }
+    }
+}
+
+/// The indication answering a control point request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlPointResponse {
+    pub request: OpCode,
+    pub result: ResultCode,
+}
+
+impl ControlPointResponse {
+    pub fn encode(&self) -> Vec<u8> {
+        vec![RESPONSE_CODE, self.request.to_byte(), self.result.to_byte()]
+    }
+    pub fn decode(bytes: &[u8]) -> Result<Self, FtmsError> {
+        let mut reader = Reader::new(bytes);
+        match reader.u8() {
+            Ok(RESPONSE_CODE) => Ok(ControlPointResponse {
+                request: OpCode::from_byte(reader.u8()?)?,
+                result: ResultCode::from_byte(reader.u8()?)?,
+            }),
+            Ok(other) => Err(FtmsError::NotAResponse(other)),
+            Err(_) => Err(FtmsError::Empty),
+        }
+    }
+}
+
+/// Flags of the indoor bike data characteristic, telling which fields are present.
+mod flags {
+    /// Unlike all other flags, a set bit means instantaneous speed is *missing*.
+    pub const MORE_DATA: u16 = 1 << 0;
+    pub const AVERAGE_SPEED: u16 = 1 << 1;
+    pub const INSTANTANEOUS_CADENCE: u16 = 1 << 2;
+    pub const AVERAGE_CADENCE: u16 = 1 << 3;
+    pub const TOTAL_DISTANCE: u16 = 1 << 4;
+    pub const RESISTANCE_LEVEL: u16 = 1 << 5;
+    pub const INSTANTANEOUS_POWER: u16 = 1 << 6;
+    pub const AVERAGE_POWER: u16 = 1 << 7;
+    pub const EXPENDED_ENERGY: u16 = 1 << 8;
+    pub const HEART_RATE: u16 = 1 << 9;
+    pub const METABOLIC_EQUIVALENT: u16 = 1 << 10;
+    pub const ELAPSED_TIME: u16 = 1 << 11;
+}
+
+/// A notification of the indoor bike data characteristic.
+/// Fields the trainer does not report are `None`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct IndoorBikeData {
+    pub speed_in_km_per_h: Option<f64>,
+    pub cadence_in_rpm: Option<f64>,
+    pub power_in_watts: Option<i16>,
+    pub heart_rate: Option<u8>,
+    pub elapsed_seconds: Option<u16>,
+}
+
+impl IndoorBikeData {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut flags = 0;
+        let mut fields = Vec::new();
+        match self.speed_in_km_per_h {
+            Some(speed) => {
+                fields.extend_from_slice(&((speed * 100.0).round() as u16).to_le_bytes())
+            }
+            None => flags |= flags::MORE_DATA,
+        }
+        if let Some(cadence) = self.cadence_in_rpm {
+            flags |= flags::INSTANTANEOUS_CADENCE;
+            fields.extend_from_slice(&((cadence * 2.0).round() as u16).to_le_bytes());
+        }
+        if let Some(power) = self.power_in_watts {
+            flags |= flags::INSTANTANEOUS_POWER;
+            fields.extend_from_slice(&power.to_le_bytes());
+        }
+        if let Some(heart_rate) = self.heart_rate {
+            flags |= flags::HEART_RATE;
+            fields.push(heart_rate);
+        }
+        if let Some(elapsed_seconds) = self.elapsed_seconds {
+            flags |= flags::ELAPSED_TIME;
+            fields.extend_from_slice(&elapsed_seconds.to_le_bytes());
+        }
+
+        let mut bytes = flags.to_le_bytes().to_vec();
+        bytes.extend(fields);
+        bytes
+    }
+    /// Fields not kept by this struct are skipped.
+    pub fn decode(bytes: &[u8]) -> Result<Self, FtmsError> {
+        let mut reader = Reader::new(bytes);
+        let flags = reader.u16()?;
+        let is_set = |flag: u16| flags & flag != 0;
+        let mut data = IndoorBikeData::default();
+
+        if !is_set(flags::MORE_DATA) {
+            data.speed_in_km_per_h = Some(f64::from(reader.u16()?) / 100.0);
+        }
+        if is_set(flags::AVERAGE_SPEED) {
+            reader.skip(2)?;
+        }
+        if is_set(flags::INSTANTANEOUS_CADENCE) {
+            data.cadence_in_rpm = Some(f64::from(reader.u16()?) / 2.0);
+        }
+        if is_set(flags::AVERAGE_CADENCE) {
+            reader.skip(2)?;
+        }
+        if is_set(flags::TOTAL_DISTANCE) {
+            reader.skip(3)?;
+        }
+        if is_set(flags::RESISTANCE_LEVEL) {
+            reader.skip(2)?;
+        }
+        if is_set(flags::INSTANTANEOUS_POWER) {
+            data.power_in_watts = Some(reader.i16()?);
+        }
+        if is_set(flags::AVERAGE_POWER) {
+            reader.skip(2)?;
+        }
+        if is_set(flags::EXPENDED_ENERGY) {
+            reader.skip(5)?;
+        }
+        if is_set(flags::HEART_RATE) {
+            data.heart_rate = Some(reader.u8()?);
+        }
+        if is_set(flags::METABOLIC_EQUIVALENT) {
+            reader.skip(1)?;
+        }
+        if is_set(flags::ELAPSED_TIME) {
+            data.elapsed_seconds = Some(reader.u16()?);
+        }
+        Ok(data)
+    }
+}
+
+/// Reads little endian values from the front of a message.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+    fn take(&mut self, count: usize) -> Result<&'a [u8], FtmsError> {
+        if self.bytes.len() < count {
+            return Err(FtmsError::TooShort);
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+    fn skip(&mut self, count: usize) -> Result<(), FtmsError> {
+        self.take(count).map(|_| ())
+    }
+    fn u8(&mut self) -> Result<u8, FtmsError> {
+        Ok(self.take(1)?[0])
+    }
+    fn u16(&mut self) -> Result<u16, FtmsError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+    fn i16(&mut self) -> Result<i16, FtmsError> {
+        let bytes = self.take(2)?;
+        Ok(i16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    mod control_point {
+        use super::*;
+
+        #[test]
+        fn set_target_power_is_little_endian() {
+            assert_eq!(
+                ControlPointRequest::SetTargetPower(260).encode(),
+                vec![0x05, 0x04, 0x01]
+            );
+        }
+        #[test]
+        fn requests_round_trip() {
+            for request in [
+                ControlPointRequest::RequestControl,
+                ControlPointRequest::Reset,
+                ControlPointRequest::SetTargetPower(-5),
+                ControlPointRequest::StartOrResume,
+                ControlPointRequest::Stop,
+                ControlPointRequest::Pause,
+            ] {
+                assert_eq!(ControlPointRequest::decode(&request.encode()), Ok(request));
+            }
+        }
+        #[test]
+        fn unknown_op_code() {
+            assert_eq!(
+                ControlPointRequest::decode(&[0x42]),
+                Err(FtmsError::UnknownOpCode(0x42))
+            );
+        }
+        #[test]
+        fn target_power_without_parameter() {
+            assert_eq!(
+                ControlPointRequest::decode(&[0x05, 0x01]),
+                Err(FtmsError::TooShort)
+            );
+        }
+        #[test]
+        fn decode_response() {
+            assert_eq!(
+                ControlPointResponse::decode(&[0x80, 0x05, 0x05]),
+                Ok(ControlPointResponse {
+                    request: OpCode::SetTargetPower,
+                    result: ResultCode::ControlNotPermitted
+                })
+            );
+        }
+        #[test]
+        fn response_needs_response_code() {
+            assert_eq!(
+                ControlPointResponse::decode(&[0x05, 0x05, 0x01]),
+                Err(FtmsError::NotAResponse(0x05))
+            );
+        }
+    }
+
+    mod indoor_bike_data {
+        use super::*;
+
+        #[test]
+        fn decode_speed_cadence_and_power() {
+            // Speed 30.00 km/h, cadence 90 rpm, power 250 W.
+            let bytes = [0x44, 0x00, 0xB8, 0x0B, 0xB4, 0x00, 0xFA, 0x00];
+            assert_eq!(
+                IndoorBikeData::decode(&bytes),
+                Ok(IndoorBikeData {
+                    speed_in_km_per_h: Some(30.0),
+                    cadence_in_rpm: Some(90.0),
+                    power_in_watts: Some(250),
+                    heart_rate: None,
+                    elapsed_seconds: None,
+                })
+            );
+        }
+        #[test]
+        fn skips_unused_fields() {
+            // More data set, total distance 1000 m, power 100 W, expended energy, heart rate 140.
+            let bytes = [
+                0x51, 0x03, 0xE8, 0x03, 0x00, 0x64, 0x00, 0x10, 0x00, 0xFF, 0xFF, 0xFF, 0x8C,
+            ];
+            assert_eq!(
+                IndoorBikeData::decode(&bytes),
+                Ok(IndoorBikeData {
+                    power_in_watts: Some(100),
+                    heart_rate: Some(140),
+                    ..IndoorBikeData::default()
+                })
+            );
+        }
+        #[test]
+        fn round_trip() {
+            let data = IndoorBikeData {
+                speed_in_km_per_h: Some(32.5),
+                cadence_in_rpm: Some(87.5),
+                power_in_watts: Some(310),
+                heart_rate: Some(150),
+                elapsed_seconds: Some(3600),
+            };
+            assert_eq!(IndoorBikeData::decode(&data.encode()), Ok(data));
+        }
+        #[test]
+        fn truncated_notification() {
+            assert_eq!(
+                IndoorBikeData::decode(&[0x40, 0x00, 0xFA]),
+                Err(FtmsError::TooShort)
+            );
+        }
+    }
+}
@@ -0,0 +1,32 @@
+/// Encoding and decoding of the Fitness Machine Service (FTMS) messages.
+pub mod ftms;
+
+/// Drives a trainer through a workout.
+pub mod session;
+
+/// A trainer living in memory, to ride without hardware.
+pub mod simulated;
+
+use ftms::{FtmsError, OpCode, ResultCode};
+
+/// A connection to a trainer, e.g. over Bluetooth.
+/// Messages are passed as raw bytes as they are sent over the air.
+pub trait Transport {
+    /// Write to the FTMS control point and return the indication answering it.
+    fn write_control_point(&mut self, request: &[u8]) -> Result<Vec<u8>, TrainerError>;
+    /// Indoor bike data notifications received since the last poll.
+    fn poll_notifications(&mut self) -> Vec<Vec<u8>>;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TrainerError {
+    Codec(FtmsError),
+    Rejected { op_code: OpCode, result: ResultCode },
+    Transport(String),
+}
+
+impl From<FtmsError> for TrainerError {
+    fn from(error: FtmsError) -> Self {
+        TrainerError::Codec(error)
+    }
+}
@@ -0,0 +1,129 @@
+use super::ftms::{ControlPointRequest, ControlPointResponse, IndoorBikeData, ResultCode};
+use super::{TrainerError, Transport};
+use crate::workout_data::player::{Clock, ManualClock, PlayerState, WorkoutPlayer};
+use crate::workout_data::workout::Workout;
+use std::time::Duration;
+
+/// Keeps a trainer in ERG mode at the target of a workout player.
+pub struct TrainerSession<T: Transport> {
+    transport: T,
+    /// Last target sent, to not flood the trainer with identical requests.
+    target_power: Option<i16>,
+    /// All notifications received during the session.
+    ride_data: Vec<IndoorBikeData>,
+}
+
+impl<T: Transport> TrainerSession<T> {
+    /// Take control of the trainer and start it.
+    pub fn start(transport: T) -> Result<Self, TrainerError> {
+        let mut session = Self {
+            transport,
+            target_power: None,
+            ride_data: Vec::new(),
+        };
+        session.send(ControlPointRequest::RequestControl)?;
+        session.send(ControlPointRequest::StartOrResume)?;
+        Ok(session)
+    }
+
+    /// Update the trainer to the current state of the player
+    /// and collect the data it reported since the last call.
+    pub fn follow<C: Clock>(&mut self, player: &WorkoutPlayer<C>) -> Result<(), TrainerError> {
+        if let Some(target) = player.current_target() {
+            let target = target.round() as i16;
+            if self.target_power != Some(target) {
+                self.send(ControlPointRequest::SetTargetPower(target))?;
+                self.target_power = Some(target);
+            }
+        }
+        for notification in self.transport.poll_notifications() {
+            self.ride_data.push(IndoorBikeData::decode(&notification)?);
+        }
+        Ok(())
+    }
+
+    /// Stop the trainer and return everything it reported.
+    pub fn stop(mut self) -> Result<Vec<IndoorBikeData>, TrainerError> {
+        self.send(ControlPointRequest::Stop)?;
+        Ok(self.ride_data)
+    }
+
+    pub fn ride_data(&self) -> &[IndoorBikeData] {
+        &self.ride_data
+    }
+
+    fn send(&mut self, request: ControlPointRequest) -> Result<(), TrainerError> {
+        let response =
+            ControlPointResponse::decode(&self.transport.write_control_point(&request.encode())?)?;
+        match response.result {
+            ResultCode::Success if response.request == request.op_code() => Ok(()),
+            result => Err(TrainerError::Rejected {
+                op_code: response.request,
+                result,
+            }),
+        }
+    }
+}
+
+/// Ride the whole workout second by second without waiting in real time.
+pub fn ride_workout<T: Transport>(
+    workout: &Workout,
+    transport: T,
+) -> Result<Vec<IndoorBikeData>, TrainerError> {
+    let clock = ManualClock::default();
+    let mut player = WorkoutPlayer::new(workout, &clock);
+    let mut session = TrainerSession::start(transport)?;
+
+    player.play();
+    while player.state() != PlayerState::Finished {
+        session.follow(&player)?;
+        clock.advance(Duration::from_secs(1));
+    }
+    session.stop()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::trainer::simulated::SimulatedTrainer;
+    use crate::workout_data::test_workout;
+
+    #[test]
+    fn ride_follows_workout() {
+        let ride_data = ride_workout(&test_workout(), SimulatedTrainer::default()).unwrap();
+        let powers: Vec<i16> = ride_data
+            .iter()
+            .filter_map(|data| data.power_in_watts)
+            .collect();
+
+        assert_eq!(powers.len(), 27 * 60);
+        assert_eq!(powers[0], 100);
+        assert_eq!(powers[300], 150);
+        assert_eq!(powers[450], 175);
+        assert!(powers[600..900].iter().all(|&power| power == 300));
+    }
+    #[test]
+    fn identical_targets_are_sent_once() {
+        let clock = ManualClock::default();
+        let mut player = WorkoutPlayer::new(&test_workout(), &clock);
+        let mut session = TrainerSession::start(SimulatedTrainer::default()).unwrap();
+        player.play();
+        player.skip();
+        for _ in 0..10 {
+            session.follow(&player).unwrap();
+            clock.advance(Duration::from_secs(1));
+        }
+        assert_eq!(session.transport.target_power_requests(), 1);
+    }
+    #[test]
+    fn rejected_request_is_an_error() {
+        let trainer = SimulatedTrainer::default().refusing_control();
+        assert_eq!(
+            TrainerSession::start(trainer).err(),
+            Some(TrainerError::Rejected {
+                op_code: crate::trainer::ftms::OpCode::RequestControl,
+                result: ResultCode::ControlNotPermitted
+            })
+        );
+    }
+}
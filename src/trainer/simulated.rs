@@ -0,0 +1,148 @@
+use super::ftms::{ControlPointRequest, ControlPointResponse, IndoorBikeData, ResultCode};
+use super::{TrainerError, Transport};
+
+/// Cadence the simulated rider pedals at.
+const CADENCE_IN_RPM: f64 = 90.0;
+
+/// A trainer that holds the target power perfectly.
+/// Each poll reports one second of riding while the trainer is started.
+#[derive(Default)]
+pub struct SimulatedTrainer {
+    refuses_control: bool,
+    in_control: bool,
+    running: bool,
+    target_power: i16,
+    elapsed_seconds: u16,
+    target_power_requests: usize,
+}
+
+impl SimulatedTrainer {
+    /// A trainer already controlled by someone else.
+    pub fn refusing_control(mut self) -> Self {
+        self.refuses_control = true;
+        self
+    }
+    pub fn target_power_requests(&self) -> usize {
+        self.target_power_requests
+    }
+
+    fn handle(&mut self, request: ControlPointRequest) -> ResultCode {
+        match request {
+            ControlPointRequest::RequestControl if self.refuses_control => {
+                ResultCode::ControlNotPermitted
+            }
+            ControlPointRequest::RequestControl => {
+                self.in_control = true;
+                ResultCode::Success
+            }
+            _ if !self.in_control => ResultCode::ControlNotPermitted,
+            ControlPointRequest::Reset => {
+                *self = SimulatedTrainer::default();
+                ResultCode::Success
+            }
+            ControlPointRequest::SetTargetPower(watts) if watts < 0 => ResultCode::InvalidParameter,
+            ControlPointRequest::SetTargetPower(watts) => {
+                self.target_power = watts;
+                self.target_power_requests += 1;
+                ResultCode::Success
+            }
+            ControlPointRequest::StartOrResume => {
+                self.running = true;
+                ResultCode::Success
+            }
+            ControlPointRequest::Stop | ControlPointRequest::Pause => {
+                self.running = false;
+                ResultCode::Success
+            }
+        }
+    }
+}
+
+impl Transport for SimulatedTrainer {
+    fn write_control_point(&mut self, request: &[u8]) -> Result<Vec<u8>, TrainerError> {
+        let request = ControlPointRequest::decode(request)?;
+        Ok(ControlPointResponse {
+            request: request.op_code(),
+            result: self.handle(request),
+        }
+        .encode())
+    }
+
+    fn poll_notifications(&mut self) -> Vec<Vec<u8>> {
+        if !self.running {
+            return Vec::new();
+        }
+        self.elapsed_seconds = self.elapsed_seconds.saturating_add(1);
+        vec![IndoorBikeData {
+            speed_in_km_per_h: Some(speed_for_power(self.target_power)),
+            cadence_in_rpm: Some(CADENCE_IN_RPM),
+            power_in_watts: Some(self.target_power),
+            heart_rate: None,
+            elapsed_seconds: Some(self.elapsed_seconds),
+        }
+        .encode()]
+    }
+}
+
+/// Air drag of the simulated rider, `0.5 * air density * CdA`.
+const DRAG_COEFFICIENT: f64 = 0.18;
+
+/// Rough flat road speed for a power, good enough for a simulation.
+fn speed_for_power(watts: i16) -> f64 {
+    let meters_per_second = (f64::from(watts.max(0)) / DRAG_COEFFICIENT).cbrt();
+    meters_per_second * 3.6
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write(trainer: &mut SimulatedTrainer, request: ControlPointRequest) -> ResultCode {
+        ControlPointResponse::decode(&trainer.write_control_point(&request.encode()).unwrap())
+            .unwrap()
+            .result
+    }
+
+    #[test]
+    fn control_must_be_requested_first() {
+        let mut trainer = SimulatedTrainer::default();
+        assert_eq!(
+            write(&mut trainer, ControlPointRequest::SetTargetPower(200)),
+            ResultCode::ControlNotPermitted
+        );
+        assert_eq!(
+            write(&mut trainer, ControlPointRequest::RequestControl),
+            ResultCode::Success
+        );
+        assert_eq!(
+            write(&mut trainer, ControlPointRequest::SetTargetPower(200)),
+            ResultCode::Success
+        );
+    }
+    #[test]
+    fn only_reports_while_running() {
+        let mut trainer = SimulatedTrainer::default();
+        write(&mut trainer, ControlPointRequest::RequestControl);
+        write(&mut trainer, ControlPointRequest::SetTargetPower(200));
+        assert!(trainer.poll_notifications().is_empty());
+
+        write(&mut trainer, ControlPointRequest::StartOrResume);
+        let notifications = trainer.poll_notifications();
+        assert_eq!(notifications.len(), 1);
+        let data = IndoorBikeData::decode(&notifications[0]).unwrap();
+        assert_eq!(data.power_in_watts, Some(200));
+        assert_eq!(data.elapsed_seconds, Some(1));
+
+        write(&mut trainer, ControlPointRequest::Pause);
+        assert!(trainer.poll_notifications().is_empty());
+    }
+    #[test]
+    fn negative_power_is_invalid() {
+        let mut trainer = SimulatedTrainer::default();
+        write(&mut trainer, ControlPointRequest::RequestControl);
+        assert_eq!(
+            write(&mut trainer, ControlPointRequest::SetTargetPower(-1)),
+            ResultCode::InvalidParameter
+        );
+    }
+}
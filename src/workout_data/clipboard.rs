@@ -0,0 +1,186 @@
+#[cfg(feature = "zwo")]
+use crate::workout_data::text_format::ExtractTextFormatError;
+use crate::workout_data::text_format::Thresholds;
+use crate::workout_data::workout::{efforts_to_mrc, ExtractWorkoutError, Workout};
+use regex::Regex;
+use std::fmt;
+
+/// Formats workouts and efforts are copied in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyFormat {
+    /// Plain text steps like `- 5m 200W`.
+    #[default]
+    Text,
+    /// The minutes and watts of the `[COURSE DATA]` of an MRC file.
+    CourseData,
+    Json,
+}
+
+impl CopyFormat {
+    pub const ALL: [CopyFormat; 3] = [CopyFormat::Text, CopyFormat::CourseData, CopyFormat::Json];
+}
+
+impl fmt::Display for CopyFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            CopyFormat::Text => "Text",
+            CopyFormat::CourseData => "MRC",
+            CopyFormat::Json => "JSON",
+        })
+    }
+}
+
+/// Formats pasted text is recognised in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PastedFormat {
+    Mrc,
+    /// Only the lines of minutes and watts of an MRC file.
+    CourseData,
+    Plan,
+    Json,
+    #[cfg(feature = "zwo")]
+    Zwo,
+    Text,
+}
+
+/// Write the workout to be pasted into another workout or application.
+/// Targets of the text format are in percent of `ftp` if given.
+pub fn copy(workout: &Workout, format: CopyFormat, ftp: Option<f64>) -> String {
+    match format {
+        CopyFormat::Text => workout.to_text_format(ftp),
+        CopyFormat::CourseData => efforts_to_mrc(workout.efforts(), 0.0).0,
+        CopyFormat::Json => workout.to_json(),
+    }
+}
+
+/// Recognise the format of pasted text by its markers.
+/// Text without any is read as course data if it only contains pairs of numbers.
+pub fn detect_format(text: &str) -> PastedFormat {
+    let course_data_line =
+        Regex::new(r"^\s*\d+(\.\d+)?\s+\d+(\.\d+)?\s*$").expect("This regex is valid.");
+    let trimmed = text.trim_start();
+    if trimmed.starts_with('{') {
+        return PastedFormat::Json;
+    }
+    if trimmed.starts_with("=HEADER=") || text.contains("=STREAM=") {
+        return PastedFormat::Plan;
+    }
+    if text.contains("[COURSE DATA]") {
+        return PastedFormat::Mrc;
+    }
+    #[cfg(feature = "zwo")]
+    if text.contains("<workout_file") || text.contains("<workout>") {
+        return PastedFormat::Zwo;
+    }
+    let mut lines = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .peekable();
+    if lines.peek().is_some() && lines.all(|line| course_data_line.is_match(line)) {
+        return PastedFormat::CourseData;
+    }
+    PastedFormat::Text
+}
+
+/// Read a workout from pasted text in any of the formats of `detect_format`.
+pub fn paste(text: &str, thresholds: &Thresholds) -> Result<Workout, ExtractWorkoutError> {
+    match detect_format(text) {
+        PastedFormat::Mrc => Workout::from_mrc(text),
+        PastedFormat::CourseData => Workout::from_mrc(&format!(
+            "[COURSE DATA]\n{}\n[END COURSE DATA]",
+            text.trim()
+        )),
+        PastedFormat::Plan => Workout::from_plan_format(text),
+        PastedFormat::Json => Workout::from_json(text),
+        #[cfg(feature = "zwo")]
+        PastedFormat::Zwo => match thresholds.ftp {
+            Some(ftp) => Workout::from_zwo(text, ftp),
+            None => Err(ExtractTextFormatError::MissingFtp.into()),
+        },
+        PastedFormat::Text => Workout::from_text_format(text, thresholds),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workout_data::effort::Effort;
+    use crate::workout_data::test_workout;
+
+    #[test]
+    fn formats_are_detected() {
+        assert_eq!(detect_format(&test_workout().to_json()), PastedFormat::Json);
+        assert_eq!(
+            detect_format(&test_workout().to_plan_format()),
+            PastedFormat::Plan
+        );
+        assert_eq!(detect_format(&test_workout().to_mrc()), PastedFormat::Mrc);
+        assert_eq!(
+            detect_format("0.00\t100.00\n10.00   150.00\n"),
+            PastedFormat::CourseData
+        );
+        assert_eq!(detect_format("- 10m 200W"), PastedFormat::Text);
+        assert_eq!(detect_format(""), PastedFormat::Text);
+    }
+    #[cfg(feature = "zwo")]
+    #[test]
+    fn zwift_workout_is_detected() {
+        assert_eq!(
+            detect_format(&test_workout().to_zwo(200.0)),
+            PastedFormat::Zwo
+        );
+    }
+    #[test]
+    fn course_data_round_trip() {
+        let workout = Workout::new(
+            "Sweet Spot",
+            "",
+            vec![
+                Effort::new(10.0, 100.0, Some(150.0)),
+                Effort::new(20.0, 220.0, None),
+            ],
+        );
+        let copied = copy(&workout, CopyFormat::CourseData, None);
+        assert!(!copied.contains("[COURSE DATA]"));
+        assert_eq!(
+            paste(&copied, &Thresholds::default()).map(|pasted| pasted.efforts().to_vec()),
+            Ok(workout.efforts().to_vec())
+        );
+    }
+    #[test]
+    fn json_round_trip() {
+        let copied = copy(&test_workout(), CopyFormat::Json, None);
+        assert_eq!(paste(&copied, &Thresholds::default()), Ok(test_workout()));
+    }
+    #[test]
+    fn text_in_percent_of_ftp() {
+        let workout = Workout::new(
+            "Sweet Spot",
+            "",
+            vec![
+                Effort::new(10.0, 100.0, Some(150.0)),
+                Effort::new(20.0, 220.0, None),
+            ],
+        );
+        let thresholds = Thresholds {
+            ftp: Some(200.0),
+            lthr: None,
+        };
+        let copied = copy(&workout, CopyFormat::Text, thresholds.ftp);
+        assert!(copied.contains('%'));
+        assert_eq!(
+            paste(&copied, &thresholds).map(|pasted| pasted.efforts().to_vec()),
+            Ok(workout.efforts().to_vec())
+        );
+    }
+    #[cfg(feature = "zwo")]
+    #[test]
+    fn zwift_workout_needs_ftp() {
+        assert_eq!(
+            paste(&test_workout().to_zwo(200.0), &Thresholds::default()),
+            Err(ExtractWorkoutError::FromTextFormat(
+                ExtractTextFormatError::MissingFtp
+            ))
+        );
+    }
+}
@@ -1,6 +1,11 @@
 use crate::workout_data::ToMRC;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
 const SPLITTING_THRESHOLD_IN_MINUTES: f64 = 0.2;
+/// Differences of durations below this are rounding errors.
+const ROUNDING_TOLERANCE_IN_MINUTES: f64 = 1e-9;
 
 pub fn is_ramp_effort(effort: &Effort) -> bool {
     effort.starting_value != effort.ending_value
@@ -8,30 +13,112 @@ pub fn is_ramp_effort(effort: &Effort) -> bool {
 pub fn effort_can_be_split(effort: &Effort) -> bool {
     effort.duration_in_minutes > SPLITTING_THRESHOLD_IN_MINUTES
 }
+/// The part of a workout an effort belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Section {
+    Warmup,
+    #[default]
+    Main,
+    Cooldown,
+}
+
+/// Why an effort could not be split into steady chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SplitRampError {
+    NonPositiveDuration,
+    NoRamp,
+}
+
 /// Combining a type of effort with a duration
 /// for which it should be executed.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Effort {
     pub(crate) duration_in_minutes: f64,
     pub(crate) starting_value: f64,
     pub(crate) ending_value: f64,
-    pub gui_state: EffortState,
+    #[serde(default)]
+    pub(crate) section: Section,
+    /// Cadence to ride the effort at, in revolutions per minute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) cadence: Option<u32>,
+    /// Instruction shown to the rider during the effort.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) cue: Option<String>,
+    /// Label of the effort, like `Warmup` or `VO2 #3`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) name: Option<String>,
+    /// Color to draw the effort in instead of the one of its power zone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) color: Option<EffortColor>,
+    /// Band of wattage the rider may stay within, like 88 to 94% of the FTP,
+    /// while the trainer is set to the target value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) target_range: Option<TargetRange>,
 }
 
-#[derive(Debug, Clone, Default)]
-pub enum EffortState {
-    #[default]
-    Idle,
-    Editing {
-        starting_value: String,
-        ending_value: String,
-        duration_in_minutes: String,
-    },
+/// Lowest and highest wattage of a target band.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TargetRange {
+    pub low: f64,
+    pub high: f64,
+}
+
+/// A color written as `#rrggbb`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct EffortColor {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+#[derive(PartialEq, Debug)]
+pub struct ParseEffortColorError(String);
+
+impl fmt::Display for ParseEffortColorError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{:?} is no color like #ff8800", self.0)
+    }
 }
 
-impl PartialEq for EffortState {
-    fn eq(&self, other: &Self) -> bool {
-        format!("{:?}", self) == format!("{:?}", other)
+impl FromStr for EffortColor {
+    type Err = ParseEffortColorError;
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        let error = || ParseEffortColorError(String::from(hex));
+        let digits = hex.trim().strip_prefix('#').ok_or_else(error)?;
+        if digits.len() != 6 || !digits.is_ascii() {
+            return Err(error());
+        }
+        let channel = |start: usize| u8::from_str_radix(&digits[start..start + 2], 16);
+        Ok(Self {
+            red: channel(0).map_err(|_| error())?,
+            green: channel(2).map_err(|_| error())?,
+            blue: channel(4).map_err(|_| error())?,
+        })
+    }
+}
+
+impl TryFrom<String> for EffortColor {
+    type Error = ParseEffortColorError;
+    fn try_from(hex: String) -> Result<Self, Self::Error> {
+        hex.parse()
+    }
+}
+
+impl From<EffortColor> for String {
+    fn from(color: EffortColor) -> Self {
+        color.to_string()
+    }
+}
+
+impl fmt::Display for EffortColor {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "#{:02x}{:02x}{:02x}",
+            self.red, self.green, self.blue
+        )
     }
 }
 
@@ -42,29 +129,139 @@ impl Effort {
             duration_in_minutes,
             starting_value,
             ending_value: ending_value.unwrap_or(starting_value),
-            gui_state: EffortState::default(),
+            section: Section::default(),
+            cadence: None,
+            cue: None,
+            name: None,
+            color: None,
+            target_range: None,
         }
     }
-    pub fn split_ramp_effort_into_constant_chunks(&self) -> Vec<Effort> {
-        assert!(self.duration_in_minutes > 0.0);
-        assert!(self.starting_value != self.ending_value);
-
-        let steps = (self.duration_in_minutes / SPLITTING_THRESHOLD_IN_MINUTES).ceil() as usize;
-        let step_size = (self.ending_value - self.starting_value) / (steps as f64);
-        let mut result = Vec::with_capacity(steps);
-
-        for index in 0..steps {
-            let interpolated_effort_value =
-                self.starting_value + (index as f64 * step_size) + step_size / 2.0;
+    /// Assign the effort to a section of the workout.
+    pub fn with_section(self, section: Section) -> Self {
+        Self { section, ..self }
+    }
+    pub fn section(&self) -> Section {
+        self.section
+    }
+    pub fn with_cadence(self, cadence: u32) -> Self {
+        Self {
+            cadence: Some(cadence),
+            ..self
+        }
+    }
+    pub fn cadence(&self) -> Option<u32> {
+        self.cadence
+    }
+    pub fn with_cue(self, cue: &str) -> Self {
+        Self {
+            cue: Some(String::from(cue)),
+            ..self
+        }
+    }
+    pub fn cue(&self) -> Option<&str> {
+        self.cue.as_deref()
+    }
+    pub fn with_name(self, name: &str) -> Self {
+        Self {
+            name: Some(String::from(name)),
+            ..self
+        }
+    }
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    pub fn with_color(self, color: EffortColor) -> Self {
+        Self {
+            color: Some(color),
+            ..self
+        }
+    }
+    pub fn color(&self) -> Option<EffortColor> {
+        self.color
+    }
+    /// Let the rider stay between `low` and `high`, in either order.
+    pub fn with_target_range(self, low: f64, high: f64) -> Self {
+        Self {
+            target_range: Some(TargetRange {
+                low: low.min(high),
+                high: low.max(high),
+            }),
+            ..self
+        }
+    }
+    pub fn target_range(&self) -> Option<TargetRange> {
+        self.target_range
+    }
+    pub fn duration_in_minutes(&self) -> f64 {
+        self.duration_in_minutes
+    }
+    pub fn starting_value(&self) -> f64 {
+        self.starting_value
+    }
+    pub fn ending_value(&self) -> f64 {
+        self.ending_value
+    }
+    /// Split the effort after `minutes` into two efforts,
+    /// `None` unless that is strictly within the effort.
+    /// The value of a ramp is interpolated at the splitting point.
+    pub fn split_at(&self, minutes: f64) -> Option<(Effort, Effort)> {
+        if !(minutes > 0.0 && minutes < self.duration_in_minutes) {
+            return None;
+        }
 
-            result.push(Effort {
-                duration_in_minutes: SPLITTING_THRESHOLD_IN_MINUTES,
-                starting_value: interpolated_effort_value,
-                ending_value: interpolated_effort_value,
-                gui_state: self.gui_state.clone(),
-            });
+        let value_at_split = self.starting_value
+            + (self.ending_value - self.starting_value) * (minutes / self.duration_in_minutes);
+        Some((
+            Effort {
+                duration_in_minutes: minutes,
+                ending_value: value_at_split,
+                ..self.clone()
+            },
+            Effort {
+                duration_in_minutes: self.duration_in_minutes - minutes,
+                starting_value: value_at_split,
+                ..self.clone()
+            },
+        ))
+    }
+    /// Steady chunks of `SPLITTING_THRESHOLD_IN_MINUTES` following the ramp,
+    /// each at the value in its middle. The last chunk holds the remainder,
+    /// so the chunks last as long as the ramp.
+    pub fn split_ramp_effort_into_constant_chunks(&self) -> Result<Vec<Effort>, SplitRampError> {
+        if self.duration_in_minutes <= 0.0 {
+            return Err(SplitRampError::NonPositiveDuration);
         }
-        result
+        if !is_ramp_effort(self) {
+            return Err(SplitRampError::NoRamp);
+        }
+
+        let steps = ((self.duration_in_minutes - ROUNDING_TOLERANCE_IN_MINUTES)
+            / SPLITTING_THRESHOLD_IN_MINUTES)
+            .ceil()
+            .max(1.0) as usize;
+        let slope = (self.ending_value - self.starting_value) / self.duration_in_minutes;
+        Ok((0..steps)
+            .map(|index| {
+                let starting_minute = index as f64 * SPLITTING_THRESHOLD_IN_MINUTES;
+                let remainder = self.duration_in_minutes - starting_minute;
+                let duration_in_minutes = if index + 1 == steps
+                    && remainder < SPLITTING_THRESHOLD_IN_MINUTES - ROUNDING_TOLERANCE_IN_MINUTES
+                {
+                    remainder
+                } else {
+                    SPLITTING_THRESHOLD_IN_MINUTES
+                };
+                let value =
+                    self.starting_value + slope * (starting_minute + duration_in_minutes / 2.0);
+                Effort {
+                    duration_in_minutes,
+                    starting_value: value,
+                    ending_value: value,
+                    ..self.clone()
+                }
+            })
+            .collect())
     }
     pub fn to_mrc(&self, starting_minute: f64) -> (String, f64) {
         let end_of_effort = starting_minute + self.duration_in_minutes;
@@ -76,83 +273,36 @@ impl Effort {
             end_of_effort,
         )
     }
+    /// The effort as interval of a plan file, with its name as message.
+    /// Its target range becomes the range of power, or else its starting value.
     pub fn to_plan_format(&self) -> String {
+        let message = match &self.name {
+            Some(name) => format!("MESG={}\n", name.replace(['\n', '\r'], " ")),
+            None => String::new(),
+        };
+        let (low, high) = match self.target_range {
+            Some(range) => (range.low, range.high),
+            None => (self.starting_value, self.starting_value),
+        };
         format!(
             "=INTERVAL=\n\
+                {}\
                 PWR_LO={}\n\
                 PWR_HI={}\n\
                 MESG_DURATION_SEC>={}?EXIT",
-            self.starting_value,
-            self.starting_value,
+            message,
+            low,
+            high,
             (self.duration_in_minutes * 60.0).round() as i64
         )
     }
-
-    pub fn to_edit(&mut self) {
-        self.gui_state = EffortState::Editing {
-            starting_value: self.starting_value.to_mrc(),
-            ending_value: self.ending_value.to_mrc(),
-            duration_in_minutes: self.duration_in_minutes.to_mrc(),
-        }
-    }
-    pub fn to_idle(&self) -> Option<Effort> {
-        if let EffortState::Editing {
-            starting_value,
-            ending_value,
-            duration_in_minutes,
-        } = &self.gui_state
-        {
-            let new_ending_value = if ending_value.is_empty() {
-                None
-            } else {
-                Some(ending_value.parse().ok()?)
-            };
-
-            return Some(Effort::new(
-                duration_in_minutes.parse().ok()?,
-                starting_value.parse().ok()?,
-                new_ending_value,
-            ));
-        }
-        None
-    }
-    pub fn update_duration_of_effort(&mut self, updated_duration_of_effort: String) {
-        if let EffortState::Editing {
-            starting_value: _,
-            ending_value: _,
-            duration_in_minutes,
-        } = &mut self.gui_state
-        {
-            *duration_in_minutes = updated_duration_of_effort;
-        }
-    }
-    pub fn update_starting_value(&mut self, updated_starting_value: String) {
-        if let EffortState::Editing {
-            starting_value,
-            ending_value: _,
-            duration_in_minutes: _,
-        } = &mut self.gui_state
-        {
-            *starting_value = updated_starting_value;
-        }
-    }
-    pub fn update_ending_value(&mut self, updated_ending_value: String) {
-        if let EffortState::Editing {
-            starting_value: _,
-            ending_value,
-            duration_in_minutes: _,
-        } = &mut self.gui_state
-        {
-            *ending_value = updated_ending_value;
-        }
-    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{effort_can_be_split, is_ramp_effort, Effort};
+    use super::{effort_can_be_split, is_ramp_effort, Effort, Section, SplitRampError};
     mod effort_unit {
-        use super::{effort_can_be_split, is_ramp_effort, Effort};
+        use super::{effort_can_be_split, is_ramp_effort, Effort, Section, SplitRampError};
         use crate::workout_data::workout::{efforts_to_mrc, extract_initial_starting_minutes};
         use crate::workout_data::ToMRC;
 
@@ -186,7 +336,7 @@ mod tests {
         fn test_extract_starting_minutes_from_efforts() {
             assert_eq!(
                 extract_initial_starting_minutes(
-                    &vec![Effort::new(7.0, 100.0, None), Effort::new(9.0, 100.0, None)],
+                    &[Effort::new(7.0, 100.0, None), Effort::new(9.0, 100.0, None)],
                     5.0
                 ),
                 vec![5.0, 12.0]
@@ -197,7 +347,7 @@ mod tests {
         fn test_efforts_to_mrc() {
             assert_eq!(
                 efforts_to_mrc(
-                    &vec![
+                    &[
                         Effort::new(5.0, 100.0, None),
                         Effort::new(10.0, 150.0, None),
                         Effort::new(15.0, 200.0, None),
@@ -224,7 +374,7 @@ mod tests {
         fn ramp_efforts_to_mrc() {
             assert_eq!(
                 efforts_to_mrc(
-                    &vec![
+                    &[
                         Effort::new(5.0, 100.0, None),
                         Effort::new(1.0, 100.0, Some(150.0))
                     ],
@@ -266,14 +416,70 @@ mod tests {
         fn split_effort_into_five_constant_pieces() {
             assert_eq!(
                 Effort::new(1.0, 100.0, Some(150.0)).split_ramp_effort_into_constant_chunks(),
-                vec![
+                Ok(vec![
                     Effort::new(0.2, 105.0, Some(105.0)),
                     Effort::new(0.2, 115.0, Some(115.0)),
                     Effort::new(0.2, 125.0, Some(125.0)),
                     Effort::new(0.2, 135.0, Some(135.0)),
                     Effort::new(0.2, 145.0, Some(145.0))
-                ]
+                ])
+            )
+        }
+        #[test]
+        fn last_piece_holds_the_remainder() {
+            let pieces = Effort::new(1.1, 100.0, Some(210.0))
+                .split_ramp_effort_into_constant_chunks()
+                .unwrap();
+            assert_eq!(pieces.len(), 6);
+            let duration: f64 = pieces.iter().map(|piece| piece.duration_in_minutes).sum();
+            assert!((duration - 1.1).abs() < 1e-9);
+            assert!((pieces[5].duration_in_minutes - 0.1).abs() < 1e-9);
+            assert!((pieces[5].starting_value - 205.0).abs() < 1e-9);
+        }
+        #[test]
+        fn only_ramps_with_duration_are_split() {
+            assert_eq!(
+                Effort::new(1.0, 100.0, None).split_ramp_effort_into_constant_chunks(),
+                Err(SplitRampError::NoRamp)
+            );
+            assert_eq!(
+                Effort::new(-1.0, 100.0, Some(150.0)).split_ramp_effort_into_constant_chunks(),
+                Err(SplitRampError::NonPositiveDuration)
+            );
+        }
+        #[test]
+        fn split_constant_effort() {
+            assert_eq!(
+                Effort::new(10.0, 100.0, None).split_at(4.0),
+                Some((Effort::new(4.0, 100.0, None), Effort::new(6.0, 100.0, None)))
             )
         }
+        #[test]
+        fn split_ramp_effort_interpolates_value() {
+            assert_eq!(
+                Effort::new(10.0, 100.0, Some(200.0)).split_at(5.0),
+                Some((
+                    Effort::new(5.0, 100.0, Some(150.0)),
+                    Effort::new(5.0, 150.0, Some(200.0))
+                ))
+            )
+        }
+        #[test]
+        fn split_keeps_section() {
+            let (first, second) = Effort::new(10.0, 100.0, None)
+                .with_section(Section::Warmup)
+                .split_at(5.0)
+                .unwrap();
+            assert_eq!(first.section(), Section::Warmup);
+            assert_eq!(second.section(), Section::Warmup);
+        }
+        #[test]
+        fn split_outside_of_effort() {
+            let effort = Effort::new(10.0, 100.0, None);
+            assert_eq!(effort.split_at(0.0), None);
+            assert_eq!(effort.split_at(10.0), None);
+            assert_eq!(effort.split_at(-1.0), None);
+            assert_eq!(effort.split_at(f64::NAN), None);
+        }
     }
 }
@@ -135,7 +135,12 @@ fn extract_effort_from_string(effort_as_string: String) -> Result<Effort, Effort
         duration_in_minutes,
         starting_value: start_wattage,
         ending_value: end_wattage,
-        gui_state: super::effort::EffortState::default(),
+        section: super::effort::Section::default(),
+        cadence: None,
+        cue: None,
+        name: None,
+        color: None,
+        target_range: None,
     })
 }
 
@@ -239,7 +244,12 @@ MINUTES WATTS\n\
                     duration_in_minutes: 10.0,
                     starting_value: 80.0,
                     ending_value: 150.0,
-                    gui_state: crate::workout_data::effort::EffortState::default()
+                    section: crate::workout_data::effort::Section::default(),
+                    cadence: None,
+                    cue: None,
+                    name: None,
+                    color: None,
+                    target_range: None,
                 })
             )
         }
@@ -255,7 +265,12 @@ MINUTES WATTS\n\
                     duration_in_minutes: 5.0,
                     starting_value: 300.0,
                     ending_value: 300.0,
-                    gui_state: crate::workout_data::effort::EffortState::default()
+                    section: crate::workout_data::effort::Section::default(),
+                    cadence: None,
+                    cue: None,
+                    name: None,
+                    color: None,
+                    target_range: None,
                 })
             )
         }
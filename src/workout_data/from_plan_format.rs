@@ -60,17 +60,16 @@ fn extract_effort_from_string(effort_as_string: &str) -> Result<Effort, ExtractP
         .captures(effort_as_string)
         .ok_or(ExtractPlanFormatError::InvalidFormat)?;
 
-    let higher_wattage: f64 = caps
+    let lower_wattage: f64 = caps
         .get(1)
         .ok_or(ExtractPlanFormatError::InvalidFormat)?
         .as_str()
         .parse()?;
-    let lower_wattage: f64 = caps
+    let higher_wattage: f64 = caps
         .get(2)
         .ok_or(ExtractPlanFormatError::InvalidFormat)?
         .as_str()
         .parse()?;
-    let average_wattage = (higher_wattage + lower_wattage) / 2.0;
 
     let duration_in_seconds: f64 = caps
         .get(3)
@@ -78,7 +77,23 @@ fn extract_effort_from_string(effort_as_string: &str) -> Result<Effort, ExtractP
         .as_str()
         .parse()?;
     let duration_in_minutes = duration_in_seconds / 60.0;
-    Ok(Effort::new(duration_in_minutes, average_wattage, None))
+    // A range of power is kept as target range, with the trainer set to its middle.
+    let effort = if lower_wattage == higher_wattage {
+        Effort::new(duration_in_minutes, lower_wattage, None)
+    } else {
+        Effort::new(
+            duration_in_minutes,
+            (lower_wattage + higher_wattage) / 2.0,
+            None,
+        )
+        .with_target_range(lower_wattage, higher_wattage)
+    };
+
+    let extract_message = Regex::new(r"(?m)^\s*MESG=(.*)$").expect("This regex is valid.");
+    Ok(match extract_message.captures(effort_as_string) {
+        Some(caps) if !caps[1].trim().is_empty() => effort.with_name(caps[1].trim()),
+        _ => effort,
+    })
 }
 
 #[cfg(test)]
@@ -184,6 +199,33 @@ MESG_DURATION_SEC>=30?EXIT";
                 Ok(Effort::new(0.5, 50.0, None))
             )
         }
+        #[test]
+        fn message_names_effort() {
+            assert_eq!(
+                extract_effort_from_string(
+                    &Effort::new(2.0, 300.0, None)
+                        .with_name("VO2 #3")
+                        .to_plan_format()
+                ),
+                Ok(Effort::new(2.0, 300.0, None).with_name("VO2 #3"))
+            )
+        }
+        #[test]
+        fn power_range_is_target_range() {
+            let effort = extract_effort_from_string(
+                "PWR_LO=176
+PWR_HI=188
+MESG_DURATION_SEC>=600?EXIT",
+            );
+            assert_eq!(
+                effort,
+                Ok(Effort::new(10.0, 182.0, None).with_target_range(176.0, 188.0))
+            );
+            assert!(effort
+                .unwrap()
+                .to_plan_format()
+                .contains("PWR_LO=176\nPWR_HI=188\n"));
+        }
     }
     #[test]
     pub fn extract_workout_simple_case() {
@@ -0,0 +1,185 @@
+//! Workouts as JSON documents, carrying every field of the model:
+//! ```json
+//! {
+//!   "version": 2,
+//!   "name": "Sweet Spot",
+//!   "description": "Three blocks just below threshold.",
+//!   "efforts": [
+//!     {
+//!       "duration_in_minutes": 10.0,
+//!       "starting_value": 100.0,
+//!       "ending_value": 150.0,
+//!       "section": "warmup"
+//!     },
+//!     {
+//!       "duration_in_minutes": 12.0,
+//!       "starting_value": 180.0,
+//!       "ending_value": 180.0,
+//!       "section": "main",
+//!       "cadence": 90,
+//!       "cue": "Stay seated",
+//!       "name": "Sweet Spot #1",
+//!       "color": "#ff8800",
+//!       "target_range": {"low": 176.0, "high": 188.0}
+//!     }
+//!   ]
+//! }
+//! ```
+//! Values are in watts and `section` is one of `warmup`, `main` or `cooldown`.
+//! `name` labels the effort, `color` is written as `#rrggbb` and
+//! `target_range` is the band of watts around the target the rider may stay within.
+//! `description`, `section`, `cadence`, `cue`, `name`, `color` and `target_range` may be left out.
+//! Documents of a newer `version` than `SCHEMA_VERSION` are rejected.
+//! Version 2 added `name`, `color` and `target_range`, documents of version 1 are read as before.
+use crate::workout_data::workout::Workout;
+use serde::{Deserialize, Serialize};
+
+/// Version of the schema written by this crate.
+/// Increase it whenever the schema changes, even by fields older readers would drop.
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(PartialEq, Debug)]
+pub enum ExtractJsonError {
+    InvalidJson(String),
+    MissingVersion,
+    UnsupportedVersion(u32),
+}
+
+#[derive(Serialize)]
+struct WorkoutDocumentRef<'a> {
+    version: u32,
+    #[serde(flatten)]
+    workout: &'a Workout,
+}
+
+#[derive(Deserialize)]
+struct WorkoutDocument {
+    #[serde(flatten)]
+    workout: Workout,
+}
+
+#[derive(Deserialize)]
+struct Version {
+    version: Option<u32>,
+}
+
+pub fn workout_to_json(workout: &Workout) -> String {
+    serde_json::to_string_pretty(&WorkoutDocumentRef {
+        version: SCHEMA_VERSION,
+        workout,
+    })
+    .expect("A workout only contains strings and numbers.")
+}
+
+pub fn extract_workout(json: &str) -> Result<Workout, ExtractJsonError> {
+    let invalid = |error: serde_json::Error| ExtractJsonError::InvalidJson(error.to_string());
+    match serde_json::from_str::<Version>(json)
+        .map_err(invalid)?
+        .version
+    {
+        None => return Err(ExtractJsonError::MissingVersion),
+        Some(version) if version > SCHEMA_VERSION => {
+            return Err(ExtractJsonError::UnsupportedVersion(version))
+        }
+        Some(_) => (),
+    }
+    let document: WorkoutDocument = serde_json::from_str(json).map_err(invalid)?;
+    Ok(document.workout)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workout_data::effort::Effort;
+    use crate::workout_data::test_workout;
+
+    #[test]
+    fn round_trip_keeps_every_field() {
+        assert_eq!(
+            extract_workout(&workout_to_json(&test_workout())),
+            Ok(test_workout())
+        );
+    }
+    #[test]
+    fn plan_round_trip_through_json() {
+        let workout = Workout::new(
+            "Sweet Spot",
+            "",
+            vec![
+                Effort::new(12.0, 182.0, None)
+                    .with_name("Sweet Spot #1")
+                    .with_color("#ff8800".parse().unwrap())
+                    .with_target_range(176.0, 188.0),
+                Effort::new(5.0, 100.0, None).with_name("Recover"),
+            ],
+        );
+        let plan = extract_workout(&workout_to_json(&workout))
+            .unwrap()
+            .to_plan_format();
+        assert_eq!(plan, workout.to_plan_format());
+
+        let from_plan = Workout::from_plan_format(&plan).unwrap();
+        assert_eq!(
+            from_plan.efforts()[0],
+            Effort::new(12.0, 182.0, None)
+                .with_name("Sweet Spot #1")
+                .with_target_range(176.0, 188.0)
+        );
+        let from_json = extract_workout(&workout_to_json(&from_plan)).unwrap();
+        assert_eq!(from_json, from_plan);
+        assert_eq!(from_json.to_plan_format(), plan);
+    }
+    #[test]
+    fn optional_fields_can_be_left_out() {
+        let json = r#"{"version": 1, "name": "Short", "efforts": [
+            {"duration_in_minutes": 1.0, "starting_value": 200.0, "ending_value": 250.0}
+        ]}"#;
+        assert_eq!(
+            extract_workout(json),
+            Ok(Workout::new(
+                "Short",
+                "",
+                vec![Effort::new(1.0, 200.0, Some(250.0))]
+            ))
+        );
+    }
+    #[test]
+    fn documents_of_version_1_are_read() {
+        let json = r#"{"version": 1, "name": "Tempo", "description": "", "efforts": [
+            {"duration_in_minutes": 20.0, "starting_value": 200.0, "ending_value": 200.0,
+             "section": "main", "cadence": 90, "cue": "Stay seated"}
+        ]}"#;
+        assert_eq!(
+            extract_workout(json),
+            Ok(Workout::new(
+                "Tempo",
+                "",
+                vec![Effort::new(20.0, 200.0, None)
+                    .with_cadence(90)
+                    .with_cue("Stay seated")]
+            ))
+        );
+    }
+    #[test]
+    fn version_is_written() {
+        assert!(workout_to_json(&test_workout()).contains("\"version\": 2"));
+    }
+    #[test]
+    fn version_is_checked() {
+        assert_eq!(
+            extract_workout(r#"{"name": "", "efforts": []}"#),
+            Err(ExtractJsonError::MissingVersion)
+        );
+        assert_eq!(
+            extract_workout(r#"{"version": 3, "name": "", "efforts": []}"#),
+            Err(ExtractJsonError::UnsupportedVersion(3))
+        );
+    }
+    #[test]
+    fn invalid_json() {
+        assert!(matches!(
+            extract_workout("{"),
+            Err(ExtractJsonError::InvalidJson(_))
+        ));
+    }
+}
@@ -7,6 +7,25 @@ pub mod effort;
 pub mod from_mrc;
 pub mod from_plan_format;
 
+/// Plain text workouts as written in training platforms, e.g. `- 5m 105%`.
+pub mod text_format;
+
+/// Lossless JSON representation of workouts.
+pub mod json;
+
+/// Zwift workout files.
+#[cfg(feature = "zwo")]
+pub mod zwo;
+
+/// Copying workouts as text and recognising the format of pasted text.
+pub mod clipboard;
+
+/// Checks to run on a workout before it is used.
+pub mod validation;
+
+/// Riding a workout in real time.
+pub mod player;
+
 pub trait ToMRC {
     fn to_mrc(&self) -> String;
 }
@@ -17,6 +36,28 @@ impl ToMRC for f64 {
     }
 }
 
+/// A workout using every property of efforts, shared by the tests.
+#[cfg(test)]
+pub(crate) fn test_workout() -> workout::Workout {
+    use effort::{Effort, Section};
+    workout::Workout::new(
+        "Over & <Under>",
+        "Threshold \"blocks\"",
+        vec![
+            Effort::new(10.0, 100.0, Some(200.0)).with_section(Section::Warmup),
+            Effort::new(5.0, 300.0, None)
+                .with_cadence(95)
+                .with_cue("Go hard")
+                .with_name("VO2 <#1>"),
+            Effort::new(4.0, 182.0, None)
+                .with_target_range(176.0, 188.0)
+                .with_color("#ff8800".parse().unwrap()),
+            Effort::new(2.0, 150.0, Some(250.0)),
+            Effort::new(6.0, 150.0, Some(100.0)).with_section(Section::Cooldown),
+        ],
+    )
+}
+
 #[cfg(test)]
 mod test {
     use super::ToMRC;
@@ -0,0 +1,317 @@
+use crate::workout_data::effort::Effort;
+use crate::workout_data::workout::{extract_initial_starting_minutes, Workout};
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+#[cfg(target_arch = "wasm32")]
+use wasm_timer::Instant;
+
+/// Source of the current time for the player,
+/// measured from an arbitrary but fixed point.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+/// The wall clock.
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A clock that only moves when told to, e.g. in tests.
+#[derive(Default)]
+pub struct ManualClock {
+    now: std::cell::Cell<Duration>,
+}
+
+impl ManualClock {
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for &ManualClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayerState {
+    Ready,
+    Running,
+    Paused,
+    Finished,
+}
+
+/// Plays a workout in real time.
+/// Skipping and extending intervals only changes the session,
+/// not the workout that is played.
+pub struct WorkoutPlayer<C: Clock> {
+    clock: C,
+    planned_efforts: Vec<Effort>,
+    /// Durations of the efforts in this session, changed by extending.
+    durations_in_minutes: Vec<f64>,
+    /// Time played before the last start or resume.
+    played_before_resume: Duration,
+    running_since: Option<Duration>,
+    started: bool,
+}
+
+impl<C: Clock> WorkoutPlayer<C> {
+    pub fn new(workout: &Workout, clock: C) -> Self {
+        Self {
+            clock,
+            planned_efforts: workout.efforts().to_vec(),
+            durations_in_minutes: workout
+                .efforts()
+                .iter()
+                .map(|effort| effort.duration_in_minutes)
+                .collect(),
+            played_before_resume: Duration::ZERO,
+            running_since: None,
+            started: false,
+        }
+    }
+
+    pub fn state(&self) -> PlayerState {
+        if self.elapsed_minutes() >= self.session_duration_in_minutes() && self.started {
+            PlayerState::Finished
+        } else if self.running_since.is_some() {
+            PlayerState::Running
+        } else if self.started {
+            PlayerState::Paused
+        } else {
+            PlayerState::Ready
+        }
+    }
+    /// Start or resume playing.
+    pub fn play(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(self.clock.now());
+            self.started = true;
+        }
+    }
+    pub fn pause(&mut self) {
+        if let Some(running_since) = self.running_since.take() {
+            self.played_before_resume += self.clock.now().saturating_sub(running_since);
+        }
+    }
+    pub fn toggle_pause(&mut self) {
+        match self.state() {
+            PlayerState::Running => self.pause(),
+            PlayerState::Ready | PlayerState::Paused => self.play(),
+            PlayerState::Finished => {}
+        }
+    }
+    /// Jump to the start of the next interval.
+    pub fn skip(&mut self) {
+        if let Some(index) = self.current_index() {
+            let start_of_next: f64 = self.durations_in_minutes[..=index].iter().sum();
+            self.set_elapsed_minutes(start_of_next);
+        }
+    }
+    /// Make the current interval last longer.
+    pub fn extend(&mut self, by: Duration) {
+        if let Some(index) = self.current_index() {
+            self.durations_in_minutes[index] += by.as_secs_f64() / 60.0;
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.played_before_resume
+            + self.running_since.map_or(Duration::ZERO, |running_since| {
+                self.clock.now().saturating_sub(running_since)
+            })
+    }
+    fn elapsed_minutes(&self) -> f64 {
+        self.elapsed().as_secs_f64() / 60.0
+    }
+    fn set_elapsed_minutes(&mut self, minutes: f64) {
+        self.played_before_resume = Duration::from_secs_f64(minutes * 60.0);
+        if self.running_since.is_some() {
+            self.running_since = Some(self.clock.now());
+        }
+    }
+    fn session_duration_in_minutes(&self) -> f64 {
+        self.durations_in_minutes.iter().sum()
+    }
+    fn session_starting_minutes(&self) -> Vec<f64> {
+        self.durations_in_minutes
+            .iter()
+            .scan(0.0, |starting_minute, duration| {
+                let current_starting_minute = *starting_minute;
+                *starting_minute += duration;
+                Some(current_starting_minute)
+            })
+            .collect()
+    }
+
+    /// Index of the effort played right now.
+    pub fn current_index(&self) -> Option<usize> {
+        let elapsed_minutes = self.elapsed_minutes();
+        self.session_starting_minutes()
+            .iter()
+            .zip(&self.durations_in_minutes)
+            .position(|(starting_minute, duration)| elapsed_minutes < starting_minute + duration)
+    }
+    pub fn current_effort(&self) -> Option<&Effort> {
+        self.current_index()
+            .map(|index| &self.planned_efforts[index])
+    }
+    pub fn next_effort(&self) -> Option<&Effort> {
+        self.current_index()
+            .and_then(|index| self.planned_efforts.get(index + 1))
+    }
+    pub fn current_cue(&self) -> Option<&str> {
+        self.current_effort().and_then(|effort| effort.cue())
+    }
+    /// Minutes played of the current effort, counted in the planned workout.
+    /// Time added by extending is spent at the end of a ramp.
+    fn minutes_into_current_effort(&self, index: usize) -> f64 {
+        let played = self.elapsed_minutes() - self.session_starting_minutes()[index];
+        played.min(self.planned_efforts[index].duration_in_minutes)
+    }
+    /// The wattage to ride right now.
+    pub fn current_target(&self) -> Option<f64> {
+        let index = self.current_index()?;
+        let effort = &self.planned_efforts[index];
+        // Time played of an effort without duration was added by extending it, spent at its end.
+        let progress = if effort.duration_in_minutes > 0.0 {
+            self.minutes_into_current_effort(index) / effort.duration_in_minutes
+        } else {
+            1.0
+        };
+        Some(effort.starting_value + (effort.ending_value - effort.starting_value) * progress)
+    }
+    pub fn time_left_in_interval(&self) -> Option<Duration> {
+        let index = self.current_index()?;
+        let end_of_interval =
+            self.session_starting_minutes()[index] + self.durations_in_minutes[index];
+        Some(Duration::from_secs_f64(
+            (end_of_interval - self.elapsed_minutes()).max(0.0) * 60.0,
+        ))
+    }
+    /// Where in the planned workout the session is, e.g. to draw a cursor.
+    pub fn planned_minute(&self) -> f64 {
+        match self.current_index() {
+            Some(index) => {
+                extract_initial_starting_minutes(&self.planned_efforts, 0.0)[index]
+                    + self.minutes_into_current_effort(index)
+            }
+            None => self
+                .planned_efforts
+                .iter()
+                .map(|effort| effort.duration_in_minutes)
+                .sum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workout_data::test_workout;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn player_starts_ready() {
+        let clock = ManualClock::default();
+        let player = WorkoutPlayer::new(&test_workout(), &clock);
+        assert_eq!(player.state(), PlayerState::Ready);
+        assert_eq!(player.elapsed(), Duration::ZERO);
+    }
+    #[test]
+    fn time_only_passes_while_running() {
+        let clock = ManualClock::default();
+        let mut player = WorkoutPlayer::new(&test_workout(), &clock);
+        clock.advance(MINUTE);
+        player.play();
+        clock.advance(2 * MINUTE);
+        player.pause();
+        clock.advance(MINUTE);
+
+        assert_eq!(player.state(), PlayerState::Paused);
+        assert_eq!(player.elapsed(), 2 * MINUTE);
+    }
+    #[test]
+    fn target_of_ramp_is_interpolated() {
+        let clock = ManualClock::default();
+        let mut player = WorkoutPlayer::new(&test_workout(), &clock);
+        player.play();
+        clock.advance(5 * MINUTE);
+
+        assert_eq!(player.current_index(), Some(0));
+        assert_eq!(player.current_target(), Some(150.0));
+        assert_eq!(player.time_left_in_interval(), Some(5 * MINUTE));
+        assert_eq!(
+            player.next_effort().and_then(|effort| effort.cue()),
+            Some("Go hard")
+        );
+    }
+    #[test]
+    fn target_of_extended_effort_without_duration() {
+        let clock = ManualClock::default();
+        let workout = Workout::new("", "", vec![Effort::new(0.0, 100.0, Some(200.0))]);
+        let mut player = WorkoutPlayer::new(&workout, &clock);
+        player.durations_in_minutes[0] = 1.0;
+        player.play();
+        clock.advance(MINUTE / 2);
+
+        assert_eq!(player.current_index(), Some(0));
+        assert_eq!(player.current_target(), Some(200.0));
+    }
+    #[test]
+    fn skip_to_next_interval() {
+        let clock = ManualClock::default();
+        let mut player = WorkoutPlayer::new(&test_workout(), &clock);
+        player.play();
+        clock.advance(MINUTE);
+        player.skip();
+
+        assert_eq!(player.current_index(), Some(1));
+        assert_eq!(player.current_cue(), Some("Go hard"));
+        assert_eq!(player.elapsed(), 10 * MINUTE);
+        clock.advance(MINUTE);
+        assert_eq!(player.elapsed(), 11 * MINUTE);
+    }
+    #[test]
+    fn extend_interval() {
+        let clock = ManualClock::default();
+        let mut player = WorkoutPlayer::new(&test_workout(), &clock);
+        player.play();
+        clock.advance(12 * MINUTE);
+        player.extend(2 * MINUTE);
+        clock.advance(4 * MINUTE);
+
+        assert_eq!(player.current_index(), Some(1));
+        assert_eq!(player.time_left_in_interval(), Some(MINUTE));
+        assert_eq!(player.planned_minute(), 15.0);
+    }
+    #[test]
+    fn finish_after_last_interval() {
+        let clock = ManualClock::default();
+        let mut player = WorkoutPlayer::new(&test_workout(), &clock);
+        player.play();
+        clock.advance(28 * MINUTE);
+
+        assert_eq!(player.state(), PlayerState::Finished);
+        assert_eq!(player.current_target(), None);
+        assert_eq!(player.planned_minute(), 27.0);
+    }
+}
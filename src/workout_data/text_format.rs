@@ -0,0 +1,574 @@
+use crate::workout_data::effort::{is_ramp_effort, Effort, Section};
+use crate::workout_data::workout::Workout;
+use regex::Regex;
+
+/// Percent of the lactate threshold heart rate and the percent of FTP
+/// riding at that heart rate roughly corresponds to, following the usual training zones.
+const HEART_RATE_TO_POWER: [(f64, f64); 6] = [
+    (0.0, 0.0),
+    (68.0, 55.0),
+    (83.0, 75.0),
+    (94.0, 90.0),
+    (105.0, 105.0),
+    (120.0, 130.0),
+];
+/// Most times the steps of a header may be repeated, keeping pasted text from
+/// creating more efforts than the designer can handle.
+const MAXIMAL_NUMBER_OF_REPEATS: usize = 100;
+
+#[derive(PartialEq, Debug)]
+pub enum ExtractTextFormatError {
+    InvalidStep(String),
+    MissingDuration(String),
+    MissingTarget(String),
+    MissingFtp,
+    MissingLthr,
+    NoEfforts,
+    /// A header repeating its steps more than `MAXIMAL_NUMBER_OF_REPEATS` times.
+    TooManyRepeats(String),
+}
+
+/// Thresholds to turn relative targets into wattages.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Thresholds {
+    pub ftp: Option<f64>,
+    /// Lactate threshold heart rate, needed for targets in beats per minute.
+    pub lthr: Option<f64>,
+}
+
+/// A target as written in the text, before it is turned into watts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Target {
+    Watts(f64),
+    PercentOfFtp(f64),
+    PercentOfLthr(f64),
+    BeatsPerMinute(f64),
+}
+
+impl Target {
+    fn to_watts(self, thresholds: &Thresholds) -> Result<f64, ExtractTextFormatError> {
+        let ftp = thresholds.ftp.ok_or(ExtractTextFormatError::MissingFtp);
+        match self {
+            Target::Watts(watts) => Ok(watts),
+            Target::PercentOfFtp(percent) => Ok(ftp? * percent / 100.0),
+            Target::PercentOfLthr(percent) => Ok(ftp? * heart_rate_to_power(percent) / 100.0),
+            Target::BeatsPerMinute(beats) => {
+                let lthr = thresholds.lthr.ok_or(ExtractTextFormatError::MissingLthr)?;
+                Target::PercentOfLthr(beats / lthr * 100.0).to_watts(thresholds)
+            }
+        }
+    }
+}
+
+/// Interpolate between the zones of `HEART_RATE_TO_POWER`, continuing the last one above it.
+fn heart_rate_to_power(percent_of_lthr: f64) -> f64 {
+    let upper = HEART_RATE_TO_POWER
+        .iter()
+        .position(|(heart_rate, _)| *heart_rate >= percent_of_lthr)
+        .unwrap_or(HEART_RATE_TO_POWER.len() - 1)
+        .max(1);
+    let (lower_heart_rate, lower_power) = HEART_RATE_TO_POWER[upper - 1];
+    let (upper_heart_rate, upper_power) = HEART_RATE_TO_POWER[upper];
+    lower_power
+        + (percent_of_lthr - lower_heart_rate) * (upper_power - lower_power)
+            / (upper_heart_rate - lower_heart_rate)
+}
+
+/// Read a workout written as plain text steps, e.g.
+/// ```text
+/// Warmup
+/// - 10m ramp 50-75%
+///
+/// Main Set 3x
+/// - 5m 105% 95rpm
+/// - Spin easy 3m 55%
+/// ```
+/// Lines that do not start with `-` are headers. A header ending in `Nx` repeats
+/// the steps up to the next empty line or header. Headers mentioning warm up or
+/// cool down put their steps into that section.
+/// Targets are given in percent of FTP, watts (`200W`), percent of the
+/// lactate threshold heart rate (`75% HR`) or beats per minute (`140bpm`).
+/// A range of two targets, like `88-94%`, is a target range ridden at its middle.
+/// After `ramp`, the range is ridden as a ramp from the first target to the second,
+/// and a second range, like in `ramp 50-75% 45-80%`, becomes its target range.
+pub fn extract_workout(
+    text: &str,
+    thresholds: &Thresholds,
+) -> Result<Workout, ExtractTextFormatError> {
+    let capture_repeats = Regex::new(r"(?i)(\d+)\s*x\s*$").expect("This regex is valid.");
+    let mut efforts = Vec::new();
+    let mut block = Vec::new();
+    let mut repeats = 1;
+    let mut section = Section::Main;
+
+    for line in text.lines().map(str::trim) {
+        if let Some(step) = line.strip_prefix('-') {
+            block.push(extract_effort(step, thresholds)?.with_section(section));
+            continue;
+        }
+        for _ in 0..repeats {
+            efforts.extend(block.iter().cloned());
+        }
+        block.clear();
+        repeats = 1;
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(captures) = capture_repeats.captures(line) {
+            repeats = captures[1]
+                .parse()
+                .ok()
+                .filter(|repeats| *repeats <= MAXIMAL_NUMBER_OF_REPEATS)
+                .ok_or(ExtractTextFormatError::TooManyRepeats(String::from(line)))?;
+        }
+        // A header of only the repeats stays in the current section.
+        let header = capture_repeats
+            .replace(line, "")
+            .to_lowercase()
+            .replace([' ', '-'], "");
+        if header.contains("warmup") {
+            section = Section::Warmup;
+        } else if header.contains("cooldown") {
+            section = Section::Cooldown;
+        } else if !header.is_empty() {
+            section = Section::Main;
+        }
+    }
+    for _ in 0..repeats {
+        efforts.extend(block.iter().cloned());
+    }
+
+    if efforts.is_empty() {
+        return Err(ExtractTextFormatError::NoEfforts);
+    }
+    Ok(Workout::new("", "", efforts))
+}
+
+fn extract_effort(step: &str, thresholds: &Thresholds) -> Result<Effort, ExtractTextFormatError> {
+    let capture_duration = Regex::new(
+        r"^(?:(\d+(?:\.\d+)?)h)?(?:(\d+(?:\.\d+)?)(?:m|min))?(?:(\d+(?:\.\d+)?)(?:s|sec))?$",
+    )
+    .expect("This regex is valid.");
+    let capture_target = Regex::new(r"(?i)^(\d+(?:\.\d+)?)(?:-(\d+(?:\.\d+)?))?(%|w|bpm)$")
+        .expect("This regex is valid.");
+    let capture_cadence = Regex::new(r"(?i)^(\d+)rpm$").expect("This regex is valid.");
+
+    let mut duration_in_minutes = None;
+    let mut targets = None;
+    let mut target_range = None;
+    let mut cadence = None;
+    let mut is_ramp = false;
+    let mut cue = Vec::new();
+    let mut tokens = step.split_whitespace().peekable();
+    while let Some(token) = tokens.next() {
+        if token.eq_ignore_ascii_case("ramp") {
+            is_ramp = true;
+            continue;
+        }
+        if token.eq_ignore_ascii_case("ftp") {
+            continue;
+        }
+        if let Some(captures) = capture_duration.captures(token) {
+            let part = |index: usize| -> f64 {
+                captures
+                    .get(index)
+                    .and_then(|part| part.as_str().parse().ok())
+                    .unwrap_or(0.0)
+            };
+            duration_in_minutes = Some(part(1) * 60.0 + part(2) + part(3) / 60.0);
+        } else if let Some(captures) = capture_target.captures(token) {
+            let is_heart_rate = tokens
+                .peek()
+                .is_some_and(|next| ["hr", "lthr"].contains(&next.to_lowercase().as_str()));
+            if is_heart_rate {
+                tokens.next();
+            }
+            let target = |value: f64| match captures[3].to_lowercase().as_str() {
+                "%" if is_heart_rate => Target::PercentOfLthr(value),
+                "%" => Target::PercentOfFtp(value),
+                "bpm" => Target::BeatsPerMinute(value),
+                _ => Target::Watts(value),
+            };
+            let parse = |value: &str| {
+                value
+                    .parse::<f64>()
+                    .map_err(|_| ExtractTextFormatError::InvalidStep(String::from(step)))
+            };
+            let starting = target(parse(&captures[1])?);
+            let ending = match captures.get(2) {
+                Some(ending) => target(parse(ending.as_str())?),
+                None => starting,
+            };
+            if targets.is_none() {
+                targets = Some((starting, ending));
+            } else if is_ramp && target_range.is_none() && captures.get(2).is_some() {
+                target_range = Some((starting, ending));
+            } else {
+                return Err(ExtractTextFormatError::InvalidStep(String::from(step)));
+            }
+        } else if let Some(captures) = capture_cadence.captures(token) {
+            cadence = captures[1].parse().ok();
+        } else if duration_in_minutes.is_none() && targets.is_none() {
+            cue.push(token);
+        } else {
+            return Err(ExtractTextFormatError::InvalidStep(String::from(step)));
+        }
+    }
+
+    let duration_in_minutes = duration_in_minutes
+        .filter(|duration| *duration > 0.0)
+        .ok_or(ExtractTextFormatError::MissingDuration(String::from(step)))?;
+    let (starting, ending) =
+        targets.ok_or(ExtractTextFormatError::MissingTarget(String::from(step)))?;
+    let (starting, ending) = (
+        starting.to_watts(thresholds)?.round(),
+        ending.to_watts(thresholds)?.round(),
+    );
+    let mut effort = if is_ramp || starting == ending {
+        let effort = Effort::new(duration_in_minutes, starting, Some(ending));
+        match target_range {
+            Some((low, high)) => effort.with_target_range(
+                low.to_watts(thresholds)?.round(),
+                high.to_watts(thresholds)?.round(),
+            ),
+            None => effort,
+        }
+    } else {
+        Effort::new(duration_in_minutes, (starting + ending) / 2.0, None)
+            .with_target_range(starting, ending)
+    };
+    if let Some(cadence) = cadence {
+        effort = effort.with_cadence(cadence);
+    }
+    if !cue.is_empty() {
+        effort = effort.with_cue(&cue.join(" "));
+    }
+    Ok(effort)
+}
+
+/// Write the workout as plain text steps, one block per section.
+/// Repeated groups of efforts are written once with the number of repeats.
+/// With an `ftp`, targets are written in percent of it, otherwise in watts.
+pub fn workout_to_text(workout: &Workout, ftp: Option<f64>) -> String {
+    [
+        (Section::Warmup, "Warmup"),
+        (Section::Main, "Main Set"),
+        (Section::Cooldown, "Cooldown"),
+    ]
+    .into_iter()
+    .filter_map(|(section, header)| {
+        let efforts = workout.section(section);
+        if efforts.is_empty() {
+            return None;
+        }
+        let blocks: Vec<String> = find_repeats(&efforts)
+            .into_iter()
+            .map(|(block, repeats)| {
+                let steps: Vec<String> = block
+                    .iter()
+                    .map(|effort| effort_to_text(effort, ftp))
+                    .collect();
+                if repeats > 1 {
+                    format!("{}x\n{}", repeats, steps.join("\n"))
+                } else {
+                    steps.join("\n")
+                }
+            })
+            .collect();
+        Some(format!("{}\n{}", header, blocks.join("\n\n")))
+    })
+    .collect::<Vec<_>>()
+    .join("\n\n")
+}
+
+/// Split efforts into blocks, each together with how often it is repeated in a row.
+/// At every position the block covering the most efforts is taken.
+fn find_repeats(efforts: &[Effort]) -> Vec<(&[Effort], usize)> {
+    let mut blocks: Vec<(&[Effort], usize)> = Vec::new();
+    let mut start = 0;
+    while start < efforts.len() {
+        let remaining = &efforts[start..];
+        let (length, repeats) = (1..=remaining.len() / 2)
+            .map(|length| {
+                let block = &remaining[..length];
+                let repeats = remaining
+                    .chunks(length)
+                    .take_while(|chunk| *chunk == block)
+                    .count();
+                (length, repeats)
+            })
+            .filter(|(_, repeats)| *repeats > 1)
+            .max_by_key(|(length, repeats)| (length * repeats, usize::MAX - length))
+            .unwrap_or((1, 1));
+
+        // Single efforts in a row are joined into one block without repeats.
+        match blocks.last_mut() {
+            Some((block, 1)) if repeats == 1 => {
+                *block = &efforts[start - block.len()..start + 1];
+            }
+            _ => blocks.push((&remaining[..length], repeats)),
+        }
+        start += length * repeats;
+    }
+    blocks
+}
+
+fn effort_to_text(effort: &Effort, ftp: Option<f64>) -> String {
+    let format_value = |watts: f64| match ftp {
+        Some(ftp) => format_number(watts / ftp * 100.0),
+        None => format_number(watts),
+    };
+    let unit = if ftp.is_some() { "%" } else { "W" };
+    let format_range =
+        |low: f64, high: f64| format!("{}-{}{}", format_value(low), format_value(high), unit);
+    let target_range = effort
+        .target_range()
+        .map(|range| format_range(range.low, range.high));
+    let target = if is_ramp_effort(effort) {
+        let ramp = format_range(effort.starting_value(), effort.ending_value());
+        match target_range {
+            Some(target_range) => format!("ramp {} {}", ramp, target_range),
+            None => format!("ramp {}", ramp),
+        }
+    } else {
+        target_range.unwrap_or_else(|| format!("{}{}", format_value(effort.starting_value()), unit))
+    };
+
+    let mut step = vec![String::from("-")];
+    if let Some(cue) = effort.cue() {
+        step.push(String::from(cue));
+    }
+    step.push(format_duration(effort.duration_in_minutes()));
+    step.push(target);
+    if let Some(cadence) = effort.cadence() {
+        step.push(format!("{}rpm", cadence));
+    }
+    step.join(" ")
+}
+
+/// At most one decimal, without trailing zeros.
+fn format_number(value: f64) -> String {
+    let rounded = (value * 10.0).round() / 10.0;
+    format!("{}", rounded)
+}
+
+fn format_duration(minutes: f64) -> String {
+    let seconds = (minutes * 60.0).round() as u64;
+    match (seconds / 3600, seconds % 3600 / 60, seconds % 60) {
+        (0, 0, seconds) => format!("{}s", seconds),
+        (0, minutes, 0) => format!("{}m", minutes),
+        (0, minutes, seconds) => format!("{}m{}s", minutes, seconds),
+        (hours, 0, 0) => format!("{}h", hours),
+        (hours, minutes, 0) => format!("{}h{}m", hours, minutes),
+        (hours, minutes, seconds) => format!("{}h{}m{}s", hours, minutes, seconds),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const FTP: Thresholds = Thresholds {
+        ftp: Some(200.0),
+        lthr: Some(160.0),
+    };
+
+    fn values(workout: &Workout) -> Vec<(f64, f64, f64)> {
+        workout
+            .efforts()
+            .iter()
+            .map(|effort| {
+                (
+                    effort.duration_in_minutes(),
+                    effort.starting_value(),
+                    effort.ending_value(),
+                )
+            })
+            .collect()
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn steps_with_repeats_and_ramps() {
+            let workout = extract_workout(
+                "Warmup\n- 10m ramp 50-75%\n\nMain Set 3x\n- 5m 105%\n- 3m 55%\n\nCooldown\n- 10m ramp 75-50%",
+                &FTP,
+            )
+            .unwrap();
+            assert_eq!(workout.efforts().len(), 8);
+            assert_eq!(values(&workout)[0], (10.0, 100.0, 150.0));
+            assert_eq!(values(&workout)[5], (5.0, 210.0, 210.0));
+            assert_eq!(values(&workout)[7], (10.0, 150.0, 100.0));
+            assert_eq!(workout.efforts()[0].section(), Section::Warmup);
+            assert_eq!(workout.efforts()[1].section(), Section::Main);
+            assert_eq!(workout.efforts()[6].section(), Section::Main);
+            assert_eq!(workout.efforts()[7].section(), Section::Cooldown);
+        }
+        #[test]
+        fn durations() {
+            let workout =
+                extract_workout("- 1h 100W\n- 1m30s 100W\n- 45s 100W\n- 2min 100W", &FTP).unwrap();
+            let durations: Vec<f64> = values(&workout).iter().map(|value| value.0).collect();
+            assert_eq!(durations, vec![60.0, 1.5, 0.75, 2.0]);
+        }
+        #[test]
+        fn targets_in_watts_and_heart_rate() {
+            let workout = extract_workout(
+                "- 5m ramp 150-250w\n- 5m 83% HR\n- 5m 160bpm\n- 5m 95% FTP",
+                &FTP,
+            )
+            .unwrap();
+            assert_eq!(
+                values(&workout),
+                vec![
+                    (5.0, 150.0, 250.0),
+                    (5.0, 150.0, 150.0),
+                    (5.0, 196.0, 196.0),
+                    (5.0, 190.0, 190.0)
+                ]
+            );
+        }
+        #[test]
+        fn range_without_ramp_is_target_range() {
+            let workout = extract_workout("- 10m 88-94%", &FTP).unwrap();
+            assert_eq!(
+                workout.efforts(),
+                &[Effort::new(10.0, 182.0, None).with_target_range(176.0, 188.0)]
+            );
+        }
+        #[test]
+        fn ramp_with_target_range() {
+            let workout = extract_workout("- 10m ramp 50-75% 45-80%", &FTP).unwrap();
+            assert_eq!(
+                workout.efforts(),
+                &[Effort::new(10.0, 100.0, Some(150.0)).with_target_range(90.0, 160.0)]
+            );
+        }
+        #[test]
+        fn second_target_without_ramp() {
+            assert_eq!(
+                extract_workout("- 10m 88-94% 90%", &FTP),
+                Err(ExtractTextFormatError::InvalidStep(String::from(
+                    " 10m 88-94% 90%"
+                )))
+            );
+        }
+        #[test]
+        fn repeats_are_limited() {
+            assert_eq!(
+                extract_workout("99999999x\n- 1m 100W", &FTP),
+                Err(ExtractTextFormatError::TooManyRepeats(String::from(
+                    "99999999x"
+                )))
+            );
+            assert_eq!(
+                extract_workout("100x\n- 1m 100W", &FTP)
+                    .unwrap()
+                    .efforts()
+                    .len(),
+                100
+            );
+        }
+        #[test]
+        fn cue_and_cadence() {
+            let workout = extract_workout("- Stay seated 5m 105% 95rpm", &FTP).unwrap();
+            assert_eq!(workout.efforts()[0].cue(), Some("Stay seated"));
+            assert_eq!(workout.efforts()[0].cadence(), Some(95));
+        }
+        #[test]
+        fn percent_needs_ftp() {
+            assert_eq!(
+                extract_workout("- 5m 105%", &Thresholds::default()),
+                Err(ExtractTextFormatError::MissingFtp)
+            );
+            assert!(extract_workout("- 5m 250W", &Thresholds::default()).is_ok());
+        }
+        #[test]
+        fn beats_per_minute_need_lthr() {
+            assert_eq!(
+                extract_workout(
+                    "- 5m 140bpm",
+                    &Thresholds {
+                        ftp: Some(200.0),
+                        lthr: None
+                    }
+                ),
+                Err(ExtractTextFormatError::MissingLthr)
+            );
+        }
+        #[test]
+        fn step_without_target() {
+            assert_eq!(
+                extract_workout("- 5m", &FTP),
+                Err(ExtractTextFormatError::MissingTarget(String::from(" 5m")))
+            );
+        }
+        #[test]
+        fn text_without_steps() {
+            assert_eq!(
+                extract_workout("Just some notes", &FTP),
+                Err(ExtractTextFormatError::NoEfforts)
+            );
+        }
+    }
+
+    mod print {
+        use super::*;
+
+        #[test]
+        fn repeats_are_found() {
+            let efforts = vec![
+                Effort::new(10.0, 100.0, Some(150.0)),
+                Effort::new(5.0, 210.0, None),
+                Effort::new(3.0, 110.0, None),
+                Effort::new(5.0, 210.0, None),
+                Effort::new(3.0, 110.0, None),
+                Effort::new(10.0, 100.0, None),
+            ];
+            let blocks: Vec<(usize, usize)> = find_repeats(&efforts)
+                .into_iter()
+                .map(|(block, repeats)| (block.len(), repeats))
+                .collect();
+            assert_eq!(blocks, vec![(1, 1), (2, 2), (1, 1)]);
+        }
+        #[test]
+        fn single_efforts_are_grouped() {
+            let efforts = vec![
+                Effort::new(1.0, 100.0, None),
+                Effort::new(2.0, 100.0, None),
+                Effort::new(3.0, 100.0, None),
+            ];
+            assert_eq!(find_repeats(&efforts), vec![(&efforts[..], 1)]);
+        }
+        #[test]
+        fn effort_in_percent() {
+            assert_eq!(
+                effort_to_text(
+                    &Effort::new(1.5, 100.0, Some(150.0)).with_cadence(90),
+                    Some(200.0)
+                ),
+                "- 1m30s ramp 50-75% 90rpm"
+            );
+            assert_eq!(
+                effort_to_text(&Effort::new(60.0, 215.0, None).with_cue("Hold"), None),
+                "- Hold 1h 215W"
+            );
+        }
+        #[test]
+        fn round_trip() {
+            let text = "Warmup\n- 10m ramp 50-75% 45-80%\n\nMain Set\n3x\n- 5m 105%\n- 3m 55%\n\n- 1m 50%\n- 10m 88-94%\n\nCooldown\n- 10m ramp 75-50%";
+            let workout = extract_workout(text, &FTP).unwrap();
+            assert_eq!(workout_to_text(&workout, FTP.ftp), text);
+        }
+    }
+
+    #[test]
+    fn heart_rate_zones() {
+        assert_eq!(heart_rate_to_power(83.0), 75.0);
+        assert_eq!(heart_rate_to_power(34.0), 27.5);
+        assert_eq!(heart_rate_to_power(135.0), 155.0);
+    }
+}
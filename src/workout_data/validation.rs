@@ -0,0 +1,218 @@
+use crate::workout_data::effort::Effort;
+use crate::workout_data::workout::Workout;
+use serde::{Deserialize, Serialize};
+
+/// How severe a violated rule is.
+/// Errors prevent a workout from being exported, warnings do not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// The individual checks run against a workout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    NonPositiveDuration,
+    WattageOutOfRange,
+    WorkoutTooLong,
+    NoEfforts,
+}
+
+/// A violated rule, optionally pointing to the offending effort.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub rule: Rule,
+    pub severity: Severity,
+    pub effort_index: Option<usize>,
+    pub message: String,
+}
+
+/// Configuration of the rules to check.
+/// A rule with severity `None` is not checked at all.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValidationRules {
+    pub non_positive_duration: Option<Severity>,
+    pub wattage_out_of_range: Option<Severity>,
+    pub max_wattage: f64,
+    pub workout_too_long: Option<Severity>,
+    pub max_duration_in_minutes: f64,
+    pub no_efforts: Option<Severity>,
+}
+
+impl Default for ValidationRules {
+    fn default() -> Self {
+        Self {
+            non_positive_duration: Some(Severity::Error),
+            wattage_out_of_range: Some(Severity::Warning),
+            max_wattage: 1500.0,
+            workout_too_long: Some(Severity::Warning),
+            max_duration_in_minutes: 300.0,
+            no_efforts: Some(Severity::Error),
+        }
+    }
+}
+
+impl ValidationRules {
+    /// Check the workout against all enabled rules.
+    pub fn validate(&self, workout: &Workout) -> Vec<Finding> {
+        let mut findings: Vec<Finding> = workout
+            .efforts()
+            .iter()
+            .enumerate()
+            .flat_map(|(effort_index, effort)| self.validate_effort(effort_index, effort))
+            .collect();
+
+        if let Some(severity) = self.no_efforts {
+            if workout.efforts().is_empty() {
+                findings.push(Finding {
+                    rule: Rule::NoEfforts,
+                    severity,
+                    effort_index: None,
+                    message: String::from("The workout has no efforts."),
+                });
+            }
+        }
+        if let Some(severity) = self.workout_too_long {
+            let duration = workout.total_time_of_workout();
+            if duration > self.max_duration_in_minutes {
+                findings.push(Finding {
+                    rule: Rule::WorkoutTooLong,
+                    severity,
+                    effort_index: None,
+                    message: format!(
+                        "The workout takes {:.0} minutes, more than {:.0}.",
+                        duration, self.max_duration_in_minutes
+                    ),
+                });
+            }
+        }
+        findings
+    }
+
+    fn validate_effort(&self, effort_index: usize, effort: &Effort) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        if let Some(severity) = self.non_positive_duration {
+            if effort.duration_in_minutes.is_nan() || effort.duration_in_minutes <= 0.0 {
+                findings.push(Finding {
+                    rule: Rule::NonPositiveDuration,
+                    severity,
+                    effort_index: Some(effort_index),
+                    message: String::from("Duration must be positive."),
+                });
+            }
+        }
+        if let Some(severity) = self.wattage_out_of_range {
+            if [effort.starting_value, effort.ending_value]
+                .iter()
+                .any(|&value| !(0.0..=self.max_wattage).contains(&value))
+            {
+                findings.push(Finding {
+                    rule: Rule::WattageOutOfRange,
+                    severity,
+                    effort_index: Some(effort_index),
+                    message: format!("Wattage must be between 0 and {:.0}.", self.max_wattage),
+                });
+            }
+        }
+        findings
+    }
+}
+
+/// Whether any of the findings prevents an export.
+pub fn contains_errors(findings: &[Finding]) -> bool {
+    findings
+        .iter()
+        .any(|finding| finding.severity == Severity::Error)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn rules_of(findings: Vec<Finding>) -> Vec<(Rule, Severity, Option<usize>)> {
+        findings
+            .into_iter()
+            .map(|finding| (finding.rule, finding.severity, finding.effort_index))
+            .collect()
+    }
+
+    #[test]
+    fn valid_workout() {
+        let workout = Workout::new("", "", vec![Effort::new(10.0, 200.0, None)]);
+        assert_eq!(ValidationRules::default().validate(&workout), vec![]);
+    }
+    #[test]
+    fn empty_workout() {
+        assert_eq!(
+            rules_of(ValidationRules::default().validate(&Workout::empty("", ""))),
+            vec![(Rule::NoEfforts, Severity::Error, None)]
+        );
+    }
+    #[test]
+    fn zero_and_negative_durations() {
+        let workout = Workout::new(
+            "",
+            "",
+            vec![
+                Effort::new(0.0, 200.0, None),
+                Effort::new(5.0, 200.0, None),
+                Effort::new(-1.0, 200.0, None),
+                Effort::new(f64::NAN, 200.0, None),
+            ],
+        );
+        assert_eq!(
+            rules_of(ValidationRules::default().validate(&workout)),
+            vec![
+                (Rule::NonPositiveDuration, Severity::Error, Some(0)),
+                (Rule::NonPositiveDuration, Severity::Error, Some(2)),
+                (Rule::NonPositiveDuration, Severity::Error, Some(3)),
+            ]
+        );
+    }
+    #[test]
+    fn unrealistic_wattage() {
+        let workout = Workout::new("", "", vec![Effort::new(1.0, 100.0, Some(2000.0))]);
+        assert_eq!(
+            rules_of(ValidationRules::default().validate(&workout)),
+            vec![(Rule::WattageOutOfRange, Severity::Warning, Some(0))]
+        );
+    }
+    #[test]
+    fn six_hour_workout() {
+        let workout = Workout::new("", "", vec![Effort::new(360.0, 150.0, None)]);
+        assert_eq!(
+            rules_of(ValidationRules::default().validate(&workout)),
+            vec![(Rule::WorkoutTooLong, Severity::Warning, None)]
+        );
+    }
+    #[test]
+    fn rules_can_be_configured() {
+        let rules = ValidationRules {
+            no_efforts: None,
+            wattage_out_of_range: Some(Severity::Error),
+            max_wattage: 300.0,
+            ..ValidationRules::default()
+        };
+        assert_eq!(rules.validate(&Workout::empty("", "")), vec![]);
+        assert_eq!(
+            rules_of(rules.validate(&Workout::new("", "", vec![Effort::new(1.0, 400.0, None)]))),
+            vec![(Rule::WattageOutOfRange, Severity::Error, Some(0))]
+        );
+    }
+    #[test]
+    fn only_errors_block() {
+        let warning_only = ValidationRules::default().validate(&Workout::new(
+            "",
+            "",
+            vec![Effort::new(1.0, 2000.0, None)],
+        ));
+        assert!(!contains_errors(&warning_only));
+        assert!(contains_errors(
+            &ValidationRules::default().validate(&Workout::empty("", ""))
+        ));
+    }
+}
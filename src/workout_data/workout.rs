@@ -1,11 +1,40 @@
-use crate::workout_data::effort::{effort_can_be_split, is_ramp_effort, Effort};
-use crate::workout_data::{from_mrc, from_plan_format};
+use crate::workout_data::effort::{effort_can_be_split, Effort, Section};
+use crate::workout_data::{from_mrc, from_plan_format, json, text_format};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
 
 #[derive(PartialEq, Debug)]
 pub enum ExtractWorkoutError {
     Description(from_mrc::ExtractDescriptionError),
     Efforts(from_mrc::ExtractEffortError),
     FromPlanFormatError,
+    FromTextFormat(text_format::ExtractTextFormatError),
+    FromJson(json::ExtractJsonError),
+    #[cfg(feature = "zwo")]
+    FromZwo(super::zwo::ExtractZwoError),
+    #[cfg(feature = "fit")]
+    FromFit(crate::activity::ImportError),
+    /// No workouts are read from files of this extension.
+    UnsupportedFormat(String),
+    NotText,
+    Io(String),
+}
+
+#[derive(PartialEq, Debug)]
+pub enum WriteWorkoutError {
+    /// No workouts are written to files of this extension.
+    UnsupportedFormat(String),
+    MissingFtp,
+    #[cfg(feature = "fit")]
+    ToFit(crate::activity::fit::ExportFitError),
+}
+
+#[cfg(feature = "fit")]
+impl From<crate::activity::fit::ExportFitError> for WriteWorkoutError {
+    fn from(value: crate::activity::fit::ExportFitError) -> Self {
+        Self::ToFit(value)
+    }
 }
 impl From<from_mrc::ExtractDescriptionError> for ExtractWorkoutError {
     fn from(value: from_mrc::ExtractDescriptionError) -> Self {
@@ -23,14 +52,41 @@ impl From<from_plan_format::ExtractPlanFormatError> for ExtractWorkoutError {
     }
 }
 
+impl From<text_format::ExtractTextFormatError> for ExtractWorkoutError {
+    fn from(value: text_format::ExtractTextFormatError) -> Self {
+        Self::FromTextFormat(value)
+    }
+}
+
+impl From<json::ExtractJsonError> for ExtractWorkoutError {
+    fn from(value: json::ExtractJsonError) -> Self {
+        Self::FromJson(value)
+    }
+}
+
+#[cfg(feature = "zwo")]
+impl From<super::zwo::ExtractZwoError> for ExtractWorkoutError {
+    fn from(value: super::zwo::ExtractZwoError) -> Self {
+        Self::FromZwo(value)
+    }
+}
+
+#[cfg(feature = "fit")]
+impl From<crate::activity::ImportError> for ExtractWorkoutError {
+    fn from(value: crate::activity::ImportError) -> Self {
+        Self::FromFit(value)
+    }
+}
+
 /// A planed workout.
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Workout {
     /// Name of the workout.
     /// The full name of the file will be <name>.mrc.
     name: String,
     /// Description of the workout.
     /// Will be in the `.mrc`-file
+    #[serde(default)]
     description: String,
     /// The individual efforst of the Workout.
     pub(crate) efforts: Vec<Effort>,
@@ -54,6 +110,8 @@ impl Workout {
         format!("{}\n{}", self.mrc_head(), self.mrc_body())
     }
 
+    /// Generate the plan file of a workout. It keeps names of efforts as messages and
+    /// target ranges as range of power, but neither colors, cues, cadences nor ramps.
     pub fn to_plan_format(&self) -> String {
         format!("{}\n{}", self.plan_format_head(), self.plan_format_body())
     }
@@ -114,31 +172,15 @@ impl Workout {
     }
     /// Add a new effort to the workout.
     pub fn add_effort(&mut self, effort: Effort) {
-        if is_ramp_effort(&effort) && effort_can_be_split(&effort) {
-            for effort in effort.split_ramp_effort_into_constant_chunks() {
-                self.efforts.push(effort)
-            }
-            return;
+        match effort.split_ramp_effort_into_constant_chunks() {
+            Ok(chunks) if effort_can_be_split(&effort) => self.efforts.extend(chunks),
+            _ => self.efforts.push(effort),
         }
-        self.efforts.push(effort);
     }
     /// Remove an effort from a workout.
     pub fn remove(&mut self, index: usize) {
         self.efforts.remove(index);
     }
-    /// Make an effort editable in the gui.
-    pub fn to_edit(&mut self, index: usize) {
-        self.efforts[index].to_edit()
-    }
-    /// Make an effort editable in the gui.
-    pub fn to_idle(&mut self, index: usize) {
-        if let Some(new_effort) = self.efforts[index].to_idle() {
-            self.efforts[index] = new_effort;
-        }
-    }
-    pub fn update_duration_of_effort(&mut self, index: usize, updated_duration_in_minutes: String) {
-        self.efforts[index].update_duration_of_effort(updated_duration_in_minutes);
-    }
     pub fn total_time_of_workout(&self) -> f64 {
         self.efforts
             .iter()
@@ -171,12 +213,230 @@ impl Workout {
             efforts,
         })
     }
+    /// Read a plan file, where messages become names of efforts
+    /// and ranges of power target ranges around their middle.
     pub fn from_plan_format(workout_in_plan_format: &str) -> Result<Self, ExtractWorkoutError> {
         Ok(from_plan_format::extract_workout(workout_in_plan_format)?)
     }
+    /// Read a workout from plain text steps like `- 10m ramp 50-75%`.
+    /// `thresholds` turn targets relative to FTP or heart rate into watts.
+    pub fn from_text_format(
+        text: &str,
+        thresholds: &text_format::Thresholds,
+    ) -> Result<Self, ExtractWorkoutError> {
+        Ok(text_format::extract_workout(text, thresholds)?)
+    }
+    /// Write the workout as plain text steps, in percent of `ftp` if given.
+    pub fn to_text_format(&self, ftp: Option<f64>) -> String {
+        text_format::workout_to_text(self, ftp)
+    }
+    /// Read a workout from the versioned JSON schema.
+    pub fn from_json(json: &str) -> Result<Self, ExtractWorkoutError> {
+        Ok(json::extract_workout(json)?)
+    }
+    /// Write the workout in the current version of the JSON schema.
+    pub fn to_json(&self) -> String {
+        json::workout_to_json(self)
+    }
+    /// Read a Zwift workout, whose targets are fractions of `ftp`.
+    #[cfg(feature = "zwo")]
+    pub fn from_zwo(zwo: &str, ftp: f64) -> Result<Self, ExtractWorkoutError> {
+        Ok(super::zwo::extract_workout(zwo, ftp)?)
+    }
+    /// Write the workout as Zwift workout, with targets as fractions of `ftp`.
+    #[cfg(feature = "zwo")]
+    pub fn to_zwo(&self, ftp: f64) -> String {
+        super::zwo::workout_to_zwo(self, ftp)
+    }
+    /// Read a FIT workout file, whose targets may be given relative to `ftp`.
+    #[cfg(feature = "fit")]
+    pub fn from_fit(fit: &[u8], ftp: Option<f64>) -> Result<Self, ExtractWorkoutError> {
+        Ok(crate::activity::fit::fit_to_workout(fit, ftp)?)
+    }
+    /// Write the workout as FIT workout file.
+    #[cfg(feature = "fit")]
+    pub fn to_fit(&self) -> Result<Vec<u8>, WriteWorkoutError> {
+        Ok(crate::activity::fit::workout_to_fit(self)?)
+    }
+    /// Read a workout from the contents of a file in the format belonging to `extension`.
+    /// `ftp` turns targets of ZWO and FIT files given relative to it into watts.
+    #[cfg_attr(not(any(feature = "zwo", feature = "fit")), allow(unused_variables))]
+    pub fn from_file(
+        extension: Option<&str>,
+        contents: &[u8],
+        ftp: Option<f64>,
+    ) -> Result<Self, ExtractWorkoutError> {
+        #[cfg(feature = "fit")]
+        if extension == Some("fit") {
+            return Self::from_fit(contents, ftp);
+        }
+        let text = std::str::from_utf8(contents).map_err(|_| ExtractWorkoutError::NotText)?;
+        match extension {
+            Some("mrc") => Self::from_mrc(text),
+            Some("plan") => Self::from_plan_format(text),
+            Some("json") => Self::from_json(text),
+            #[cfg(feature = "zwo")]
+            Some("zwo") => Self::from_zwo(
+                text,
+                ftp.ok_or(text_format::ExtractTextFormatError::MissingFtp)?,
+            ),
+            _ => Err(ExtractWorkoutError::UnsupportedFormat(String::from(
+                extension.unwrap_or_default(),
+            ))),
+        }
+    }
+    /// Read the workout file at `path`, in the format belonging to its extension.
+    pub fn from_path(path: &Path, ftp: Option<f64>) -> Result<Self, ExtractWorkoutError> {
+        let contents =
+            std::fs::read(path).map_err(|error| ExtractWorkoutError::Io(error.to_string()))?;
+        Self::from_file(path.extension().and_then(|e| e.to_str()), &contents, ftp)
+    }
+    /// Write the workout in the format belonging to `extension`,
+    /// where ZWO files need the `ftp` their targets are relative to.
+    #[cfg_attr(not(feature = "zwo"), allow(unused_variables))]
+    pub fn to_file(&self, extension: &str, ftp: Option<f64>) -> Result<Vec<u8>, WriteWorkoutError> {
+        match extension {
+            "mrc" => Ok(self.to_mrc().into_bytes()),
+            "plan" => Ok(self.to_plan_format().into_bytes()),
+            "json" => Ok(self.to_json().into_bytes()),
+            #[cfg(feature = "zwo")]
+            "zwo" => Ok(self
+                .to_zwo(ftp.ok_or(WriteWorkoutError::MissingFtp)?)
+                .into_bytes()),
+            #[cfg(feature = "fit")]
+            "fit" => self.to_fit(),
+            _ => Err(WriteWorkoutError::UnsupportedFormat(String::from(
+                extension,
+            ))),
+        }
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    /// The individual efforts of the workout.
+    pub fn efforts(&self) -> &[Effort] {
+        &self.efforts
+    }
+    /// Append all efforts of `other` to the end of this workout.
+    pub fn append(&mut self, other: &Workout) {
+        self.efforts.extend(other.efforts.iter().cloned());
+    }
+    /// Create a new workout consisting of this workout followed by `other`.
+    /// Name and description are taken from this workout.
+    pub fn concatenate(&self, other: &Workout) -> Workout {
+        let mut concatenated = self.clone();
+        concatenated.append(other);
+        concatenated
+    }
+    /// Split the workout at `minute` into two workouts.
+    /// An effort spanning the splitting point is split as well.
+    pub fn split_at(&self, minute: f64) -> (Workout, Workout) {
+        let mut efforts_before = Vec::new();
+        let mut efforts_after = Vec::new();
+
+        for (effort, starting_minute) in self
+            .efforts
+            .iter()
+            .zip(extract_initial_starting_minutes(&self.efforts, 0.0))
+        {
+            let ending_minute = starting_minute + effort.duration_in_minutes;
+            if ending_minute <= minute {
+                efforts_before.push(effort.clone());
+            } else if starting_minute >= minute {
+                efforts_after.push(effort.clone());
+            } else if let Some((first_part, second_part)) =
+                effort.split_at(minute - starting_minute)
+            {
+                efforts_before.push(first_part);
+                efforts_after.push(second_part);
+            } else {
+                // Rounding left the splitting point at the end of the effort.
+                efforts_before.push(effort.clone());
+            }
+        }
+
+        (
+            Workout::new(&self.name, &self.description, efforts_before),
+            Workout::new(&self.name, &self.description, efforts_after),
+        )
+    }
+    /// All efforts belonging to `section`.
+    pub fn section(&self, section: Section) -> Vec<Effort> {
+        self.efforts
+            .iter()
+            .filter(|effort| effort.section == section)
+            .cloned()
+            .collect()
+    }
+    /// Replace the efforts of `section` by `efforts`.
+    /// The new efforts are inserted where the section started. If the workout
+    /// has no such section yet, warmups go to the front, cooldowns to the end
+    /// and main efforts behind the warmup.
+    pub fn replace_section(&mut self, section: Section, efforts: Vec<Effort>) {
+        let insertion_index = self
+            .efforts
+            .iter()
+            .position(|effort| effort.section == section)
+            .unwrap_or_else(|| self.default_position_of_section(section));
+        self.efforts.retain(|effort| effort.section != section);
+
+        let efforts_to_insert = efforts
+            .into_iter()
+            .map(|effort| effort.with_section(section));
+        self.efforts
+            .splice(insertion_index..insertion_index, efforts_to_insert);
+    }
+    /// Exchange the efforts of `section` between this workout and `other`.
+    pub fn swap_section(&mut self, other: &mut Workout, section: Section) {
+        let own_efforts = self.section(section);
+        self.replace_section(section, other.section(section));
+        other.replace_section(section, own_efforts);
+    }
+    /// Move the efforts at `indices` one place earlier and return where they ended up.
+    /// An effort at the start stays there, as do the selected ones right behind it.
+    pub fn move_efforts_earlier(&mut self, indices: &BTreeSet<usize>) -> BTreeSet<usize> {
+        let mut moved = BTreeSet::new();
+        for &index in indices.range(..self.efforts.len()) {
+            if index > 0 && !moved.contains(&(index - 1)) {
+                self.efforts.swap(index - 1, index);
+                moved.insert(index - 1);
+            } else {
+                moved.insert(index);
+            }
+        }
+        moved
+    }
+    /// Move the efforts at `indices` one place later and return where they ended up.
+    /// An effort at the end stays there, as do the selected ones right before it.
+    pub fn move_efforts_later(&mut self, indices: &BTreeSet<usize>) -> BTreeSet<usize> {
+        let mut moved = BTreeSet::new();
+        for &index in indices.range(..self.efforts.len()).rev() {
+            if index + 1 < self.efforts.len() && !moved.contains(&(index + 1)) {
+                self.efforts.swap(index, index + 1);
+                moved.insert(index + 1);
+            } else {
+                moved.insert(index);
+            }
+        }
+        moved
+    }
+    fn default_position_of_section(&self, section: Section) -> usize {
+        match section {
+            Section::Warmup => 0,
+            Section::Main => self
+                .efforts
+                .iter()
+                .rposition(|effort| effort.section == Section::Warmup)
+                .map_or(0, |index| index + 1),
+            Section::Cooldown => self.efforts.len(),
+        }
+    }
 }
 
-pub fn efforts_to_mrc(efforts: &Vec<Effort>, starting_minute: f64) -> (String, f64) {
+pub fn efforts_to_mrc(efforts: &[Effort], starting_minute: f64) -> (String, f64) {
     let starting_minutes = extract_initial_starting_minutes(efforts, starting_minute);
     let effort_string_with_final_minute = efforts
         .iter()
@@ -197,7 +457,7 @@ pub fn efforts_to_mrc(efforts: &Vec<Effort>, starting_minute: f64) -> (String, f
     )
 }
 
-pub fn extract_initial_starting_minutes(efforts: &Vec<Effort>, starting_minute: f64) -> Vec<f64> {
+pub fn extract_initial_starting_minutes(efforts: &[Effort], starting_minute: f64) -> Vec<f64> {
     let mut starting_times = Vec::new();
     let mut current_starting_time = starting_minute;
 
@@ -316,6 +576,167 @@ mod test {
             assert_eq!(workout.average_intensity(), 200.0);
         }
     }
+    mod combine {
+        use super::*;
+
+        fn warmup_main_cooldown() -> Workout {
+            Workout::new(
+                "test_workout",
+                "test-1",
+                vec![
+                    Effort::new(10.0, 100.0, None).with_section(Section::Warmup),
+                    Effort::new(5.0, 300.0, None),
+                    Effort::new(5.0, 150.0, None).with_section(Section::Cooldown),
+                ],
+            )
+        }
+
+        #[test]
+        fn append_workout() {
+            let mut workout = Workout::new("first", "", vec![Effort::new(5.0, 80.0, None)]);
+            workout.append(&Workout::new(
+                "second",
+                "",
+                vec![Effort::new(10.0, 200.0, None)],
+            ));
+            assert_eq!(
+                workout,
+                Workout::new(
+                    "first",
+                    "",
+                    vec![Effort::new(5.0, 80.0, None), Effort::new(10.0, 200.0, None)]
+                )
+            )
+        }
+        #[test]
+        fn concatenate_keeps_original_workouts() {
+            let first = Workout::new("first", "", vec![Effort::new(5.0, 80.0, None)]);
+            let second = Workout::new("second", "", vec![Effort::new(10.0, 200.0, None)]);
+
+            let concatenated = first.concatenate(&second);
+
+            assert_eq!(concatenated.total_time_of_workout(), 15.0);
+            assert_eq!(first.efforts().len(), 1);
+        }
+        #[test]
+        fn split_between_efforts() {
+            let (before, after) = warmup_main_cooldown().split_at(10.0);
+            assert_eq!(
+                before.efforts,
+                vec![warmup_main_cooldown().efforts[0].clone()]
+            );
+            assert_eq!(after.efforts, warmup_main_cooldown().efforts[1..].to_vec());
+        }
+        #[test]
+        fn split_inside_effort() {
+            let (before, after) =
+                Workout::new("", "", vec![Effort::new(10.0, 100.0, Some(200.0))]).split_at(2.5);
+            assert_eq!(before.efforts, vec![Effort::new(2.5, 100.0, Some(125.0))]);
+            assert_eq!(after.efforts, vec![Effort::new(7.5, 125.0, Some(200.0))]);
+        }
+        #[test]
+        fn split_after_end() {
+            let (before, after) = warmup_main_cooldown().split_at(100.0);
+            assert_eq!(before, warmup_main_cooldown());
+            assert!(after.efforts.is_empty());
+        }
+        #[test]
+        fn extract_section() {
+            assert_eq!(
+                warmup_main_cooldown().section(Section::Main),
+                vec![Effort::new(5.0, 300.0, None)]
+            )
+        }
+        #[test]
+        fn replace_existing_section() {
+            let mut workout = warmup_main_cooldown();
+            workout.replace_section(
+                Section::Main,
+                vec![Effort::new(1.0, 400.0, None), Effort::new(1.0, 100.0, None)],
+            );
+            assert_eq!(
+                workout.efforts,
+                vec![
+                    Effort::new(10.0, 100.0, None).with_section(Section::Warmup),
+                    Effort::new(1.0, 400.0, None),
+                    Effort::new(1.0, 100.0, None),
+                    Effort::new(5.0, 150.0, None).with_section(Section::Cooldown),
+                ]
+            )
+        }
+        #[test]
+        fn add_missing_warmup_to_front() {
+            let mut workout = Workout::new("", "", vec![Effort::new(5.0, 300.0, None)]);
+            workout.replace_section(Section::Warmup, vec![Effort::new(10.0, 100.0, None)]);
+            assert_eq!(
+                workout.efforts,
+                vec![
+                    Effort::new(10.0, 100.0, None).with_section(Section::Warmup),
+                    Effort::new(5.0, 300.0, None),
+                ]
+            )
+        }
+        #[test]
+        fn swap_cooldowns() {
+            let mut first = warmup_main_cooldown();
+            let mut second = Workout::new(
+                "",
+                "",
+                vec![
+                    Effort::new(20.0, 250.0, None),
+                    Effort::new(15.0, 90.0, None).with_section(Section::Cooldown),
+                ],
+            );
+
+            first.swap_section(&mut second, Section::Cooldown);
+
+            assert_eq!(
+                first.section(Section::Cooldown),
+                vec![Effort::new(15.0, 90.0, None).with_section(Section::Cooldown)]
+            );
+            assert_eq!(
+                second.efforts,
+                vec![
+                    Effort::new(20.0, 250.0, None),
+                    Effort::new(5.0, 150.0, None).with_section(Section::Cooldown),
+                ]
+            );
+        }
+    }
+    mod reorder {
+        use super::*;
+        use crate::workout_data::test_workout;
+
+        fn durations(workout: &Workout) -> Vec<f64> {
+            workout
+                .efforts()
+                .iter()
+                .map(|effort| effort.duration_in_minutes)
+                .collect()
+        }
+
+        #[test]
+        fn move_efforts_earlier() {
+            let mut workout = test_workout();
+            let moved = workout.move_efforts_earlier(&BTreeSet::from([1, 2]));
+            assert_eq!(moved, BTreeSet::from([0, 1]));
+            assert_eq!(durations(&workout), vec![5.0, 4.0, 10.0, 2.0, 6.0]);
+        }
+        #[test]
+        fn efforts_at_the_start_stay() {
+            let mut workout = test_workout();
+            let moved = workout.move_efforts_earlier(&BTreeSet::from([0, 1]));
+            assert_eq!(moved, BTreeSet::from([0, 1]));
+            assert_eq!(durations(&workout), vec![10.0, 5.0, 4.0, 2.0, 6.0]);
+        }
+        #[test]
+        fn move_efforts_later() {
+            let mut workout = test_workout();
+            let moved = workout.move_efforts_later(&BTreeSet::from([0, 4]));
+            assert_eq!(moved, BTreeSet::from([1, 4]));
+            assert_eq!(durations(&workout), vec![5.0, 10.0, 4.0, 2.0, 6.0]);
+        }
+    }
     mod from_mrc {
         use super::*;
 
@@ -417,6 +838,65 @@ MINUTES WATTS
             assert_eq!(workout, reserialized_workout)
         }
     }
+    mod files {
+        use super::*;
+        use crate::workout_data::test_workout;
+
+        #[test]
+        fn round_trip_by_extension() {
+            let workout = test_workout();
+            for extension in ["plan", "json"] {
+                let contents = workout.to_file(extension, None).unwrap();
+                let read = Workout::from_file(Some(extension), &contents, None).unwrap();
+                assert_eq!(read.to_file(extension, None), Ok(contents));
+            }
+        }
+        #[test]
+        fn unsupported_format() {
+            assert_eq!(
+                Workout::from_file(Some("docx"), b"", None),
+                Err(ExtractWorkoutError::UnsupportedFormat(String::from("docx")))
+            );
+            assert_eq!(
+                Workout::from_file(None, b"", None),
+                Err(ExtractWorkoutError::UnsupportedFormat(String::new()))
+            );
+            assert_eq!(
+                test_workout().to_file("docx", None),
+                Err(WriteWorkoutError::UnsupportedFormat(String::from("docx")))
+            );
+        }
+        #[test]
+        fn text_formats_need_text() {
+            assert_eq!(
+                Workout::from_file(Some("mrc"), &[0xff], None),
+                Err(ExtractWorkoutError::NotText)
+            );
+        }
+        #[cfg(feature = "zwo")]
+        #[test]
+        fn zwift_workouts_need_ftp() {
+            assert_eq!(
+                test_workout().to_file("zwo", None),
+                Err(WriteWorkoutError::MissingFtp)
+            );
+            let zwo = test_workout().to_file("zwo", Some(200.0)).unwrap();
+            assert_eq!(
+                Workout::from_file(Some("zwo"), &zwo, None),
+                Err(ExtractWorkoutError::FromTextFormat(
+                    text_format::ExtractTextFormatError::MissingFtp
+                ))
+            );
+            assert!(Workout::from_file(Some("zwo"), &zwo, Some(200.0)).is_ok());
+        }
+        #[test]
+        fn missing_file() {
+            assert!(matches!(
+                Workout::from_path(Path::new("/nonexistent/workout.plan"), None),
+                Err(ExtractWorkoutError::Io(_))
+            ));
+        }
+    }
     mod to_plan_format {
         use super::super::{Effort, Workout};
 
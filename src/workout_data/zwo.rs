@@ -0,0 +1,331 @@
+use crate::workout_data::effort::{Effort, Section};
+use crate::workout_data::workout::Workout;
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+#[derive(PartialEq, Debug)]
+pub enum ExtractZwoError {
+    NoWorkout,
+    MissingAttribute(String),
+    InvalidAttribute(String),
+    /// Elements without a target power, like `FreeRide`.
+    UnsupportedElement(String),
+}
+
+/// Write the workout as Zwift workout file. ZWO targets are fractions of the FTP,
+/// so every value is divided by `ftp`. Ramps in the warmup and cooldown
+/// become `Warmup` and `Cooldown` elements.
+pub fn workout_to_zwo(workout: &Workout, ftp: f64) -> String {
+    let efforts: String = workout
+        .efforts()
+        .iter()
+        .map(|effort| effort_to_zwo(effort, ftp))
+        .collect();
+    format!(
+        "<workout_file>
+    <author></author>
+    <name>{}</name>
+    <description>{}</description>
+    <sportType>bike</sportType>
+    <workout>
+{}    </workout>
+</workout_file>
+",
+        escape(workout.name()),
+        escape(workout.description()),
+        efforts
+    )
+}
+
+fn effort_to_zwo(effort: &Effort, ftp: f64) -> String {
+    let duration = (effort.duration_in_minutes() * 60.0).round() as u64;
+    let (name, power) = if effort.starting_value() == effort.ending_value() {
+        (
+            "SteadyState",
+            format!("Power=\"{:.3}\"", effort.starting_value() / ftp),
+        )
+    } else {
+        let name = match effort.section() {
+            Section::Warmup => "Warmup",
+            Section::Main => "Ramp",
+            Section::Cooldown => "Cooldown",
+        };
+        (
+            name,
+            format!(
+                "PowerLow=\"{:.3}\" PowerHigh=\"{:.3}\"",
+                effort.starting_value() / ftp,
+                effort.ending_value() / ftp
+            ),
+        )
+    };
+    let mut element = format!("        <{} Duration=\"{}\" {}", name, duration, power);
+    if let Some(cadence) = effort.cadence() {
+        let _ = write!(element, " Cadence=\"{}\"", cadence);
+    }
+    if let Some(effort_name) = effort.name() {
+        let _ = write!(element, " Name=\"{}\"", escape(effort_name));
+    }
+    match effort.cue() {
+        Some(cue) => {
+            let _ = write!(
+                element,
+                ">\n            <textevent timeoffset=\"0\" message=\"{}\"/>\n        </{}>\n",
+                escape(cue),
+                name
+            );
+        }
+        None => element.push_str("/>\n"),
+    }
+    element
+}
+
+/// Read a Zwift workout file, turning fractions of the FTP into watts with `ftp`.
+/// `IntervalsT` are expanded into their on and off efforts.
+/// The first text event of an element becomes the cue of its effort,
+/// its `Name` attribute the name of all of its efforts.
+pub fn extract_workout(zwo: &str, ftp: f64) -> Result<Workout, ExtractZwoError> {
+    let capture_workout = Regex::new(r"(?s)<workout>(.*)</workout>").expect("This regex is valid.");
+    let capture_element =
+        Regex::new(r"<(Warmup|Cooldown|SteadyState|Ramp|IntervalsT|FreeRide|MaxEffort)\b([^>]*)>")
+            .expect("This regex is valid.");
+    let capture_cue =
+        Regex::new(r#"<textevent\b[^>]*\bmessage="([^"]*)""#).expect("This regex is valid.");
+
+    let body = &capture_workout
+        .captures(zwo)
+        .ok_or(ExtractZwoError::NoWorkout)?[1];
+    let elements: Vec<_> = capture_element.captures_iter(body).collect();
+    let mut efforts = Vec::new();
+    for (index, element) in elements.iter().enumerate() {
+        let name = &element[1];
+        let attributes = Attributes::new(&element[2]);
+        let content_start = element.get(0).expect("The whole match exists.").end();
+        let content_end = elements
+            .get(index + 1)
+            .and_then(|next| next.get(0))
+            .map_or(body.len(), |next| next.start());
+        let content = &body[content_start..content_end];
+        let cue = capture_cue
+            .captures(content)
+            .map(|captures| unescape(&captures[1]));
+
+        let duration_in_minutes = attributes.number("Duration").map(|seconds| seconds / 60.0);
+        let watts = |name: &str| attributes.number(name).map(|power| (power * ftp).round());
+        let mut element_efforts = match name {
+            "SteadyState" => vec![Effort::new(duration_in_minutes?, watts("Power")?, None)],
+            "Warmup" | "Cooldown" | "Ramp" => {
+                let section = match name {
+                    "Warmup" => Section::Warmup,
+                    "Cooldown" => Section::Cooldown,
+                    _ => Section::Main,
+                };
+                vec![Effort::new(
+                    duration_in_minutes?,
+                    watts("PowerLow")?,
+                    Some(watts("PowerHigh")?),
+                )
+                .with_section(section)]
+            }
+            "IntervalsT" => {
+                let repeats = attributes.number("Repeat")? as usize;
+                let on = Effort::new(
+                    attributes.number("OnDuration")? / 60.0,
+                    watts("OnPower")?,
+                    None,
+                );
+                let off = Effort::new(
+                    attributes.number("OffDuration")? / 60.0,
+                    watts("OffPower")?,
+                    None,
+                );
+                let off = match attributes.number("CadenceResting") {
+                    Ok(cadence) => off.with_cadence(cadence as u32),
+                    Err(_) => off,
+                };
+                [on, off]
+                    .iter()
+                    .cycle()
+                    .take(2 * repeats)
+                    .cloned()
+                    .collect()
+            }
+            _ => return Err(ExtractZwoError::UnsupportedElement(String::from(name))),
+        };
+        if let Ok(cadence) = attributes.number("Cadence") {
+            // For intervals, `Cadence` is the cadence of the on efforts.
+            let step = if name == "IntervalsT" { 2 } else { 1 };
+            for effort in element_efforts.iter_mut().step_by(step) {
+                *effort = effort.clone().with_cadence(cadence as u32);
+            }
+        }
+        if let Some(effort_name) = attributes.text("Name") {
+            for effort in element_efforts.iter_mut() {
+                *effort = effort.clone().with_name(&effort_name);
+            }
+        }
+        if let (Some(cue), Some(first)) = (cue, element_efforts.first_mut()) {
+            *first = first.clone().with_cue(&cue);
+        }
+        efforts.extend(element_efforts);
+    }
+
+    let capture_text = |tag: &str| {
+        Regex::new(&format!(r"(?s)<{tag}>(.*?)</{tag}>"))
+            .expect("This regex is valid.")
+            .captures(zwo)
+            .map_or(String::new(), |captures| unescape(captures[1].trim()))
+    };
+    Ok(Workout::new(
+        &capture_text("name"),
+        &capture_text("description"),
+        efforts,
+    ))
+}
+
+struct Attributes(HashMap<String, String>);
+
+impl Attributes {
+    fn new(attributes: &str) -> Self {
+        let capture_attribute =
+            Regex::new(r#"(\w+)\s*=\s*"([^"]*)""#).expect("This regex is valid.");
+        Self(
+            capture_attribute
+                .captures_iter(attributes)
+                .map(|captures| (captures[1].to_lowercase(), String::from(&captures[2])))
+                .collect(),
+        )
+    }
+    fn text(&self, name: &str) -> Option<String> {
+        self.0
+            .get(&name.to_lowercase())
+            .map(|text| unescape(text))
+            .filter(|text| !text.is_empty())
+    }
+    fn number(&self, name: &str) -> Result<f64, ExtractZwoError> {
+        self.0
+            .get(&name.to_lowercase())
+            .ok_or(ExtractZwoError::MissingAttribute(String::from(name)))?
+            .trim()
+            .parse()
+            .map_err(|_| ExtractZwoError::InvalidAttribute(String::from(name)))
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::workout_data::test_workout;
+
+    #[test]
+    fn steady_state_with_cue() {
+        assert_eq!(
+            effort_to_zwo(
+                &Effort::new(5.0, 210.0, None)
+                    .with_cadence(95)
+                    .with_cue("Stay \"seated\""),
+                200.0
+            ),
+            "        <SteadyState Duration=\"300\" Power=\"1.050\" Cadence=\"95\">
+            <textevent timeoffset=\"0\" message=\"Stay &quot;seated&quot;\"/>
+        </SteadyState>\n"
+        );
+    }
+    #[test]
+    fn ramps_by_section() {
+        let zwo = workout_to_zwo(&test_workout(), 200.0);
+        assert!(zwo.contains("<Warmup Duration=\"600\" PowerLow=\"0.500\" PowerHigh=\"1.000\"/>"));
+        assert!(zwo.contains("<Ramp Duration=\"120\" PowerLow=\"0.750\" PowerHigh=\"1.250\"/>"));
+        assert!(zwo.contains("<Cooldown Duration=\"360\" PowerLow=\"0.750\" PowerHigh=\"0.500\"/>"));
+        assert!(zwo.contains("<name>Over &amp; &lt;Under&gt;</name>"));
+    }
+    #[test]
+    fn named_effort() {
+        assert_eq!(
+            effort_to_zwo(&Effort::new(1.0, 300.0, None).with_name("VO2 <#3>"), 200.0),
+            "        <SteadyState Duration=\"60\" Power=\"1.500\" Name=\"VO2 &lt;#3&gt;\"/>\n"
+        );
+    }
+    #[test]
+    fn round_trip() {
+        let workout = Workout::new(
+            "Over & Under",
+            "Threshold <intervals>",
+            vec![
+                Effort::new(10.0, 100.0, Some(150.0)).with_section(Section::Warmup),
+                Effort::new(5.0, 210.0, None)
+                    .with_cadence(95)
+                    .with_cue("Stay \"seated\""),
+                Effort::new(2.0, 150.0, Some(250.0)),
+                Effort::new(1.0, 300.0, None).with_name("VO2 <#3>"),
+                Effort::new(5.0, 150.0, Some(100.0)).with_section(Section::Cooldown),
+            ],
+        );
+        assert_eq!(
+            extract_workout(&workout_to_zwo(&workout, 200.0), 200.0),
+            Ok(workout)
+        );
+    }
+    #[test]
+    fn intervals_are_expanded() {
+        let zwo = r#"<workout_file><name>VO2</name><workout>
+            <IntervalsT Repeat="2" OnDuration="60" OffDuration="30" OnPower="1.2" OffPower="0.5" Cadence="100" CadenceResting="85"/>
+        </workout></workout_file>"#;
+        let workout = extract_workout(zwo, 250.0).unwrap();
+        assert_eq!(
+            workout.efforts(),
+            &[
+                Effort::new(1.0, 300.0, None).with_cadence(100),
+                Effort::new(0.5, 125.0, None).with_cadence(85),
+                Effort::new(1.0, 300.0, None).with_cadence(100),
+                Effort::new(0.5, 125.0, None).with_cadence(85),
+            ]
+        );
+        assert_eq!(workout.name(), "VO2");
+    }
+    #[test]
+    fn free_ride_is_not_supported() {
+        assert_eq!(
+            extract_workout(
+                "<workout_file><workout><FreeRide Duration=\"600\"/></workout></workout_file>",
+                200.0
+            ),
+            Err(ExtractZwoError::UnsupportedElement(String::from(
+                "FreeRide"
+            )))
+        );
+    }
+    #[test]
+    fn missing_attribute() {
+        assert_eq!(
+            extract_workout(
+                "<workout_file><workout><SteadyState Duration=\"600\"/></workout></workout_file>",
+                200.0
+            ),
+            Err(ExtractZwoError::MissingAttribute(String::from("Power")))
+        );
+    }
+    #[test]
+    fn no_workout() {
+        assert_eq!(
+            extract_workout("<plan></plan>", 200.0),
+            Err(ExtractZwoError::NoWorkout)
+        );
+    }
+}